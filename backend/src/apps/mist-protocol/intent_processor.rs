@@ -336,9 +336,10 @@ async fn decrypt_swap_details(
 
     info!("  Encrypted details length: {} chars", encrypted_str.len());
 
-    // Try plain JSON first (for testing without SEAL)
+    // trusted-input builds (dev and tests only) skip SEAL for plain JSON
+    #[cfg(feature = "trusted-input")]
     if let Ok(details) = serde_json::from_str::<DecryptedSwapDetails>(&encrypted_str) {
-        info!("  Parsed as plain JSON (test mode)");
+        tracing::warn!("  INSECURE: trusted-input build, intent details taken as plaintext without SEAL");
         return Ok(details);
     }
 
@@ -437,74 +438,57 @@ async fn decrypt_swap_details(
         .timeout(Duration::from_secs(10))
         .build()?;
 
-    let mut responses: Vec<(ObjectId, FetchKeyResponse)> = Vec::new();
+    // Use to_json_string for proper signature serialization
+    let request_body = fetch_request.to_json_string()
+        .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
 
-    for server_id in &SEAL_CONFIG.key_servers {
-        let server_url = if server_id.to_string() == "0x73d05d62c18d9374e3ea529e8e0ed6161da1a141a94d3f76ae3fe4e99356db75" {
-            "https://seal-key-server-testnet-1.mystenlabs.com"
-        } else {
-            "https://seal-key-server-testnet-2.mystenlabs.com"
-        };
-
-        let url = format!("{}/v1/fetch_key", server_url);
-        info!("  Calling SEAL server: {}", server_url);
+    // Query every server at once; stop as soon as the threshold is met
+    let threshold = encrypted_obj.threshold as usize;
+    let requests = SEAL_CONFIG
+        .key_servers
+        .iter()
+        .map(|server_id| {
+            let server_url = if server_id.to_string() == "0x73d05d62c18d9374e3ea529e8e0ed6161da1a141a94d3f76ae3fe4e99356db75" {
+                "https://seal-key-server-testnet-1.mystenlabs.com"
+            } else {
+                "https://seal-key-server-testnet-2.mystenlabs.com"
+            };
+            let request = fetch_key_from_server(client.clone(), server_url, request_body.clone());
+            (*server_id, request)
+        })
+        .collect();
 
-        // Use to_json_string for proper signature serialization
-        let request_body = fetch_request.to_json_string()
-            .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
+    let responses: Vec<(ObjectId, FetchKeyResponse)> =
+        super::seal_fetch::collect_threshold(requests, threshold).await;
 
-        match client.post(&url)
-            .header("Client-Sdk-Version", "0.5.11")
-            .header("Content-Type", "application/json")
-            .body(request_body.clone())
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let status = response.status();
-                if status.is_success() {
-                    match response.json::<FetchKeyResponse>().await {
-                        Ok(fetch_response) => {
-                            info!("  Got key from {}", server_url);
-                            responses.push((*server_id, fetch_response));
-                        }
-                        Err(e) => {
-                            error!("  Failed to parse response: {}", e);
-                        }
-                    }
-                } else {
-                    let error_body = response.text().await.unwrap_or_default();
-                    error!("  Server error {}: {}", status, error_body);
-                }
-            }
-            Err(e) => {
-                error!("  Connection failed: {}", e);
-            }
-        }
-    }
-
-    if responses.is_empty() {
-        return Err(anyhow::anyhow!("Failed to fetch keys from any SEAL server"));
+    if responses.len() < threshold {
+        return Err(anyhow::anyhow!(
+            "Got {} of {} required SEAL key responses",
+            responses.len(),
+            threshold
+        ));
     }
 
     info!("  Got {} key responses", responses.len());
 
     // Decrypt
+    let objects = [encrypted_obj];
     let decrypted_results = seal_decrypt_all_objects(
         _enc_secret,
         &responses,
-        &[encrypted_obj],
+        &objects,
         &SEAL_CONFIG.server_pk_map,
     )
     .map_err(|e| anyhow::anyhow!("SEAL decryption failed: {}", e))?;
 
-    if decrypted_results.is_empty() {
+    // One plaintext per object, or none are used
+    let decrypted = super::seal_fetch::pair_decrypted(&objects, decrypted_results)?;
+    let Some((_, decrypted_bytes)) = decrypted.into_iter().next() else {
         return Err(anyhow::anyhow!("No data decrypted"));
-    }
+    };
 
     // Parse decrypted JSON
-    let decrypted_bytes = &decrypted_results[0];
-    let details: DecryptedSwapDetails = serde_json::from_slice(decrypted_bytes)
+    let details: DecryptedSwapDetails = serde_json::from_slice(&decrypted_bytes)
         .map_err(|e| anyhow::anyhow!("Failed to parse decrypted details: {}", e))?;
 
     info!("  Successfully decrypted swap details");
@@ -512,6 +496,38 @@ async fn decrypt_swap_details(
     Ok(details)
 }
 
+/// POST a fetch_key request to one SEAL key server
+#[cfg(feature = "mist-protocol")]
+async fn fetch_key_from_server(
+    client: reqwest::Client,
+    server_url: &'static str,
+    request_body: String,
+) -> Result<seal_sdk::types::FetchKeyResponse> {
+    let url = format!("{}/v1/fetch_key", server_url);
+    info!("  Calling SEAL server: {}", server_url);
+
+    let response = client.post(&url)
+        .header("Client-Sdk-Version", "0.5.11")
+        .header("Content-Type", "application/json")
+        .body(request_body)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Connection to {} failed: {}", server_url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!("Server error {}: {}", status, error_body));
+    }
+
+    let fetch_response = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response from {}: {}", server_url, e))?;
+    info!("  Got key from {}", server_url);
+    Ok(fetch_response)
+}
+
 #[cfg(not(feature = "mist-protocol"))]
 async fn decrypt_swap_details(
     _encrypted_bytes: &[u8],
@@ -678,4 +694,19 @@ mod tests {
         assert_eq!(details.input_amount, "1000");
         assert_eq!(details.signature, "AAAA");
     }
+
+    #[cfg(not(feature = "trusted-input"))]
+    #[tokio::test]
+    async fn test_plaintext_details_need_trusted_input() {
+        use fastcrypto::traits::KeyPair;
+
+        let state = AppState {
+            eph_kp: fastcrypto::ed25519::Ed25519KeyPair::generate(&mut rand::thread_rng()),
+            api_key: String::new(),
+        };
+        let plaintext = br#"{"nullifier":"0x1234","inputAmount":"1000","outputStealth":"0xabc","remainderStealth":"0xdef","signature":"AAAA"}"#;
+
+        let err = decrypt_swap_details(plaintext, &state).await.unwrap_err();
+        assert!(err.to_string().starts_with("Failed to decode base64"), "{}", err);
+    }
 }
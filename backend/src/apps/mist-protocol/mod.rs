@@ -10,6 +10,9 @@
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(all(feature = "trusted-input", not(debug_assertions)))]
+compile_error!("the trusted-input feature bypasses SEAL and must not be enabled in release builds");
+
 // Intent processor for polling and processing swap intents
 #[cfg(feature = "mist-protocol")]
 pub mod intent_processor;
@@ -22,6 +25,10 @@ pub mod swap_executor;
 #[cfg(feature = "mist-protocol")]
 pub mod seal_types;
 
+// Concurrent SEAL key fetching with early exit at threshold
+#[cfg(feature = "mist-protocol")]
+pub mod seal_fetch;
+
 // ============ DATA STRUCTURES ============
 
 /// Decrypted deposit data (from SEAL encrypted blob on Deposit object)
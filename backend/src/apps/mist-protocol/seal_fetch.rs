@@ -0,0 +1,125 @@
+//! Concurrent SEAL key fetching
+//!
+//! Key servers are queried in parallel and collection stops as soon as
+//! `threshold` servers have answered, so a slow or dead server doesn't add
+//! its full timeout to every decryption. Outstanding requests are cancelled.
+
+use anyhow::Result;
+use std::fmt::Display;
+use std::future::Future;
+use tokio::task::JoinSet;
+use tracing::{error, warn};
+
+/// Run every request concurrently and return the first `threshold` successes.
+///
+/// Returns fewer than `threshold` results only when too many requests failed;
+/// the caller decides whether that is fatal.
+pub async fn collect_threshold<K, T, Fut>(requests: Vec<(K, Fut)>, threshold: usize) -> Vec<(K, T)>
+where
+    K: Display + Send + 'static,
+    T: Send + 'static,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let mut pending = JoinSet::new();
+    for (key, request) in requests {
+        pending.spawn(async move { (key, request.await) });
+    }
+
+    let mut responses = Vec::new();
+    while let Some(joined) = pending.join_next().await {
+        match joined {
+            Ok((key, Ok(response))) => {
+                responses.push((key, response));
+                if responses.len() >= threshold {
+                    break;
+                }
+            }
+            Ok((key, Err(e))) => warn!("  Key server {} failed: {:#}", key, e),
+            Err(e) => error!("  Key server task failed: {}", e),
+        }
+    }
+
+    // Cancel the stragglers
+    pending.abort_all();
+    responses
+}
+
+/// Pair each encrypted object with its plaintext. `seal_decrypt_all_objects`
+/// returns one plaintext per object, in input order; a different count fails
+/// instead of attributing a plaintext to the wrong object.
+pub fn pair_decrypted<T>(objects: &[T], decrypted: Vec<Vec<u8>>) -> Result<Vec<(&T, Vec<u8>)>> {
+    if decrypted.len() != objects.len() {
+        anyhow::bail!(
+            "SEAL decrypted {} plaintext(s) for {} encrypted object(s)",
+            decrypted.len(),
+            objects.len()
+        );
+    }
+    Ok(objects.iter().zip(decrypted).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+    use std::time::Duration;
+    use tokio::time::{sleep, Instant};
+
+    type MockRequest = Pin<Box<dyn Future<Output = Result<u32>> + Send>>;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_returns_once_threshold_met() {
+        let started = Instant::now();
+        let requests: Vec<(&str, MockRequest)> = vec![
+            ("slow", Box::pin(async {
+                sleep(Duration::from_secs(3600)).await;
+                Ok(2)
+            })),
+            ("fast", Box::pin(async {
+                sleep(Duration::from_millis(50)).await;
+                Ok(1)
+            })),
+        ];
+
+        let responses = collect_threshold(requests, 1).await;
+        assert_eq!(responses, vec![("fast", 1)]);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failures_do_not_count() {
+        let requests: Vec<(&str, MockRequest)> = vec![
+            ("broken", Box::pin(async { Err(anyhow::anyhow!("503")) })),
+            ("a", Box::pin(async {
+                sleep(Duration::from_millis(10)).await;
+                Ok(1)
+            })),
+            ("b", Box::pin(async {
+                sleep(Duration::from_millis(20)).await;
+                Ok(2)
+            })),
+        ];
+
+        let mut responses = collect_threshold(requests, 2).await;
+        responses.sort();
+        assert_eq!(responses, vec![("a", 1), ("b", 2)]);
+
+        // Not enough healthy servers: returns what it got
+        let requests: Vec<(&str, MockRequest)> = vec![
+            ("broken", Box::pin(async { Err(anyhow::anyhow!("503")) })),
+            ("a", Box::pin(async { Ok(1) })),
+        ];
+        assert_eq!(collect_threshold(requests, 2).await, vec![("a", 1)]);
+    }
+
+    #[test]
+    fn test_decrypted_count_mismatch() {
+        let objects = ["intent-a", "intent-b", "intent-c"];
+        let pairs = pair_decrypted(&objects, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]).unwrap();
+        assert_eq!(pairs[2], (&"intent-c", b"c".to_vec()));
+
+        // intent-b failed to decrypt
+        let err = pair_decrypted(&objects, vec![b"a".to_vec(), b"c".to_vec()]).unwrap_err();
+        assert_eq!(err.to_string(), "SEAL decrypted 2 plaintext(s) for 3 encrypted object(s)");
+    }
+}
@@ -35,6 +35,12 @@ async fn main() -> Result<()> {
         println!("🔑 This address is hardcoded in contract for authorization\n");
     }
 
+    #[cfg(not(feature = "mist-protocol"))]
+    println!(
+        "⚠️  Built without an app feature ({}); no intents will be processed\n",
+        APP_FEATURES.join(", ")
+    );
+
     // For mist-protocol, we don't need API_KEY
     let api_key = String::new();
     let state = Arc::new(AppState { eph_kp: backend_kp, api_key });
@@ -69,10 +75,27 @@ async fn main() -> Result<()> {
         .map_err(|e| anyhow::anyhow!("Server error: {}", e))
 }
 
+/// Cargo features that each enable an app, listed by the base build's ping
+#[cfg(not(feature = "mist-protocol"))]
+const APP_FEATURES: &[&str] = &["mist-protocol"];
+
+#[cfg(feature = "mist-protocol")]
 async fn ping() -> &'static str {
     "Pong!"
 }
 
+/// Without an app feature only attestation and health checks are served;
+/// say so instead of answering like a working backend.
+#[cfg(not(feature = "mist-protocol"))]
+async fn ping() -> axum::Json<serde_json::Value> {
+    axum::Json(serde_json::json!({
+        "status": "no_app",
+        "message": "No app is active: this binary was built without an app feature. \
+                    Only /get_attestation and /health_check are served.",
+        "enable_features": APP_FEATURES,
+    }))
+}
+
 /// Load backend keypair from environment variable
 ///
 /// Expects BACKEND_PRIVATE_KEY in Bech32 format (suiprivkey1...)
@@ -116,3 +139,23 @@ fn load_backend_keypair() -> Result<Ed25519KeyPair> {
 
     Ok(keypair)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "mist-protocol"))]
+    #[tokio::test]
+    async fn test_base_build_ping_explains_missing_app() {
+        let axum::Json(body) = ping().await;
+        assert_eq!(body["status"], "no_app");
+        assert!(body["message"].as_str().unwrap().contains("No app is active"));
+        assert_eq!(body["enable_features"], serde_json::json!(["mist-protocol"]));
+    }
+
+    #[cfg(feature = "mist-protocol")]
+    #[tokio::test]
+    async fn test_app_build_ping() {
+        assert_eq!(ping().await, "Pong!");
+    }
+}
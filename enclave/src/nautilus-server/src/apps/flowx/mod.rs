@@ -3,8 +3,13 @@
 //! Provides swap transaction building for FlowX CLMM.
 
 pub mod config;
+pub mod pool_selection;
 pub mod transaction;
 pub mod utils;
 
 pub use config::Config as FlowXConfig;
-pub use transaction::build_swap_transaction;
+pub use pool_selection::{select_pool, PoolCandidate, PoolSelectionStrategy};
+pub use transaction::{
+    add_owned_inputs, build_swap_transaction, fetch_owned_object_ref, fetch_pair_pools,
+    select_pool_for_pair, PairPools, SelectedPool,
+};
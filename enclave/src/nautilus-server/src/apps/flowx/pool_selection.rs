@@ -0,0 +1,385 @@
+//! Pool selection across FlowX fee tiers
+//!
+//! A token pair can have several pools (one per fee tier). Every candidate is
+//! quoted with the CLMM math and the winner is picked by the configured strategy.
+//!
+//! FlowX stores each pool as `Pool<X, Y>` with X and Y ordered by type name,
+//! so the swap direction is derived from the pool's type parameters rather
+//! than assumed.
+
+use anyhow::Result;
+use std::str::FromStr;
+
+use super::utils::math::{self, SwapEstimate};
+
+/// Environment variable selecting the pool selection strategy
+pub const POOL_SELECTION_ENV: &str = "MIST_POOL_SELECTION";
+
+/// How to choose between multiple pools for the same pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolSelectionStrategy {
+    /// Highest expected output after fees
+    #[default]
+    BestOutput,
+    /// Smallest price movement
+    LowestImpact,
+    /// Cheapest fee tier
+    LowestFee,
+}
+
+impl FromStr for PoolSelectionStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "bestoutput" => Ok(Self::BestOutput),
+            "lowestimpact" => Ok(Self::LowestImpact),
+            "lowestfee" => Ok(Self::LowestFee),
+            _ => Err(anyhow::anyhow!("Unknown pool selection strategy: {}", s)),
+        }
+    }
+}
+
+impl PoolSelectionStrategy {
+    /// Read the strategy from `MIST_POOL_SELECTION`, defaulting to `BestOutput`
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(POOL_SELECTION_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+/// On-chain state of a candidate pool
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolCandidate {
+    /// Pool object ID
+    pub pool_id: String,
+    /// Fee rate (e.g., 3000 for 0.3%)
+    pub fee_rate: u64,
+    /// Current sqrt price (Q64.64)
+    pub sqrt_price: u128,
+    /// Active liquidity
+    pub liquidity: u128,
+}
+
+impl PoolCandidate {
+    /// Build a candidate from the Move object fields of a FlowX pool.
+    /// Sui JSON-RPC renders u64/u128 as strings, so both forms are accepted.
+    pub fn from_fields(pool_id: &str, fields: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            pool_id: pool_id.to_string(),
+            fee_rate: u64::try_from(parse_number(fields.get("swap_fee_rate")?)?).ok()?,
+            sqrt_price: parse_number(fields.get("sqrt_price")?)?,
+            liquidity: parse_number(fields.get("liquidity")?)?,
+        })
+    }
+}
+
+fn parse_number(value: &serde_json::Value) -> Option<u128> {
+    match value {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.as_u64().map(u128::from),
+        _ => None,
+    }
+}
+
+/// Normalize a coin type to long-form address (`0x2::sui::SUI` →
+/// `0x000…002::sui::SUI`) so short and long forms compare equal
+pub fn normalize_coin_type(coin_type: &str) -> String {
+    let coin_type = coin_type.trim();
+    match coin_type.split_once("::") {
+        Some((address, rest)) => {
+            let hex = address
+                .strip_prefix("0x")
+                .unwrap_or(address)
+                .to_lowercase();
+            format!("0x{:0>64}::{}", hex, rest)
+        }
+        None => coin_type.to_string(),
+    }
+}
+
+/// Extract `(X, Y)` from a pool object type such as
+/// `0xpkg::pool::Pool<0x2::sui::SUI, 0xabc::token::TOKEN>`
+pub fn pool_coin_types(pool_type: &str) -> Option<(String, String)> {
+    let start = pool_type.find('<')?;
+    let params = pool_type[start + 1..].strip_suffix('>')?;
+
+    // Split on the top-level comma only (coin types may be generic themselves)
+    let mut depth = 0usize;
+    let mut split_at = None;
+    for (i, c) in params.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                if split_at.is_some() {
+                    return None;
+                }
+                split_at = Some(i);
+            }
+            _ => {}
+        }
+    }
+
+    let (x, y) = params.split_at(split_at?);
+    Some((x.trim().to_string(), y[1..].trim().to_string()))
+}
+
+/// Swap direction for `coin_in → coin_out` against a `Pool<X, Y>`.
+/// Returns `Some(true)` for X→Y, `Some(false)` for Y→X and `None` when the
+/// pool is for a different pair.
+pub fn swap_direction(pool_x: &str, pool_y: &str, coin_in: &str, coin_out: &str) -> Option<bool> {
+    let (x, y) = (normalize_coin_type(pool_x), normalize_coin_type(pool_y));
+    let (coin_in, coin_out) = (normalize_coin_type(coin_in), normalize_coin_type(coin_out));
+
+    if x == coin_in && y == coin_out {
+        Some(true)
+    } else if x == coin_out && y == coin_in {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Check a computed `x_for_y` for `coin_in → coin_out` against a
+/// `Pool<X, Y>`'s coin types. Errors when the pool is for another pair or
+/// the direction is reversed, instead of letting a reversed swap be built.
+pub fn verify_direction(
+    pool_x: &str,
+    pool_y: &str,
+    coin_in: &str,
+    coin_out: &str,
+    x_for_y: bool,
+) -> Result<()> {
+    match swap_direction(pool_x, pool_y, coin_in, coin_out) {
+        Some(expected) if expected == x_for_y => Ok(()),
+        Some(expected) => Err(anyhow::anyhow!(
+            "Swap direction for {} -> {} is {}, but Pool<{}, {}> needs {}",
+            coin_in,
+            coin_out,
+            direction_name(x_for_y),
+            pool_x,
+            pool_y,
+            direction_name(expected)
+        )),
+        None => Err(anyhow::anyhow!(
+            "Cannot verify swap direction for {} -> {}: pool is Pool<{}, {}>",
+            coin_in,
+            coin_out,
+            pool_x,
+            pool_y
+        )),
+    }
+}
+
+fn direction_name(x_for_y: bool) -> &'static str {
+    if x_for_y {
+        "X->Y"
+    } else {
+        "Y->X"
+    }
+}
+
+/// Swap direction implied by FlowX's type-name ordering, for when no pool
+/// object is available to read the type parameters from.
+/// Errors if both sides are the same coin.
+pub fn ordered_direction(coin_in: &str, coin_out: &str) -> Result<bool> {
+    let coin_in = normalize_coin_type(coin_in);
+    let coin_out = normalize_coin_type(coin_out);
+    match coin_in.cmp(&coin_out) {
+        std::cmp::Ordering::Less => Ok(true),
+        std::cmp::Ordering::Greater => Ok(false),
+        std::cmp::Ordering::Equal => Err(anyhow::anyhow!(
+            "Cannot order pool types: both sides are {}",
+            coin_in
+        )),
+    }
+}
+
+/// A candidate pool together with its quote
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolQuote<'a> {
+    pub pool: &'a PoolCandidate,
+    pub estimate: SwapEstimate,
+}
+
+/// Quote every candidate and pick one according to `strategy`.
+/// Pools that would return nothing are ignored. Ties are broken by output.
+pub fn select_pool(
+    candidates: &[PoolCandidate],
+    amount_in: u64,
+    x_for_y: bool,
+    strategy: PoolSelectionStrategy,
+) -> Option<PoolQuote<'_>> {
+    let quotes = candidates.iter().filter_map(|pool| {
+        let estimate = math::estimate_swap_output(
+            amount_in,
+            pool.sqrt_price,
+            pool.liquidity,
+            pool.fee_rate,
+            x_for_y,
+        );
+        (estimate.amount_out > 0).then_some(PoolQuote { pool, estimate })
+    });
+
+    match strategy {
+        PoolSelectionStrategy::BestOutput => quotes.max_by_key(|q| q.estimate.amount_out),
+        PoolSelectionStrategy::LowestImpact => quotes.min_by(|a, b| {
+            a.estimate
+                .price_impact
+                .total_cmp(&b.estimate.price_impact)
+                .then(b.estimate.amount_out.cmp(&a.estimate.amount_out))
+        }),
+        PoolSelectionStrategy::LowestFee => quotes.min_by(|a, b| {
+            a.pool
+                .fee_rate
+                .cmp(&b.pool.fee_rate)
+                .then(b.estimate.amount_out.cmp(&a.estimate.amount_out))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(id: &str, fee_rate: u64, liquidity: u128) -> PoolCandidate {
+        PoolCandidate {
+            pool_id: id.to_string(),
+            fee_rate,
+            sqrt_price: math::calculate_sqrt_price(1.0, 9, 9),
+            liquidity,
+        }
+    }
+
+    #[test]
+    fn test_strategy_picks_expected_pool() {
+        // Cheap but shallow pool vs. pricier but deep pool
+        let pools = vec![pool("0xcheap", 500, 20_000_000), pool("0xdeep", 3000, 1_000_000_000_000)];
+        let amount = 5_000_000;
+
+        let best = select_pool(&pools, amount, true, PoolSelectionStrategy::BestOutput).unwrap();
+        assert_eq!(best.pool.pool_id, "0xdeep");
+
+        let impact = select_pool(&pools, amount, true, PoolSelectionStrategy::LowestImpact).unwrap();
+        assert_eq!(impact.pool.pool_id, "0xdeep");
+
+        let fee = select_pool(&pools, amount, true, PoolSelectionStrategy::LowestFee).unwrap();
+        assert_eq!(fee.pool.pool_id, "0xcheap");
+    }
+
+    #[test]
+    fn test_same_liquidity_different_fees() {
+        // Same depth: lower fee gives more output, higher fee moves price less
+        let pools = vec![pool("0xlow", 100, 50_000_000), pool("0xhigh", 10000, 50_000_000)];
+        let amount = 10_000_000;
+
+        let best = select_pool(&pools, amount, false, PoolSelectionStrategy::BestOutput).unwrap();
+        assert_eq!(best.pool.pool_id, "0xlow");
+
+        let impact = select_pool(&pools, amount, false, PoolSelectionStrategy::LowestImpact).unwrap();
+        assert_eq!(impact.pool.pool_id, "0xhigh");
+    }
+
+    #[test]
+    fn test_empty_pools_are_ignored() {
+        let pools = vec![pool("0xempty", 100, 0)];
+        assert!(select_pool(&pools, 1_000, true, PoolSelectionStrategy::LowestFee).is_none());
+    }
+
+    #[test]
+    fn test_strategy_parsing() {
+        assert_eq!("best_output".parse::<PoolSelectionStrategy>().unwrap(), PoolSelectionStrategy::BestOutput);
+        assert_eq!("LowestImpact".parse::<PoolSelectionStrategy>().unwrap(), PoolSelectionStrategy::LowestImpact);
+        assert_eq!("lowest-fee".parse::<PoolSelectionStrategy>().unwrap(), PoolSelectionStrategy::LowestFee);
+        assert!("cheapest".parse::<PoolSelectionStrategy>().is_err());
+    }
+
+    const SUI: &str = "0x2::sui::SUI";
+    const TOKEN: &str = "0xabc::mist_token::MIST_TOKEN";
+    const EARLY: &str = "0x1::early::EARLY";
+
+    #[test]
+    fn test_normalize_coin_type() {
+        assert_eq!(
+            normalize_coin_type(SUI),
+            format!("0x{}2::sui::SUI", "0".repeat(63))
+        );
+        assert_eq!(normalize_coin_type(SUI), normalize_coin_type(&normalize_coin_type(SUI)));
+        assert_eq!(normalize_coin_type("0xABC::m::T"), normalize_coin_type("0xabc::m::T"));
+    }
+
+    #[test]
+    fn test_pool_coin_types() {
+        let pool_type = format!("0xf1::pool::Pool<{}, {}>", SUI, TOKEN);
+        assert_eq!(
+            pool_coin_types(&pool_type),
+            Some((SUI.to_string(), TOKEN.to_string()))
+        );
+
+        let generic = "0xf1::pool::Pool<0x1::a::Wrapped<0x2::sui::SUI>, 0x3::b::B>";
+        assert_eq!(
+            pool_coin_types(generic),
+            Some(("0x1::a::Wrapped<0x2::sui::SUI>".to_string(), "0x3::b::B".to_string()))
+        );
+
+        assert_eq!(pool_coin_types("0xf1::pool::Registry"), None);
+        assert_eq!(pool_coin_types("0xf1::pool::Pool<0x1::a::A>"), None);
+    }
+
+    #[test]
+    fn test_swap_direction_normal_and_reversed() {
+        // SUI is X: selling SUI is X→Y
+        assert_eq!(swap_direction(SUI, TOKEN, SUI, TOKEN), Some(true));
+        // Token sorts before SUI, so the pool is Pool<EARLY, SUI>: selling SUI is Y→X
+        assert_eq!(swap_direction(EARLY, SUI, SUI, EARLY), Some(false));
+        // Long-form addresses from RPC still match
+        assert_eq!(
+            swap_direction(&normalize_coin_type(SUI), TOKEN, SUI, TOKEN),
+            Some(true)
+        );
+        // Pool for another pair
+        assert_eq!(swap_direction(SUI, EARLY, SUI, TOKEN), None);
+    }
+
+    #[test]
+    fn test_ordered_direction() {
+        assert!(ordered_direction(SUI, TOKEN).unwrap());
+        assert!(!ordered_direction(SUI, EARLY).unwrap());
+        assert!(ordered_direction(SUI, SUI).is_err());
+    }
+
+    #[test]
+    fn test_verify_direction_normal_and_reversed() {
+        // Pool<SUI, TOKEN>: selling SUI is X→Y, buying it back is Y→X
+        assert!(verify_direction(SUI, TOKEN, SUI, TOKEN, true).is_ok());
+        assert!(verify_direction(SUI, TOKEN, TOKEN, SUI, false).is_ok());
+        // Pool<EARLY, SUI>: selling SUI is Y→X
+        assert!(verify_direction(EARLY, SUI, SUI, EARLY, false).is_ok());
+
+        // A reversed direction is refused, not built
+        let err = verify_direction(EARLY, SUI, SUI, EARLY, true).unwrap_err();
+        assert!(err.to_string().contains("is X->Y, but Pool<"), "{}", err);
+        assert!(err.to_string().ends_with("needs Y->X"), "{}", err);
+        assert!(verify_direction(SUI, TOKEN, TOKEN, SUI, true).is_err());
+
+        // So is a pool for another pair
+        let err = verify_direction(SUI, EARLY, SUI, TOKEN, true).unwrap_err();
+        assert!(err.to_string().starts_with("Cannot verify swap direction"), "{}", err);
+    }
+
+    #[test]
+    fn test_candidate_from_fields() {
+        let fields = serde_json::json!({
+            "swap_fee_rate": "3000",
+            "sqrt_price": "18446744073709551616",
+            "liquidity": "123456789",
+        });
+        let candidate = PoolCandidate::from_fields("0xpool", &fields).unwrap();
+        assert_eq!(candidate.fee_rate, 3000);
+        assert_eq!(candidate.sqrt_price, 1u128 << 64);
+        assert_eq!(candidate.liquidity, 123456789);
+    }
+}
@@ -5,21 +5,164 @@
 use anyhow::Result;
 use sui_sdk::{
     types::{
-        base_types::{ObjectID, SuiAddress, SequenceNumber, ObjectDigest},
+        base_types::{ObjectID, ObjectRef, SuiAddress, SequenceNumber, ObjectDigest},
         object::Owner,
         programmable_transaction_builder::ProgrammableTransactionBuilder,
         transaction::{Command, ProgrammableMoveCall, TransactionData, ObjectArg, SharedObjectMutability, Argument},
         TypeTag, Identifier,
     },
-    rpc_types::SuiObjectDataOptions,
+    rpc_types::{SuiObjectDataOptions, SuiParsedData},
     SuiClient,
 };
 use std::str::FromStr;
-use tracing::info;
+use tracing::{info, warn};
 
 use super::config::Config;
+use super::pool_selection::{self, PoolCandidate, PoolSelectionStrategy};
 use super::utils::math;
 
+/// Pool chosen for a swap
+#[derive(Debug, Clone)]
+pub struct SelectedPool {
+    /// Pool object ID
+    pub pool_id: String,
+    /// Fee tier passed to swap_router
+    pub fee_rate: u64,
+    /// Swap direction derived from the pool's `Pool<X, Y>` type parameters
+    pub x_for_y: bool,
+    /// Quote for the requested input amount
+    pub estimate: math::SwapEstimate,
+}
+
+impl SelectedPool {
+    /// sqrt_price limit that lets the swap run as far as needed in its direction
+    pub fn sqrt_price_limit(&self) -> u128 {
+        if self.x_for_y {
+            // Price must decrease, so set minimum limit
+            math::MIN_SQRT_PRICE + 1
+        } else {
+            // Price must increase, so set maximum limit
+            math::MAX_SQRT_PRICE - 1
+        }
+    }
+}
+
+/// On-chain state of the candidate pools for one pair, ready to be quoted
+#[derive(Debug, Clone)]
+pub struct PairPools {
+    pub candidates: Vec<PoolCandidate>,
+    /// Swap direction shared by every candidate
+    pub x_for_y: bool,
+}
+
+impl PairPools {
+    /// Quote every candidate for `amount_in` and pick one with `strategy`
+    pub fn select(&self, amount_in: u64, strategy: PoolSelectionStrategy) -> Result<SelectedPool> {
+        let quote = pool_selection::select_pool(&self.candidates, amount_in, self.x_for_y, strategy)
+            .ok_or_else(|| anyhow::anyhow!("No FlowX pool can fill this swap"))?;
+
+        info!(
+            "Selected FlowX pool {} (fee {}, strategy {:?}, est. output {}, impact {:.4})",
+            quote.pool.pool_id,
+            quote.pool.fee_rate,
+            strategy,
+            quote.estimate.amount_out,
+            quote.estimate.price_impact
+        );
+
+        Ok(SelectedPool {
+            pool_id: quote.pool.pool_id.clone(),
+            fee_rate: quote.pool.fee_rate,
+            x_for_y: self.x_for_y,
+            estimate: quote.estimate,
+        })
+    }
+}
+
+/// Quote every candidate pool for `coin_in → coin_out` and pick one with `strategy`
+pub async fn select_pool_for_pair(
+    client: &SuiClient,
+    pool_ids: &[String],
+    coin_in: &str,
+    coin_out: &str,
+    amount_in: u64,
+    strategy: PoolSelectionStrategy,
+) -> Result<SelectedPool> {
+    fetch_pair_pools(client, pool_ids, coin_in, coin_out)
+        .await?
+        .select(amount_in, strategy)
+}
+
+/// Read the candidate pools for `coin_in → coin_out` from chain.
+///
+/// Pools whose `Pool<X, Y>` type is not this pair are dropped, and the swap
+/// direction comes from the surviving pools' type parameters. Needs no
+/// amount, so it can run before the intent is decrypted.
+pub async fn fetch_pair_pools(
+    client: &SuiClient,
+    pool_ids: &[String],
+    coin_in: &str,
+    coin_out: &str,
+) -> Result<PairPools> {
+    let mut candidates: Vec<PoolCandidate> = Vec::new();
+    let mut direction: Option<bool> = None;
+
+    for pool_id_str in pool_ids {
+        let pool_id = ObjectID::from_str(pool_id_str)?;
+        let data = match client
+            .read_api()
+            .get_object_with_options(pool_id, SuiObjectDataOptions::new().with_type().with_content())
+            .await?
+            .data
+        {
+            Some(data) => data,
+            None => {
+                warn!("FlowX pool {} not found, skipping", pool_id_str);
+                continue;
+            }
+        };
+
+        let pool_type = data.type_.as_ref().map(|t| t.to_string()).unwrap_or_default();
+        let pool_direction = pool_selection::pool_coin_types(&pool_type)
+            .and_then(|(x, y)| pool_selection::swap_direction(&x, &y, coin_in, coin_out));
+        let pool_direction = match pool_direction {
+            Some(d) => d,
+            None => {
+                warn!(
+                    "FlowX pool {} ({}) is not a {} / {} pool, skipping",
+                    pool_id_str, pool_type, coin_in, coin_out
+                );
+                continue;
+            }
+        };
+
+        // Every pool of the same pair must agree on the X/Y ordering
+        if direction.is_some_and(|d| d != pool_direction) {
+            anyhow::bail!(
+                "Ambiguous coin ordering across FlowX pools for {} / {}",
+                coin_in,
+                coin_out
+            );
+        }
+        direction = Some(pool_direction);
+
+        let fields = data.content.as_ref().and_then(|content| match content {
+            SuiParsedData::MoveObject(obj) => serde_json::to_value(&obj.fields).ok(),
+            _ => None,
+        });
+
+        match fields.and_then(|f| PoolCandidate::from_fields(pool_id_str, &f)) {
+            Some(candidate) => candidates.push(candidate),
+            None => warn!("Could not read state of FlowX pool {}, skipping", pool_id_str),
+        }
+    }
+
+    let x_for_y = direction
+        .ok_or_else(|| anyhow::anyhow!("No FlowX pool configured for {} / {}", coin_in, coin_out))?;
+
+    Ok(PairPools { candidates, x_for_y })
+}
+
 /// Build a swap transaction using FlowX CLMM
 ///
 /// This function constructs an unsigned transaction for token swaps on FlowX.
@@ -35,7 +178,9 @@ use super::utils::math;
 /// * `min_amount_out` - Minimum acceptable output amount (slippage protection)
 /// * `is_sui_to_token` - Swap direction: true = SUI→TOKEN, false = TOKEN→SUI
 /// * `token_type` - The non-SUI token type (e.g., "0xpkg::module::TOKEN")
-/// * `fee_rate` - Pool fee rate (e.g., 3000 for 0.3%)
+/// * `pool_ids` - Candidate pools for the pair (one per fee tier)
+/// * `strategy` - How to pick between the candidate pools
+#[allow(clippy::too_many_arguments)]
 pub async fn build_swap_transaction(
     client: &SuiClient,
     config: &Config,
@@ -44,16 +189,22 @@ pub async fn build_swap_transaction(
     min_amount_out: u64,
     is_sui_to_token: bool,
     token_type: &str,
-    fee_rate: u64,
+    pool_ids: &[String],
+    strategy: PoolSelectionStrategy,
 ) -> Result<TransactionData> {
     let sender = SuiAddress::from_str(sender_address)?;
     let mut ptb = ProgrammableTransactionBuilder::new();
 
-    // Type arguments - MUST be in pool order (alphabetical by type)
     let sui_type = config.sui_type();
+    let (coin_in_type, coin_out_type) = if is_sui_to_token {
+        (sui_type.as_str(), token_type)
+    } else {
+        (token_type, sui_type.as_str())
+    };
 
-    // FlowX pools order types - typically SUI comes before custom tokens
-    let (type_x, type_y) = (&sui_type, token_type);
+    // Pick the fee tier, and the direction from the chosen pool's type parameters
+    let selected =
+        select_pool_for_pair(client, pool_ids, coin_in_type, coin_out_type, amount, strategy).await?;
 
     // Track command index
     let mut cmd_idx: u16 = 0;
@@ -67,11 +218,9 @@ pub async fn build_swap_transaction(
         let coins = get_user_coins(client, &sender, token_type, amount).await?;
         let used_ids: Vec<ObjectID> = coins.iter().map(|(id, _, _)| *id).collect();
 
-        let coin_arg = ptb.obj(ObjectArg::ImmOrOwnedObject((
-            coins[0].0,
-            coins[0].1,
-            coins[0].2,
-        )))?;
+        // Owned input: reference the coin's current version and digest
+        let coin_ref = fetch_owned_object_ref(client, coins[0].0).await?;
+        let coin_arg = add_owned_inputs(&mut ptb, &[coin_ref])?.remove(0);
 
         (coin_arg, used_ids)
     };
@@ -82,16 +231,8 @@ pub async fn build_swap_transaction(
     let coin_in = Argument::Result(cmd_idx);
     cmd_idx += 1;
 
-    // Calculate price limit based on swap direction
-    let sqrt_price_limit: u128 = if is_sui_to_token {
-        // SUI → TOKEN (selling X for Y, x_for_y=true)
-        // Price must decrease, so set minimum limit
-        math::MIN_SQRT_PRICE + 1
-    } else {
-        // TOKEN → SUI (selling Y for X, x_for_y=false)
-        // Price must increase, so set maximum limit
-        math::MAX_SQRT_PRICE - 1
-    };
+    // Price limit follows the pool's actual X/Y ordering
+    let sqrt_price_limit: u128 = selected.sqrt_price_limit();
 
     // Get deadline (30 minutes from now in milliseconds)
     let deadline_ms = std::time::SystemTime::now()
@@ -140,7 +281,7 @@ pub async fn build_swap_transaction(
         mutability: SharedObjectMutability::Mutable,
     })?;
 
-    let fee_rate_arg = ptb.pure(fee_rate)?;
+    let fee_rate_arg = ptb.pure(selected.fee_rate)?;
     let min_amount_out_arg = ptb.pure(min_amount_out)?;
     let sqrt_price_limit_arg = ptb.pure(sqrt_price_limit)?;
     let deadline_arg = ptb.pure(deadline_ms)?;
@@ -162,9 +303,10 @@ pub async fn build_swap_transaction(
         package: ObjectID::from_str(&config.flowx_package_id)?,
         module: Identifier::new("swap_router")?.to_string(),
         function: Identifier::new("swap_exact_input")?.to_string(),
+        // swap_router takes <input, output>; it resolves the pool ordering itself
         type_arguments: vec![
-            TypeTag::from_str(type_x)?.into(),
-            TypeTag::from_str(type_y)?.into(),
+            TypeTag::from_str(coin_in_type)?.into(),
+            TypeTag::from_str(coin_out_type)?.into(),
         ],
         arguments: vec![
             pool_registry_arg,
@@ -264,15 +406,65 @@ async fn get_gas_coins(
             continue;
         }
 
-        let obj_data = client
-            .read_api()
-            .get_object_with_options(coin.coin_object_id, SuiObjectDataOptions::new().with_owner())
-            .await?;
-
-        if let Some(data) = obj_data.data {
-            return Ok(vec![(coin.coin_object_id, data.version, data.digest)]);
-        }
+        return Ok(vec![fetch_owned_object_ref(client, coin.coin_object_id).await?]);
     }
 
     anyhow::bail!("No SUI coins available for gas")
 }
+
+/// Fetch the current object reference (ID, version, digest) of an owned or
+/// immutable object. Coin listings can lag behind the latest version, and an
+/// owned input with a stale version or digest is rejected by validators.
+pub async fn fetch_owned_object_ref(client: &SuiClient, object_id: ObjectID) -> Result<ObjectRef> {
+    let data = client
+        .read_api()
+        .get_object_with_options(object_id, SuiObjectDataOptions::new().with_owner())
+        .await?
+        .data
+        .ok_or_else(|| anyhow::anyhow!("Object {} not found", object_id))?;
+
+    match data.owner {
+        Some(Owner::AddressOwner(_)) | Some(Owner::Immutable) => Ok(data.object_ref()),
+        other => anyhow::bail!("Object {} is not owned or immutable: {:?}", object_id, other),
+    }
+}
+
+/// Add owned/immutable objects (coins, tickets) to the PTB as `ImmOrOwnedObject`
+/// inputs, in order
+pub fn add_owned_inputs(
+    ptb: &mut ProgrammableTransactionBuilder,
+    refs: &[ObjectRef],
+) -> Result<Vec<Argument>> {
+    refs.iter()
+        .map(|obj_ref| ptb.obj(ObjectArg::ImmOrOwnedObject(*obj_ref)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::transaction::CallArg;
+
+    #[test]
+    fn test_owned_inputs_carry_version_and_digest() {
+        let refs = vec![
+            (ObjectID::random(), SequenceNumber::from_u64(7), ObjectDigest::new([1; 32])),
+            (ObjectID::random(), SequenceNumber::from_u64(42), ObjectDigest::new([2; 32])),
+        ];
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let args = add_owned_inputs(&mut ptb, &refs).unwrap();
+        assert_eq!(args.len(), 2);
+
+        let pt = ptb.finish();
+        assert_eq!(pt.inputs.len(), 2);
+        for (input, expected) in pt.inputs.iter().zip(&refs) {
+            match input {
+                CallArg::Object(ObjectArg::ImmOrOwnedObject(obj_ref)) => {
+                    assert_eq!(obj_ref, expected);
+                }
+                other => panic!("expected ImmOrOwnedObject input, got {:?}", other),
+            }
+        }
+    }
+}
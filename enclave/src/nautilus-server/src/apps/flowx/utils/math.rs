@@ -73,6 +73,130 @@ pub fn calculate_price_limit(current_sqrt_price: u128, slippage_bps: u64, is_x_t
     }
 }
 
+/// Minimum acceptable output: `estimated_out` less `slippage_bps` basis points
+pub fn min_amount_out(estimated_out: u64, slippage_bps: u64) -> u64 {
+    let factor = 10000u64.saturating_sub(slippage_bps) as u128;
+    (estimated_out as u128 * factor / 10000) as u64
+}
+
+/// Fee rate denominator used by FlowX (3000 = 0.3%)
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/// Environment variable selecting the output rounding policy
+pub const OUTPUT_ROUNDING_ENV: &str = "MIST_OUTPUT_ROUNDING";
+
+/// How a computed (fractional) output is converted to integer base units
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingPolicy {
+    /// Round down; never promises more than the pool delivers
+    #[default]
+    Floor,
+    /// Round half away from zero
+    Round,
+    /// Round up
+    Ceil,
+}
+
+impl std::str::FromStr for RoundingPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "floor" => Ok(Self::Floor),
+            "round" => Ok(Self::Round),
+            "ceil" => Ok(Self::Ceil),
+            _ => Err(anyhow::anyhow!("Unknown rounding policy: {} (floor | round | ceil)", s)),
+        }
+    }
+}
+
+impl RoundingPolicy {
+    /// Read the policy from `MIST_OUTPUT_ROUNDING`, defaulting to `Floor`
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var(OUTPUT_ROUNDING_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Convert `value` to base units. Negative and NaN values give 0, values
+    /// past `u64::MAX` saturate.
+    pub fn to_base_units(self, value: f64) -> u64 {
+        let rounded = match self {
+            Self::Floor => value.floor(),
+            Self::Round => value.round(),
+            Self::Ceil => value.ceil(),
+        };
+        // `as` saturates and maps NaN to 0
+        rounded.max(0.0) as u64
+    }
+}
+
+/// Estimated result of swapping against a single pool
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapEstimate {
+    /// Expected output amount in base units (rounded down)
+    pub amount_out: u64,
+    /// Relative price movement caused by the swap (0.01 = 1%)
+    pub price_impact: f64,
+    /// Unrounded expected output
+    pub exact_out: f64,
+}
+
+impl SwapEstimate {
+    /// Expected output converted to base units with `policy`
+    pub fn amount_out_with(&self, policy: RoundingPolicy) -> u64 {
+        policy.to_base_units(self.exact_out)
+    }
+}
+
+/// Estimate swap output within the current tick range
+///
+/// Ignores tick crossings, so it's only accurate while the trade stays inside
+/// the active liquidity range. Good enough to rank pools against each other.
+///
+/// # Arguments
+/// * `amount_in` - Input amount (before fees)
+/// * `sqrt_price` - Current pool sqrt price (Q64.64)
+/// * `liquidity` - Active pool liquidity
+/// * `fee_rate` - Pool fee rate (e.g., 3000 for 0.3%)
+/// * `x_for_y` - Swap direction: true = X→Y, false = Y→X
+pub fn estimate_swap_output(
+    amount_in: u64,
+    sqrt_price: u128,
+    liquidity: u128,
+    fee_rate: u64,
+    x_for_y: bool,
+) -> SwapEstimate {
+    if liquidity == 0 || sqrt_price == 0 || fee_rate >= FEE_RATE_DENOMINATOR {
+        return SwapEstimate { amount_out: 0, price_impact: 1.0, exact_out: 0.0 };
+    }
+
+    let amount_in_net =
+        amount_in as f64 * (FEE_RATE_DENOMINATOR - fee_rate) as f64 / FEE_RATE_DENOMINATOR as f64;
+    let l = liquidity as f64;
+    let p = sqrt_price as f64 / Q64 as f64;
+
+    let (new_p, amount_out) = if x_for_y {
+        // sqrt_price moves down: P' = L * P / (L + dx * P), dy = L * (P - P')
+        let new_p = l * p / (l + amount_in_net * p);
+        (new_p, l * (p - new_p))
+    } else {
+        // sqrt_price moves up: P' = P + dy / L, dx = L * (1/P - 1/P')
+        let new_p = p + amount_in_net / l;
+        (new_p, l * (1.0 / p - 1.0 / new_p))
+    };
+
+    let price_ratio = (new_p / p) * (new_p / p);
+
+    let exact_out = amount_out.max(0.0);
+    SwapEstimate {
+        amount_out: RoundingPolicy::Floor.to_base_units(exact_out),
+        price_impact: (1.0 - price_ratio).abs(),
+        exact_out,
+    }
+}
+
 /// Min/Max sqrt price constants (from FlowX)
 pub const MIN_SQRT_PRICE: u128 = 4295048016;
 pub const MAX_SQRT_PRICE: u128 = 79226673515401279992447579055;
@@ -98,4 +222,53 @@ mod tests {
         // Price = 1 should give tick ≈ 0
         assert!(tick.abs() < 10);
     }
+
+    #[test]
+    fn test_estimate_swap_output() {
+        let sqrt_price = calculate_sqrt_price(1.0, 9, 9);
+
+        // Deep pool, small trade: output ≈ input minus 0.3% fee
+        let deep = estimate_swap_output(1_000_000, sqrt_price, 1_000_000_000_000_000, 3000, true);
+        assert!(deep.amount_out > 996_000 && deep.amount_out <= 997_000);
+        assert!(deep.price_impact < 0.0001);
+
+        // Shallow pool, same trade: noticeably worse output and higher impact
+        let shallow = estimate_swap_output(1_000_000, sqrt_price, 10_000_000, 3000, true);
+        assert!(shallow.amount_out < deep.amount_out);
+        assert!(shallow.price_impact > deep.price_impact);
+
+        // Empty pool
+        assert_eq!(estimate_swap_output(1_000, sqrt_price, 0, 3000, false).amount_out, 0);
+    }
+
+    #[test]
+    fn test_rounding_policies() {
+        assert_eq!(RoundingPolicy::Floor.to_base_units(1234.5), 1234);
+        assert_eq!(RoundingPolicy::Round.to_base_units(1234.5), 1235);
+        assert_eq!(RoundingPolicy::Round.to_base_units(1234.49), 1234);
+        assert_eq!(RoundingPolicy::Ceil.to_base_units(1234.01), 1235);
+
+        // Whole values are unchanged by every policy
+        for policy in [RoundingPolicy::Floor, RoundingPolicy::Round, RoundingPolicy::Ceil] {
+            assert_eq!(policy.to_base_units(1000.0), 1000);
+            assert_eq!(policy.to_base_units(-3.2), 0);
+            assert_eq!(policy.to_base_units(f64::NAN), 0);
+        }
+
+        let estimate = SwapEstimate { amount_out: 996, price_impact: 0.0, exact_out: 996.7 };
+        assert_eq!(estimate.amount_out_with(RoundingPolicy::default()), 996);
+        assert_eq!(estimate.amount_out_with(RoundingPolicy::Round), 997);
+        assert_eq!(estimate.amount_out_with(RoundingPolicy::Ceil), 997);
+
+        assert_eq!("Ceil".parse::<RoundingPolicy>().unwrap(), RoundingPolicy::Ceil);
+        assert!("truncate".parse::<RoundingPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_min_amount_out() {
+        assert_eq!(min_amount_out(1_000_000, 50), 995_000);
+        assert_eq!(min_amount_out(1_000_000, 0), 1_000_000);
+        assert_eq!(min_amount_out(1_000_000, 10_000), 0);
+        assert_eq!(min_amount_out(u64::MAX, 1), (u64::MAX as u128 * 9999 / 10000) as u64);
+    }
 }
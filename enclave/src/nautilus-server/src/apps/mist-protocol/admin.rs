@@ -0,0 +1,310 @@
+//! Admin endpoints for operating the intent processor at runtime
+//!
+//! All routes require `Authorization: Bearer <MIST_ADMIN_TOKEN>`.
+//! If `MIST_ADMIN_TOKEN` is not set, admin endpoints are disabled.
+
+use super::diagnostics::IntentReport;
+use super::recent_failures::FailureRecord;
+use super::{SwapIntentObject, LOG_PRIVACY};
+use crate::{AppState, EnclaveError};
+use axum::extract::{Path, State};
+use axum::http::{header::AUTHORIZATION, HeaderMap};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Environment variable holding the bearer token for admin endpoints
+pub const ADMIN_TOKEN_ENV: &str = "MIST_ADMIN_TOKEN";
+
+/// Response for processor control endpoints
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessorStatusResponse {
+    /// Whether intent execution is currently paused
+    pub paused: bool,
+}
+
+/// On-chain fields of a SwapIntent; the encrypted details only by size
+#[derive(Debug, Serialize)]
+pub struct OnChainIntent {
+    pub token_in: String,
+    pub token_out: String,
+    pub deadline: u64,
+    pub encrypted_details_len: usize,
+}
+
+/// Everything known about one intent, for incident response
+#[derive(Debug, Serialize)]
+pub struct IntentDiagnosticsResponse {
+    pub intent_id: String,
+    /// `None` when the intent is gone (consumed) or couldn't be read
+    pub on_chain: Option<OnChainIntent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_chain_error: Option<String>,
+    /// `None` when this server hasn't attempted the intent since it started
+    pub processor: Option<IntentReport>,
+}
+
+/// Admin routes, merged into the main router by the server binary
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/admin/pause", post(pause_processing))
+        .route("/admin/resume", post(resume_processing))
+        .route("/admin/intent/:id/diagnostics", get(intent_diagnostics))
+        .route("/admin/recent-failures", get(recent_failures))
+}
+
+/// Pause intent execution. The processor keeps polling but skips every intent.
+pub async fn pause_processing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ProcessorStatusResponse>, EnclaveError> {
+    authorize(&headers)?;
+    state.paused.store(true, Ordering::SeqCst);
+    warn!("Intent processing PAUSED via admin endpoint");
+    Ok(Json(ProcessorStatusResponse { paused: true }))
+}
+
+/// Resume intent execution
+pub async fn resume_processing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ProcessorStatusResponse>, EnclaveError> {
+    authorize(&headers)?;
+    state.paused.store(false, Ordering::SeqCst);
+    warn!("Intent processing RESUMED via admin endpoint");
+    Ok(Json(ProcessorStatusResponse { paused: false }))
+}
+
+/// The latest intent failures, most recent first
+pub async fn recent_failures(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FailureRecord>>, EnclaveError> {
+    authorize(&headers)?;
+    Ok(Json(state.recent_failures.recent()))
+}
+
+/// Diagnostics bundle for one intent: its on-chain fields and what the
+/// processor recorded about its attempts. Quoted amounts are included only
+/// with `MIST_LOG_PRIVACY=full`.
+pub async fn intent_diagnostics(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(intent_id): Path<String>,
+) -> Result<Json<IntentDiagnosticsResponse>, EnclaveError> {
+    use super::intent_processor::{fetch_intent_object, SUI_RPC_URL};
+    use sui_sdk::types::base_types::ObjectID;
+
+    authorize(&headers)?;
+    let object_id = ObjectID::from_hex_literal(&intent_id)
+        .map_err(|e| EnclaveError::InvalidInput(format!("invalid intent ID {}: {}", intent_id, e)))?;
+
+    let on_chain = match sui_sdk::SuiClientBuilder::default().build(SUI_RPC_URL).await {
+        Ok(sui_client) => fetch_intent_object(&sui_client, object_id).await,
+        Err(e) => Err(e.into()),
+    };
+    Ok(Json(diagnostics_bundle(&state, &intent_id, on_chain, LOG_PRIVACY.is_full())))
+}
+
+fn diagnostics_bundle(
+    state: &AppState,
+    intent_id: &str,
+    on_chain: anyhow::Result<Option<SwapIntentObject>>,
+    include_amounts: bool,
+) -> IntentDiagnosticsResponse {
+    let (on_chain, on_chain_error) = match on_chain {
+        Ok(intent) => (intent, None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    IntentDiagnosticsResponse {
+        intent_id: intent_id.to_string(),
+        on_chain: on_chain.map(|intent| OnChainIntent {
+            token_in: intent.token_in,
+            token_out: intent.token_out,
+            deadline: intent.deadline,
+            encrypted_details_len: intent.encrypted_details.len(),
+        }),
+        on_chain_error,
+        processor: state.intent_diagnostics.report(intent_id, include_amounts),
+    }
+}
+
+/// Check the bearer token against `MIST_ADMIN_TOKEN`
+fn authorize(headers: &HeaderMap) -> Result<(), EnclaveError> {
+    let expected = std::env::var(ADMIN_TOKEN_ENV).map_err(|_| {
+        EnclaveError::Unauthorized(format!("admin endpoints disabled: {} not set", ADMIN_TOKEN_ENV))
+    })?;
+    check_bearer_token(headers, &expected)
+}
+
+fn check_bearer_token(headers: &HeaderMap, expected: &str) -> Result<(), EnclaveError> {
+    let provided = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if !expected.is_empty() && tokens_match(token, expected) => Ok(()),
+        _ => Err(EnclaveError::Unauthorized("invalid admin token".to_string())),
+    }
+}
+
+/// Constant-time token comparison. Both sides are hashed first so neither the
+/// comparison time nor an early length check reveals anything about the token.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let a = Blake2b256::digest(provided.as_bytes());
+    let b = Blake2b256::digest(expected.as_bytes());
+    a.as_ref()
+        .iter()
+        .zip(b.as_ref())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_bearer_token_check() {
+        let mut headers = HeaderMap::new();
+        assert!(check_bearer_token(&headers, "secret").is_err());
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer wrong"));
+        assert!(check_bearer_token(&headers, "secret").is_err());
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        assert!(check_bearer_token(&headers, "secret").is_ok());
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secret", "secreT"));
+        assert!(!tokens_match("secret", "secret-but-longer"));
+        assert!(!tokens_match("", "secret"));
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use fastcrypto::ed25519::Ed25519KeyPair;
+        use fastcrypto::traits::KeyPair;
+        use tower::ServiceExt;
+
+        let state = Arc::new(AppState::new(
+            Ed25519KeyPair::generate(&mut rand::thread_rng()),
+            String::new(),
+        ));
+        let app = router().with_state(state.clone());
+
+        let post = |uri: &str, token: Option<&str>| {
+            let mut req = Request::builder().method("POST").uri(uri);
+            if let Some(token) = token {
+                req = req.header(AUTHORIZATION, format!("Bearer {}", token));
+            }
+            req.body(Body::empty()).unwrap()
+        };
+
+        // Token unset: admin endpoints are disabled
+        std::env::remove_var(ADMIN_TOKEN_ENV);
+        let res = app.clone().oneshot(post("/admin/pause", Some("anything"))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert!(!state.paused.load(Ordering::SeqCst));
+
+        std::env::set_var(ADMIN_TOKEN_ENV, "test-admin-token");
+
+        // Wrong or missing token
+        let res = app.clone().oneshot(post("/admin/pause", Some("wrong"))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        let res = app.clone().oneshot(post("/admin/pause", None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert!(!state.paused.load(Ordering::SeqCst));
+
+        // Valid token flips the flag both ways
+        let res = app.clone().oneshot(post("/admin/pause", Some("test-admin-token"))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(state.paused.load(Ordering::SeqCst));
+
+        let res = app.clone().oneshot(post("/admin/resume", Some("test-admin-token"))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!state.paused.load(Ordering::SeqCst));
+
+        // Recent failures: token required, most recent first
+        state.recent_failures.record("0x1", "timeout", 1);
+        state.recent_failures.record("0x2", "decrypt_failed", 2);
+        let get = |token: &str| {
+            Request::builder()
+                .uri("/admin/recent-failures")
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let res = app.clone().oneshot(get("wrong")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        let res = app.clone().oneshot(get("test-admin-token")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let failures: Vec<FailureRecord> = serde_json::from_slice(&body).unwrap();
+        let ids: Vec<&str> = failures.iter().map(|f| f.intent_id.as_str()).collect();
+        assert_eq!(ids, ["0x2", "0x1"]);
+
+        std::env::remove_var(ADMIN_TOKEN_ENV);
+    }
+
+    #[test]
+    fn test_diagnostics_bundle_for_failed_intent() {
+        use super::super::diagnostics::{AttemptStatus, IntentStage};
+        use fastcrypto::ed25519::Ed25519KeyPair;
+        use fastcrypto::traits::KeyPair;
+
+        let state = AppState::new(Ed25519KeyPair::generate(&mut rand::thread_rng()), String::new());
+        let intent_id = format!("0x{}", "1".repeat(64));
+        for _ in 0..3 {
+            state.intent_diagnostics.begin(&intent_id);
+            state.intent_diagnostics.reached(&intent_id, IntentStage::Decrypted);
+            state.intent_diagnostics.failed(&intent_id, "invalid_input", None);
+        }
+        let intent = SwapIntentObject {
+            id: intent_id.clone(),
+            encrypted_details: vec![0u8; 420],
+            token_in: "0x2::sui::SUI".to_string(),
+            token_out: "0x2::sui::SUI".to_string(),
+            deadline: 1_700_000_000_000,
+            created_at_ms: None,
+        };
+
+        let bundle = diagnostics_bundle(&state, &intent_id, Ok(Some(intent)), false);
+        let processor = bundle.processor.as_ref().unwrap();
+        assert_eq!(processor.status, AttemptStatus::Failed);
+        assert_eq!(processor.attempts, 3);
+        assert_eq!(processor.stage, IntentStage::Decrypted);
+        assert_eq!(processor.last_error_code.as_deref(), Some("invalid_input"));
+        assert_eq!(processor.last_error, None);
+
+        let json = serde_json::to_value(&bundle).unwrap();
+        assert_eq!(json["on_chain"]["deadline"], 1_700_000_000_000u64);
+        assert_eq!(json["on_chain"]["encrypted_details_len"], 420);
+        assert_eq!(json["processor"]["status"], "failed");
+        assert!(json.get("on_chain_error").is_none());
+
+        // Consumed or unreadable on-chain; the processor record still shows
+        let bundle = diagnostics_bundle(&state, &intent_id, Err(anyhow::anyhow!("rpc down")), false);
+        assert!(bundle.on_chain.is_none());
+        assert_eq!(bundle.on_chain_error.as_deref(), Some("rpc down"));
+        assert!(bundle.processor.is_some());
+    }
+
+    #[test]
+    fn test_empty_expected_token_rejects() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer "));
+        assert!(check_bearer_token(&headers, "").is_err());
+    }
+}
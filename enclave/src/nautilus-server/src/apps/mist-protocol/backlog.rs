@@ -0,0 +1,125 @@
+//! Live backlog counters for `GET /status`
+//!
+//! The processor updates these as it polls and works through intents, so
+//! monitoring can see how many intents are waiting without parsing the
+//! periodic summary lines. They live in `AppState`, so totals count since the
+//! server started and carry over processor restarts.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Snapshot returned by `GET /status`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BacklogStatus {
+    /// Intents from the latest poll not yet picked up (includes intents
+    /// skipped while paused)
+    pub pending_count: usize,
+    /// Intents being processed right now
+    pub in_flight_count: usize,
+    /// Intents executed successfully
+    pub processed_total: u64,
+    /// Intents that failed to process
+    pub failed_total: u64,
+    /// Unix time of the latest successful poll, in ms; `None` before the first
+    pub last_poll_ms: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct BacklogCounters {
+    pending: AtomicUsize,
+    in_flight: AtomicUsize,
+    processed_total: AtomicU64,
+    failed_total: AtomicU64,
+    last_poll_ms: AtomicU64,
+}
+
+impl BacklogCounters {
+    /// A poll at `at_ms` found `pending` intents
+    pub fn polled(&self, pending: usize, at_ms: u64) {
+        self.pending.store(pending, Ordering::SeqCst);
+        self.last_poll_ms.store(at_ms, Ordering::SeqCst);
+    }
+
+    /// An intent from the latest poll was picked up
+    pub fn started(&self) {
+        let _ = self
+            .pending
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// A picked-up intent finished
+    pub fn finished(&self, succeeded: bool) {
+        let _ = self
+            .in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+        if succeeded {
+            self.processed_total.fetch_add(1, Ordering::SeqCst);
+        } else {
+            self.failed_total.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    pub fn snapshot(&self) -> BacklogStatus {
+        let last_poll_ms = self.last_poll_ms.load(Ordering::SeqCst);
+        BacklogStatus {
+            pending_count: self.pending.load(Ordering::SeqCst),
+            in_flight_count: self.in_flight.load(Ordering::SeqCst),
+            processed_total: self.processed_total.load(Ordering::SeqCst),
+            failed_total: self.failed_total.load(Ordering::SeqCst),
+            last_poll_ms: (last_poll_ms > 0).then_some(last_poll_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_reflects_processor_state() {
+        let counters = BacklogCounters::default();
+        assert_eq!(counters.snapshot().last_poll_ms, None);
+
+        // Earlier cycle: one success, one failure
+        counters.polled(2, 1_700_000_000_000);
+        counters.started();
+        counters.finished(true);
+        counters.started();
+        counters.finished(false);
+
+        // Current cycle: 4 pending, first one in flight
+        counters.polled(4, 1_700_000_005_000);
+        counters.started();
+
+        let status = counters.snapshot();
+        assert_eq!(
+            status,
+            BacklogStatus {
+                pending_count: 3,
+                in_flight_count: 1,
+                processed_total: 1,
+                failed_total: 1,
+                last_poll_ms: Some(1_700_000_005_000),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            serde_json::json!({
+                "pending_count": 3,
+                "in_flight_count": 1,
+                "processed_total": 1,
+                "failed_total": 1,
+                "last_poll_ms": 1_700_000_005_000u64,
+            })
+        );
+
+        // Totals carry over polls; nothing goes negative
+        counters.finished(true);
+        counters.polled(0, 1_700_000_010_000);
+        counters.started();
+        let status = counters.snapshot();
+        assert_eq!((status.pending_count, status.in_flight_count), (0, 1));
+        assert_eq!((status.processed_total, status.failed_total), (2, 1));
+    }
+}
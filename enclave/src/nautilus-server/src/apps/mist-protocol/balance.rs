@@ -0,0 +1,138 @@
+//! Pool balance check before building a swap
+//!
+//! Every swap withdraws its input from the `LiquidityPool`'s `sui_balance`.
+//! A swap for more than the pool holds would be built, signed and then abort
+//! on-chain; checking first turns that into an early `InvalidInput`.
+//!
+//! The same goes for the intent's declared `token_in`: the pool only holds
+//! SUI, so an intent claiming another input token is rejected before any
+//! transaction is built for it. Likewise a zero input amount is rejected up
+//! front instead of surfacing later as a zero payout.
+
+use crate::EnclaveError;
+
+/// Name of the pool's balance field in the Move struct
+pub const POOL_BALANCE_FIELD: &str = "sui_balance";
+
+/// Coin type held by the pool's `sui_balance` (`Balance<SUI>`)
+pub const POOL_COIN_TYPE: &str = "0x2::sui::SUI";
+
+/// Reject an intent whose declared `token_in` isn't the pool's coin type.
+/// Addresses are compared numerically, so `0x2` matches `0x00..02`.
+pub fn check_token_in(declared: &str) -> Result<(), EnclaveError> {
+    if normalize_coin_type(declared) != normalize_coin_type(POOL_COIN_TYPE) {
+        return Err(EnclaveError::InvalidInput(format!(
+            "token_in mismatch: intent declares {}, pool holds {}",
+            declared, POOL_COIN_TYPE
+        )));
+    }
+    Ok(())
+}
+
+/// Lowercase `addr::module::name` with leading zeros stripped from the address
+pub(super) fn normalize_coin_type(coin_type: &str) -> String {
+    let coin_type = coin_type.trim().to_lowercase();
+    match coin_type.split_once("::") {
+        Some((address, rest)) => {
+            let digits = address.trim_start_matches("0x").trim_start_matches('0');
+            format!("0x{}::{}", if digits.is_empty() { "0" } else { digits }, rest)
+        }
+        None => coin_type,
+    }
+}
+
+/// Parse the decrypted input amount (base units), rejecting zero
+pub fn parse_input_amount(raw: &str) -> Result<u64, EnclaveError> {
+    let amount: u64 = raw
+        .trim()
+        .parse()
+        .map_err(|_| EnclaveError::InvalidInput(format!("input amount is not a base-unit integer: {:?}", raw)))?;
+    if amount == 0 {
+        return Err(EnclaveError::InvalidInput("input amount is zero: nothing to swap".to_string()));
+    }
+    Ok(amount)
+}
+
+/// Reject a withdrawal of `need` from a balance of `have`
+pub fn check_sufficient(have: u64, need: u64) -> Result<(), EnclaveError> {
+    if have < need {
+        return Err(EnclaveError::InvalidInput(format!(
+            "insufficient balance: have {} need {}",
+            have, need
+        )));
+    }
+    Ok(())
+}
+
+/// Read a `Balance<T>` field from Move object fields. Sui JSON-RPC renders
+/// it as a u64 string; `{ "value": .. }` is accepted as well.
+pub fn balance_field(fields: &serde_json::Value, name: &str) -> Option<u64> {
+    let value = fields.get(name)?;
+    let value = value.get("value").unwrap_or(value);
+    match value {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sufficient_and_insufficient_balance() {
+        assert!(check_sufficient(1_000, 999).is_ok());
+        assert!(check_sufficient(1_000, 1_000).is_ok());
+
+        match check_sufficient(500, 1_000) {
+            Err(EnclaveError::InvalidInput(msg)) => assert_eq!(msg, "insufficient balance: have 500 need 1000"),
+            other => panic!("expected InvalidInput, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_zero_input_amount_is_rejected() {
+        assert_eq!(parse_input_amount("50000000").unwrap(), 50_000_000);
+
+        for zero in ["0", "000", " 0 "] {
+            match parse_input_amount(zero) {
+                Err(EnclaveError::InvalidInput(msg)) => assert_eq!(msg, "input amount is zero: nothing to swap"),
+                other => panic!("expected InvalidInput, got {:?}", other),
+            }
+        }
+        assert!(matches!(parse_input_amount("-1"), Err(EnclaveError::InvalidInput(_))));
+        assert!(matches!(parse_input_amount("0.5"), Err(EnclaveError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_token_in_matches_pool() {
+        assert!(check_token_in("0x2::sui::SUI").is_ok());
+        assert!(check_token_in(&format!("0x{:0>64}::sui::SUI", "2")).is_ok());
+
+        // USDC declared, but the pool only ever pays out SUI
+        let usdc = "0xdba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7::usdc::USDC";
+        match check_token_in(usdc) {
+            Err(EnclaveError::InvalidInput(msg)) => {
+                assert_eq!(msg, format!("token_in mismatch: intent declares {}, pool holds 0x2::sui::SUI", usdc))
+            }
+            other => panic!("expected InvalidInput, got {:?}", other),
+        }
+        assert!(check_token_in("SUI").is_err());
+        assert!(check_token_in("0x3::sui::SUI").is_err());
+    }
+
+    #[test]
+    fn test_balance_field_forms() {
+        let fields = json!({ "sui_balance": "250000000", "paused": false });
+        assert_eq!(balance_field(&fields, POOL_BALANCE_FIELD), Some(250_000_000));
+
+        let nested = json!({ "sui_balance": { "value": "42" } });
+        assert_eq!(balance_field(&nested, POOL_BALANCE_FIELD), Some(42));
+
+        assert_eq!(balance_field(&json!({ "sui_balance": 7 }), POOL_BALANCE_FIELD), Some(7));
+        assert_eq!(balance_field(&json!({}), POOL_BALANCE_FIELD), None);
+        assert_eq!(balance_field(&json!({ "sui_balance": "lots" }), POOL_BALANCE_FIELD), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! Guarded BCS parsing of attacker-controlled bytes
+//!
+//! `encrypted_details` comes straight from an on-chain intent anyone can
+//! create. Parsing it must not be able to take the processor down, so input
+//! is size-bounded and any panic inside the deserializer is caught and turned
+//! into `EnclaveError::InvalidInput`.
+//!
+//! What the intent decrypts to is just as attacker-chosen, so plaintext is
+//! capped too (`MIST_MAX_DECRYPTED_BYTES`) before it is parsed as JSON.
+
+use crate::EnclaveError;
+use serde::de::DeserializeOwned;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Upper bound for a serialized SEAL `EncryptedObject`. Swap details are a
+/// few hundred bytes; anything near this is not a legitimate intent.
+pub const MAX_ENCRYPTED_OBJECT_BYTES: usize = 16 * 1024;
+
+/// Environment variable for the decrypted plaintext cap in bytes
+pub const MAX_DECRYPTED_BYTES_ENV: &str = "MIST_MAX_DECRYPTED_BYTES";
+/// Default plaintext cap. Decrypted swap details are JSON of a few hundred bytes.
+pub const DEFAULT_MAX_DECRYPTED_BYTES: usize = 4 * 1024;
+
+/// Read the plaintext cap from `MIST_MAX_DECRYPTED_BYTES`, defaulting to 4 KiB
+pub fn max_decrypted_from_env() -> anyhow::Result<usize> {
+    match std::env::var(MAX_DECRYPTED_BYTES_ENV) {
+        Ok(value) => match value.parse::<usize>() {
+            Ok(max) if max > 0 => Ok(max),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", MAX_DECRYPTED_BYTES_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_MAX_DECRYPTED_BYTES),
+    }
+}
+
+/// Reject decrypted plaintext over `max_len` bytes, before it is parsed
+pub fn check_decrypted_size(plaintext: &[u8], max_len: usize) -> Result<(), EnclaveError> {
+    if plaintext.len() > max_len {
+        return Err(EnclaveError::DecryptionFailed(format!(
+            "decrypted details too large: {} bytes (max {})",
+            plaintext.len(),
+            max_len
+        )));
+    }
+    Ok(())
+}
+
+/// Deserialize `bytes` as `T`, rejecting input over `max_len` bytes and
+/// converting both parse errors and panics into `InvalidInput`
+pub fn parse_bounded<T: DeserializeOwned>(bytes: &[u8], max_len: usize) -> Result<T, EnclaveError> {
+    if bytes.len() > max_len {
+        return Err(EnclaveError::InvalidInput(format!(
+            "BCS input too large: {} bytes (max {})",
+            bytes.len(),
+            max_len
+        )));
+    }
+
+    match panic::catch_unwind(AssertUnwindSafe(|| bcs::from_bytes::<T>(bytes))) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(EnclaveError::InvalidInput(format!("Malformed BCS input: {}", e))),
+        Err(_) => Err(EnclaveError::InvalidInput(
+            "Malformed BCS input: deserializer panicked".to_string(),
+        )),
+    }
+}
+
+/// Parse a SEAL encrypted object from intent bytes
+#[cfg(feature = "mist-protocol")]
+pub fn parse_encrypted_object(bytes: &[u8]) -> Result<seal_sdk::EncryptedObject, EnclaveError> {
+    parse_bounded(bytes, MAX_ENCRYPTED_OBJECT_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use serde::{Deserialize, Deserializer};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Sample {
+        id: Vec<u8>,
+        threshold: u8,
+        services: Vec<([u8; 32], u8)>,
+    }
+
+    /// Stands in for an SDK type whose deserializer panics on bad input
+    #[derive(Debug)]
+    struct Panicky;
+
+    impl<'de> Deserialize<'de> for Panicky {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let len = u8::deserialize(deserializer)?;
+            let buf = [0u8; 4];
+            let _ = buf[len as usize];
+            Ok(Panicky)
+        }
+    }
+
+    #[test]
+    fn test_valid_and_oversized_input() {
+        let bytes = bcs::to_bytes(&(vec![1u8, 2, 3], 2u8, vec![([7u8; 32], 1u8)])).unwrap();
+        let parsed: Sample = parse_bounded(&bytes, 1024).unwrap();
+        assert_eq!(parsed.id, vec![1, 2, 3]);
+        assert_eq!(parsed.threshold, 2);
+
+        assert!(matches!(
+            parse_bounded::<Sample>(&bytes, bytes.len() - 1),
+            Err(EnclaveError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_oversized_plaintext_is_rejected() {
+        let details = br#"{"nullifier":"0x01","inputAmount":"100","outputStealth":"0x02","remainderStealth":"0x03","signature":""}"#;
+        assert!(check_decrypted_size(details, DEFAULT_MAX_DECRYPTED_BYTES).is_ok());
+        assert!(check_decrypted_size(&[0u8; 64], 64).is_ok());
+
+        let blob = vec![b'a'; DEFAULT_MAX_DECRYPTED_BYTES + 1];
+        match check_decrypted_size(&blob, DEFAULT_MAX_DECRYPTED_BYTES) {
+            Err(EnclaveError::DecryptionFailed(msg)) => {
+                assert_eq!(msg, "decrypted details too large: 4097 bytes (max 4096)")
+            }
+            other => panic!("expected DecryptionFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_panic_is_caught() {
+        assert!(parse_bounded::<Panicky>(&[1], 16).is_ok());
+        assert!(matches!(
+            parse_bounded::<Panicky>(&[200], 16),
+            Err(EnclaveError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_random_bytes_never_panic() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x6d697374);
+        for _ in 0..10_000 {
+            let len = rng.gen_range(0..512);
+            let mut bytes = vec![0u8; len];
+            rng.fill(&mut bytes[..]);
+
+            // Claimed lengths far beyond the input must not allocate or panic
+            if len > 0 && rng.gen_bool(0.25) {
+                bytes[0] = 0xff;
+            }
+
+            match parse_bounded::<Sample>(&bytes, MAX_ENCRYPTED_OBJECT_BYTES) {
+                Ok(_) | Err(EnclaveError::InvalidInput(_)) => {}
+                Err(e) => panic!("unexpected error kind: {}", e),
+            }
+            #[cfg(feature = "mist-protocol")]
+            match parse_encrypted_object(&bytes) {
+                Ok(_) | Err(EnclaveError::InvalidInput(_)) => {}
+                Err(e) => panic!("unexpected error kind: {}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Startup canary: a synthetic intent run through the pipeline after deploy
+//!
+//! With `MIST_STARTUP_CANARY` set, the server signs a tiny intent with a
+//! throwaway wallet, SEAL-encrypts it under the TEE namespace and decrypts it
+//! through the configured key servers, exactly as a real intent is. The
+//! decrypted details are then validated as the processor would (nullifier,
+//! wallet signature, input amount). The canary has no deposit or on-chain
+//! intent behind it, so it stops there: nothing is simulated or submitted.
+//!
+//! - `off` (default): no canary
+//! - `warn`: log the outcome and start regardless
+//! - `required`: refuse to start if the canary fails
+
+use anyhow::{Context, Result};
+use fastcrypto::ed25519::Ed25519KeyPair;
+use std::future::Future;
+use std::str::FromStr;
+
+use super::DecryptedSwapDetails;
+
+/// Environment variable selecting the canary mode
+pub const STARTUP_CANARY_ENV: &str = "MIST_STARTUP_CANARY";
+
+/// Prefix of TEE encryption IDs (`seal_policy::seal_approve_tee`)
+pub const TEE_NAMESPACE: &[u8] = b"mist_protocol_v2_seal_namespace_";
+/// Random bytes after the namespace in a TEE encryption ID
+const TEE_NONCE_LEN: usize = 5;
+
+/// Input amount of the canary intent (base units)
+const CANARY_AMOUNT: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CanaryMode {
+    #[default]
+    Off,
+    Warn,
+    Required,
+}
+
+impl FromStr for CanaryMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "required" => Ok(Self::Required),
+            _ => Err(anyhow::anyhow!("Unknown startup canary mode: {}", s)),
+        }
+    }
+}
+
+impl CanaryMode {
+    /// Read the mode from `MIST_STARTUP_CANARY`, defaulting to `Off`
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(STARTUP_CANARY_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+/// Canary intent details signed by `wallet`, and the wallet's Sui address
+pub fn canary_details(wallet: &Ed25519KeyPair) -> (DecryptedSwapDetails, String) {
+    use fastcrypto::encoding::{Base64, Encoding};
+    use fastcrypto::hash::HashFunction;
+    use fastcrypto::traits::{KeyPair, Signer};
+
+    let mut details = DecryptedSwapDetails {
+        nullifier: format!("0x{}", hex::encode(rand::random::<[u8; 32]>())),
+        input_amount: CANARY_AMOUNT.to_string(),
+        output_stealth: format!("0x{}", hex::encode(rand::random::<[u8; 32]>())),
+        remainder_stealth: format!("0x{}", hex::encode(rand::random::<[u8; 32]>())),
+        signature: String::new(),
+    };
+
+    // Personal message signature, as the frontend's wallet produces it
+    let message = format!(
+        "mist_intent_v2:{}:{}:{}:{}",
+        details.nullifier, details.input_amount, details.output_stealth, details.remainder_stealth
+    );
+    let mut intent_message = vec![3, 0, 0];
+    intent_message.extend(bcs::to_bytes(&message.as_bytes().to_vec()).expect("BCS encoding should not fail"));
+    let digest = fastcrypto::hash::Blake2b256::digest(&intent_message);
+    let signature = wallet.sign(digest.as_ref());
+
+    let mut bytes = vec![0x00];
+    bytes.extend_from_slice(signature.as_ref());
+    bytes.extend_from_slice(wallet.public().as_ref());
+    details.signature = Base64::encode(bytes);
+
+    let mut address_input = vec![0x00];
+    address_input.extend_from_slice(wallet.public().as_ref());
+    let address = format!("0x{}", hex::encode(fastcrypto::hash::Blake2b256::digest(&address_input)));
+    (details, address)
+}
+
+/// Run the canary: encrypt a fresh canary intent with `encrypt`, decrypt it
+/// with `decrypt` and validate the result. Errors name the failed step.
+pub async fn run_canary<E, D, Fut>(encrypt: E, decrypt: D) -> Result<()>
+where
+    E: FnOnce(&[u8]) -> Result<Vec<u8>>,
+    D: FnOnce(Vec<u8>) -> Fut,
+    Fut: Future<Output = Result<DecryptedSwapDetails>>,
+{
+    use fastcrypto::traits::KeyPair;
+
+    let wallet = Ed25519KeyPair::generate(&mut rand::thread_rng());
+    let (details, wallet_address) = canary_details(&wallet);
+    let plaintext = serde_json::to_vec(&details)?;
+
+    let encrypted = encrypt(&plaintext).context("canary encryption failed")?;
+    let decrypted = decrypt(encrypted).await.context("canary decryption failed")?;
+    if serde_json::to_value(&decrypted)? != serde_json::to_value(&details)? {
+        anyhow::bail!("canary decrypted to different details than were encrypted");
+    }
+
+    validate(&decrypted, &wallet_address).context("canary validation failed")
+}
+
+/// The processor's checks on decrypted details
+fn validate(details: &DecryptedSwapDetails, wallet_address: &str) -> Result<()> {
+    super::types::normalize_nullifier(&details.nullifier)?;
+    let signer = super::intent_processor::verify_intent_signature(details)?;
+    if signer != wallet_address {
+        anyhow::bail!("signature verified for {}, expected the canary wallet {}", signer, wallet_address);
+    }
+    super::balance::parse_input_amount(&details.input_amount)?;
+    Ok(())
+}
+
+/// SEAL-encrypt `plaintext` for TEE decryption with the configured key
+/// servers, encoded like `SwapIntent.encrypted_details` (base64 of the BCS
+/// `EncryptedObject`)
+pub fn encrypt_for_tee(plaintext: &[u8]) -> Result<Vec<u8>> {
+    use crypto::{EncryptionInput, IBEPublicKeys};
+    use fastcrypto::encoding::{Base64, Encoding};
+
+    let config = &*super::SEAL_CONFIG;
+    let mut encryption_id = TEE_NAMESPACE.to_vec();
+    encryption_id.extend_from_slice(&rand::random::<[u8; TEE_NONCE_LEN]>());
+
+    let (encrypted_obj, _symmetric_key) = seal_sdk::seal_encrypt(
+        config.package_id,
+        encryption_id,
+        config.key_servers.clone(),
+        &IBEPublicKeys::BonehFranklinBLS12381(config.public_keys.clone()),
+        2, // threshold = 2, as the frontend encrypts
+        EncryptionInput::Aes256Gcm { data: plaintext.to_vec(), aad: None },
+    )
+    .map_err(|e| anyhow::anyhow!("SEAL encryption failed: {:?}", e))?;
+
+    Ok(Base64::encode(bcs::to_bytes(&encrypted_obj)?).into_bytes())
+}
+
+/// The canary against the configured SEAL key servers
+pub async fn startup_canary(state: &crate::AppState) -> Result<()> {
+    run_canary(encrypt_for_tee, |encrypted| async move {
+        super::intent_processor::decrypt_swap_details(&encrypted, state)
+            .await
+            .map(|outcome| outcome.details)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::traits::KeyPair;
+
+    #[tokio::test]
+    async fn test_canary_fails_on_altered_details() {
+        // Amount altered after signing: decrypts, but the signature no longer matches
+        let err = run_canary(
+            |plaintext| Ok(plaintext.to_vec()),
+            |encrypted| async move {
+                let mut details: DecryptedSwapDetails = serde_json::from_slice(&encrypted)?;
+                details.input_amount = "1000000".to_string();
+                Ok(details)
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "canary decrypted to different details than were encrypted");
+
+        let wallet = Ed25519KeyPair::generate(&mut rand::thread_rng());
+        let (mut details, address) = canary_details(&wallet);
+        validate(&details, &address).unwrap();
+        details.input_amount = "2".to_string();
+        let err = validate(&details, &address).unwrap_err();
+        assert!(err.to_string().contains("signature verification failed"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!("WARN".parse::<CanaryMode>().unwrap(), CanaryMode::Warn);
+        assert_eq!("required".parse::<CanaryMode>().unwrap(), CanaryMode::Required);
+        assert!("on".parse::<CanaryMode>().is_err());
+        assert_eq!(CanaryMode::default(), CanaryMode::Off);
+    }
+}
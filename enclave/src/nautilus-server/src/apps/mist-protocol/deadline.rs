@@ -0,0 +1,87 @@
+//! Time-to-deadline check before submission
+//!
+//! An intent can be valid when polled and still run out of time while SEAL
+//! decryption, quoting and the dry-run are in flight. Right before signing
+//! the executor requires at least `MIST_DEADLINE_MARGIN_SECS` (default 10)
+//! to be left by the enclave's clock. `0` disables the check.
+//!
+//! This is a deliberately stricter local policy, not a prediction of
+//! `E_DEADLINE_PASSED`: the contract compares the deadline with
+//! `tx_context::epoch_timestamp_ms`, the start of the current epoch, so it
+//! accepts intents the wall clock already considers expired. The enclave
+//! honours the deadline the user signed rather than that lag.
+
+use crate::EnclaveError;
+use anyhow::Result;
+use std::time::Duration;
+
+/// Environment variable for the minimum time left before submitting, in seconds
+pub const DEADLINE_MARGIN_ENV: &str = "MIST_DEADLINE_MARGIN_SECS";
+/// Default minimum time left
+pub const DEFAULT_DEADLINE_MARGIN: Duration = Duration::from_secs(10);
+
+/// Read the margin from `MIST_DEADLINE_MARGIN_SECS`, defaulting to 10 seconds
+pub fn margin_from_env() -> Result<Duration> {
+    match std::env::var(DEADLINE_MARGIN_ENV) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) => Ok(Duration::from_secs(secs)),
+            _ => anyhow::bail!("{} must be a non-negative integer, got {:?}", DEADLINE_MARGIN_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_DEADLINE_MARGIN),
+    }
+}
+
+/// Reject submission when less than `margin` is left until `deadline_ms`,
+/// by the local clock `now_ms`. An intent already past its deadline is
+/// invalid input rather than a processing failure.
+pub fn check_time_remaining(deadline_ms: u64, now_ms: u64, margin: Duration) -> Result<(), EnclaveError> {
+    if margin.is_zero() {
+        return Ok(());
+    }
+    if now_ms >= deadline_ms {
+        return Err(EnclaveError::InvalidInput(format!(
+            "intent expired: deadline {} passed {}ms ago",
+            deadline_ms,
+            now_ms - deadline_ms
+        )));
+    }
+    let remaining_ms = deadline_ms.saturating_sub(now_ms);
+    let margin_ms = margin.as_millis() as u64;
+    if remaining_ms < margin_ms {
+        return Err(EnclaveError::GenericError(format!(
+            "insufficient time to execute before deadline: {}ms left, need {}ms",
+            remaining_ms, margin_ms
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARGIN: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn test_ample_time() {
+        assert!(check_time_remaining(1_000_000, 900_000, MARGIN).is_ok());
+        assert!(check_time_remaining(1_010_000, 1_000_000, MARGIN).is_ok());
+    }
+
+    #[test]
+    fn test_insufficient_time() {
+        let err = check_time_remaining(1_009_999, 1_000_000, MARGIN).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "insufficient time to execute before deadline: 9999ms left, need 10000ms"
+        );
+
+        // Already past the deadline
+        let err = check_time_remaining(1_000_000, 1_000_500, MARGIN).unwrap_err();
+        assert!(matches!(err, EnclaveError::InvalidInput(_)), "{:?}", err);
+        assert_eq!(err.to_string(), "Invalid input: intent expired: deadline 1000000 passed 500ms ago");
+
+        // Disabled
+        assert!(check_time_remaining(1_000_000, 1_000_500, Duration::ZERO).is_ok());
+    }
+}
@@ -0,0 +1,230 @@
+//! Short-lived cache of SEAL decryptions
+//!
+//! A retried intent (e.g. after a transient swap failure) carries the same
+//! `EncryptedObject`, so its decryption is reused instead of fetching keys
+//! from the SEAL servers again. Entries are keyed by a Blake2b256 hash of the
+//! whole encrypted object, not its encryption ID: the ID is chosen by the
+//! client, and reusing it with another ciphertext must not return someone
+//! else's details. Entries expire after `MIST_DECRYPT_CACHE_TTL_SECS`
+//! (default 60, `0` disables the cache) and at most `MIST_DECRYPT_CACHE_SIZE`
+//! (default 128) are kept. The cache lives on `AppState`; the signer is
+//! fixed once the state is built, and `AppState::with_signer` clears it.
+
+use anyhow::Result;
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Environment variable for the entry lifetime in seconds
+pub const DECRYPT_CACHE_TTL_ENV: &str = "MIST_DECRYPT_CACHE_TTL_SECS";
+/// Environment variable for the maximum number of entries
+pub const DECRYPT_CACHE_SIZE_ENV: &str = "MIST_DECRYPT_CACHE_SIZE";
+/// Default entry lifetime
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+/// Default maximum number of entries
+pub const DEFAULT_CAPACITY: usize = 128;
+
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    inserted_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct DecryptCache<V> {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<Vec<u8>, Entry<V>>>,
+}
+
+/// Cache key for the encrypted object bytes `encrypted`
+pub fn cache_key(encrypted: &[u8]) -> Vec<u8> {
+    Blake2b256::digest(encrypted).as_ref().to_vec()
+}
+
+impl<V: Clone> Default for DecryptCache<V> {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL, DEFAULT_CAPACITY)
+    }
+}
+
+impl<V: Clone> DecryptCache<V> {
+    /// A zero `ttl` or `capacity` disables caching
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build from `MIST_DECRYPT_CACHE_TTL_SECS` / `MIST_DECRYPT_CACHE_SIZE`,
+    /// falling back to the defaults for unset or invalid values
+    pub fn from_env() -> Self {
+        let ttl = std::env::var(DECRYPT_CACHE_TTL_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TTL);
+        let capacity = std::env::var(DECRYPT_CACHE_SIZE_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        Self::new(ttl, capacity)
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.capacity > 0
+    }
+
+    /// Cached decryption under `key`, if present and not expired
+    pub fn get(&self, key: &[u8]) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store a decryption, evicting expired entries and then the oldest one
+    /// if the cache is full
+    pub fn insert(&self, key: Vec<u8>, value: V) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.inserted_at.elapsed() < self.ttl);
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted_at)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                value,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Return the cached decryption of the `encrypted` object bytes or run
+    /// `decrypt` and cache its result. Failures are not cached.
+    pub async fn get_or_decrypt<F, Fut>(&self, encrypted: &[u8], decrypt: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        let key = cache_key(encrypted);
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+        let value = decrypt().await?;
+        self.insert(key, value.clone());
+        Ok(value)
+    }
+
+    /// Drop every entry
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_second_decryption_hits_cache() {
+        let cache = DecryptCache::new(Duration::from_secs(60), 8);
+        let seal_calls = AtomicUsize::new(0);
+        let decrypt = || async {
+            seal_calls.fetch_add(1, Ordering::SeqCst);
+            Ok("details".to_string())
+        };
+
+        assert_eq!(cache.get_or_decrypt(b"enc-1", decrypt).await.unwrap(), "details");
+        assert_eq!(cache.get_or_decrypt(b"enc-1", decrypt).await.unwrap(), "details");
+        assert_eq!(seal_calls.load(Ordering::SeqCst), 1);
+
+        // Another object is decrypted separately
+        cache.get_or_decrypt(b"enc-2", decrypt).await.unwrap();
+        assert_eq!(seal_calls.load(Ordering::SeqCst), 2);
+
+        // Clearing empties the cache
+        cache.clear();
+        cache.get_or_decrypt(b"enc-1", decrypt).await.unwrap();
+        assert_eq!(seal_calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_keyed_by_whole_object() {
+        let cache = DecryptCache::new(Duration::from_secs(60), 8);
+        // Same encryption ID, different ciphertext
+        let first = b"id-1|ciphertext-a";
+        let reused_id = b"id-1|ciphertext-b";
+
+        cache.get_or_decrypt(first, || async { Ok("alice".to_string()) }).await.unwrap();
+        let other = cache.get_or_decrypt(reused_id, || async { Ok("bob".to_string()) }).await.unwrap();
+        assert_eq!(other, "bob");
+        assert_eq!(cache.get(&cache_key(first)), Some("alice".to_string()));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_not_cached() {
+        let cache = DecryptCache::<String>::default();
+        let failed = cache
+            .get_or_decrypt(b"enc", || async { Err(anyhow::anyhow!("SEAL unavailable")) })
+            .await;
+        assert!(failed.is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_bounded_size_evicts_oldest() {
+        let cache = DecryptCache::new(Duration::from_secs(60), 2);
+        cache.insert(b"a".to_vec(), 1);
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert(b"b".to_vec(), 2);
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert(b"c".to_vec(), 3);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(b"a"), None);
+        assert_eq!(cache.get(b"b"), Some(2));
+        assert_eq!(cache.get(b"c"), Some(3));
+    }
+
+    #[test]
+    fn test_expired_and_disabled() {
+        let cache = DecryptCache::new(Duration::from_millis(1), 8);
+        cache.insert(b"a".to_vec(), 1);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(cache.get(b"a"), None);
+        assert!(cache.is_empty());
+
+        let disabled = DecryptCache::new(Duration::ZERO, 8);
+        disabled.insert(b"a".to_vec(), 1);
+        assert_eq!(disabled.get(b"a"), None);
+    }
+}
@@ -0,0 +1,314 @@
+//! Per-intent processing records for incident response
+//!
+//! The processor notes each attempt at an intent: how often it was tried,
+//! how far the last attempt got, the last error and the quote it was priced
+//! at. `GET /admin/intent/:id/diagnostics` returns the record alongside the
+//! on-chain SwapIntent, so a repeatedly failing intent can be looked into
+//! without trawling the logs.
+//!
+//! Records live in memory only and restart with the server. Quoted amounts
+//! are derived from the decrypted input amount, so they are left out of
+//! reports unless `MIST_LOG_PRIVACY=full`. Errors are kept as their failure
+//! class; the processor only passes the error text in full mode, since
+//! messages like "insufficient balance" quote decrypted amounts.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Records kept before the least recently updated is evicted
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Furthest step an attempt reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentStage {
+    /// Picked up from the pending set; nothing decrypted yet
+    Received,
+    /// SEAL decryption succeeded
+    Decrypted,
+    /// Nullifier and wallet signature checked
+    Verified,
+    /// Venue picked and output quoted
+    Quoted,
+    /// Transaction built and handed off for signing and submission (or dry-run)
+    Submitted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptStatus {
+    InProgress,
+    Failed,
+    Succeeded,
+}
+
+/// Venue and pricing of an attempt's swap
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteSnapshot {
+    pub venue: String,
+    /// Empty when no pools are configured (default fee tier)
+    pub pool_id: String,
+    pub fee_rate: u64,
+    pub estimated_out: u64,
+    pub min_amount_out: u64,
+}
+
+/// Quote as reported; amounts only with `include_amounts`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuoteReport {
+    pub venue: String,
+    pub pool_id: String,
+    pub fee_rate: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_out: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_amount_out: Option<u64>,
+}
+
+/// Processor-side state of one intent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IntentReport {
+    pub status: AttemptStatus,
+    /// Attempts since the server started, including the current one
+    pub attempts: u32,
+    /// Stage reached by the latest attempt
+    pub stage: IntentStage,
+    /// Failure class of the most recent failed attempt
+    pub last_error_code: Option<String>,
+    /// Its error text, only recorded with `MIST_LOG_PRIVACY=full`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub quote: Option<QuoteReport>,
+    /// Unix time of the last update, in ms
+    pub updated_at_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Record {
+    status: AttemptStatus,
+    attempts: u32,
+    stage: IntentStage,
+    last_error_code: Option<String>,
+    last_error: Option<String>,
+    quote: Option<QuoteSnapshot>,
+    updated_at_ms: u64,
+}
+
+#[derive(Debug)]
+pub struct IntentDiagnostics {
+    capacity: usize,
+    records: Mutex<HashMap<String, Record>>,
+}
+
+impl Default for IntentDiagnostics {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl IntentDiagnostics {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start a new attempt. The previous attempt's error and quote are kept
+    /// until this one replaces them.
+    pub fn begin(&self, intent_id: &str) {
+        let mut records = self.records.lock().unwrap();
+        if !records.contains_key(intent_id) && records.len() >= self.capacity {
+            let oldest = records
+                .iter()
+                .min_by_key(|(_, record)| record.updated_at_ms)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                records.remove(&oldest);
+            }
+        }
+        let record = records.entry(intent_id.to_string()).or_insert_with(|| Record {
+            status: AttemptStatus::InProgress,
+            attempts: 0,
+            stage: IntentStage::Received,
+            last_error_code: None,
+            last_error: None,
+            quote: None,
+            updated_at_ms: 0,
+        });
+        record.status = AttemptStatus::InProgress;
+        record.attempts += 1;
+        record.stage = IntentStage::Received;
+        record.updated_at_ms = now_ms();
+    }
+
+    /// The current attempt got to `stage`
+    pub fn reached(&self, intent_id: &str, stage: IntentStage) {
+        self.update(intent_id, |record| record.stage = stage);
+    }
+
+    pub fn quoted(&self, intent_id: &str, quote: QuoteSnapshot) {
+        self.update(intent_id, |record| {
+            record.stage = IntentStage::Quoted;
+            record.quote = Some(quote);
+        });
+    }
+
+    /// The current attempt failed with `error_code`; `error` is its text,
+    /// passed only when it may be shown
+    pub fn failed(&self, intent_id: &str, error_code: &str, error: Option<&str>) {
+        self.update(intent_id, |record| {
+            record.status = AttemptStatus::Failed;
+            record.last_error_code = Some(error_code.to_string());
+            record.last_error = error.map(str::to_string);
+        });
+    }
+
+    pub fn succeeded(&self, intent_id: &str) {
+        self.update(intent_id, |record| record.status = AttemptStatus::Succeeded);
+    }
+
+    /// Record for `intent_id`, if the processor has attempted it
+    pub fn report(&self, intent_id: &str, include_amounts: bool) -> Option<IntentReport> {
+        let records = self.records.lock().unwrap();
+        let record = records.get(intent_id)?;
+        Some(IntentReport {
+            status: record.status,
+            attempts: record.attempts,
+            stage: record.stage,
+            last_error_code: record.last_error_code.clone(),
+            last_error: record.last_error.clone(),
+            quote: record.quote.as_ref().map(|quote| QuoteReport {
+                venue: quote.venue.clone(),
+                pool_id: quote.pool_id.clone(),
+                fee_rate: quote.fee_rate,
+                estimated_out: include_amounts.then_some(quote.estimated_out),
+                min_amount_out: include_amounts.then_some(quote.min_amount_out),
+            }),
+            updated_at_ms: record.updated_at_ms,
+        })
+    }
+
+    /// Updates for intents without a current attempt are dropped
+    fn update(&self, intent_id: &str, apply: impl FnOnce(&mut Record)) {
+        if let Some(record) = self.records.lock().unwrap().get_mut(intent_id) {
+            apply(record);
+            record.updated_at_ms = now_ms();
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote() -> QuoteSnapshot {
+        QuoteSnapshot {
+            venue: "flowx".to_string(),
+            pool_id: "0xpool".to_string(),
+            fee_rate: 3000,
+            estimated_out: 49_000_000,
+            min_amount_out: 48_500_000,
+        }
+    }
+
+    #[test]
+    fn test_failed_attempts_are_recorded() {
+        let diagnostics = IntentDiagnostics::default();
+        assert!(diagnostics.report("0xintent", true).is_none());
+
+        diagnostics.begin("0xintent");
+        diagnostics.reached("0xintent", IntentStage::Decrypted);
+        diagnostics.failed("0xintent", "invalid_input", Some("Invalid signature"));
+
+        diagnostics.begin("0xintent");
+        diagnostics.reached("0xintent", IntentStage::Verified);
+        diagnostics.quoted("0xintent", quote());
+        diagnostics.failed(
+            "0xintent",
+            "other",
+            Some("insufficient time to execute before deadline: 900ms left, need 10000ms"),
+        );
+
+        let report = diagnostics.report("0xintent", false).unwrap();
+        assert_eq!(report.status, AttemptStatus::Failed);
+        assert_eq!(report.attempts, 2);
+        assert_eq!(report.stage, IntentStage::Quoted);
+        assert_eq!(report.last_error_code.as_deref(), Some("other"));
+        assert_eq!(
+            report.last_error.as_deref(),
+            Some("insufficient time to execute before deadline: 900ms left, need 10000ms")
+        );
+        let quote_report = report.quote.unwrap();
+        assert_eq!((quote_report.pool_id.as_str(), quote_report.fee_rate), ("0xpool", 3000));
+        assert_eq!((quote_report.estimated_out, quote_report.min_amount_out), (None, None));
+
+        // A new attempt starts over but keeps the last error until it fails again
+        diagnostics.begin("0xintent");
+        let report = diagnostics.report("0xintent", true).unwrap();
+        assert_eq!(report.status, AttemptStatus::InProgress);
+        assert_eq!((report.attempts, report.stage), (3, IntentStage::Received));
+        assert!(report.last_error_code.is_some());
+        assert!(report.last_error.is_some());
+        assert_eq!(report.quote.unwrap().estimated_out, Some(49_000_000));
+
+        diagnostics.succeeded("0xintent");
+        assert_eq!(diagnostics.report("0xintent", true).unwrap().status, AttemptStatus::Succeeded);
+    }
+
+    #[test]
+    fn test_amounts_only_when_included() {
+        let diagnostics = IntentDiagnostics::default();
+        diagnostics.begin("0xintent");
+        diagnostics.quoted("0xintent", quote());
+
+        let redacted = serde_json::to_value(diagnostics.report("0xintent", false)).unwrap();
+        assert!(redacted["quote"].get("estimated_out").is_none());
+        assert!(redacted["quote"].get("min_amount_out").is_none());
+        assert_eq!(redacted["stage"], "quoted");
+
+        let full = serde_json::to_value(diagnostics.report("0xintent", true)).unwrap();
+        assert_eq!(full["quote"]["estimated_out"], 49_000_000);
+    }
+
+    #[test]
+    fn test_error_text_only_when_given() {
+        let diagnostics = IntentDiagnostics::default();
+        diagnostics.begin("0xintent");
+        diagnostics.failed("0xintent", "insufficient_balance", Some("insufficient balance: have 5 need 7"));
+
+        // A later failure without text doesn't keep the earlier text
+        diagnostics.begin("0xintent");
+        diagnostics.failed("0xintent", "insufficient_balance", None);
+
+        let json = serde_json::to_value(diagnostics.report("0xintent", false)).unwrap();
+        assert_eq!(json["last_error_code"], "insufficient_balance");
+        assert!(json.get("last_error").is_none());
+        assert!(!json.to_string().contains("have 5"));
+    }
+
+    #[test]
+    fn test_capacity_evicts_and_ignores_unknown() {
+        let diagnostics = IntentDiagnostics::new(2);
+        diagnostics.begin("0xa");
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        diagnostics.begin("0xb");
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        diagnostics.begin("0xc");
+        assert!(diagnostics.report("0xa", false).is_none());
+        assert!(diagnostics.report("0xb", false).is_some());
+        assert!(diagnostics.report("0xc", false).is_some());
+
+        // Updates without a begun attempt don't create records
+        diagnostics.failed("0xd", "other", Some("boom"));
+        assert!(diagnostics.report("0xd", false).is_none());
+    }
+}
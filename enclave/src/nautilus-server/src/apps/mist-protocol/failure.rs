@@ -0,0 +1,195 @@
+//! Machine-readable classification of failed swaps
+//!
+//! Failures reach the processor as `anyhow` errors whose text varies with
+//! the details. `classify` maps them onto a fixed set of codes that are put
+//! in failed `SwapExecutionResult`s and swap events, so dashboards can
+//! aggregate failure reasons. Typed `EnclaveError`s anywhere in the chain
+//! are used first; the rest is recognized by the messages this crate and
+//! the Sui effects produce.
+
+use crate::EnclaveError;
+
+/// Abort codes of `mist_protocol::execute_swap` (see `mist_protocol.move`)
+const E_INSUFFICIENT_BALANCE: u64 = 3;
+const E_DEADLINE_PASSED: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureCode {
+    /// Deadline passed, or too little time left to submit
+    Expired,
+    /// The pool can't fund the swap
+    InsufficientBalance,
+    /// The DEX output fell below the minimum
+    Slippage,
+    /// Any other Move abort or failed execution on-chain
+    OnchainAbort,
+    /// SEAL key fetching or decryption, or undecodable details
+    DecryptFailed,
+    /// Malformed or inconsistent intent data
+    InvalidInput,
+    /// Submission didn't complete in time
+    Timeout,
+    Other,
+}
+
+impl FailureCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Expired => "expired",
+            Self::InsufficientBalance => "insufficient_balance",
+            Self::Slippage => "slippage",
+            Self::OnchainAbort => "onchain_abort",
+            Self::DecryptFailed => "decrypt_failed",
+            Self::InvalidInput => "invalid_input",
+            Self::Timeout => "timeout",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Code for a failed intent
+pub fn classify(error: &anyhow::Error) -> FailureCode {
+    if let Some(enclave_error) = error.chain().find_map(|e| e.downcast_ref::<EnclaveError>()) {
+        return classify_enclave_error(enclave_error);
+    }
+
+    let message = error.to_string();
+    if message.starts_with("Intent expired") {
+        FailureCode::Expired
+    } else if message.starts_with("Transaction submission timed out") {
+        FailureCode::Timeout
+    } else if message.starts_with("Transaction failed") {
+        classify_onchain_failure(&message)
+    } else if [
+        "Invalid UTF-8 in encrypted_details",
+        "Failed to decode base64",
+        "Failed to parse SEAL encrypted object",
+        "Failed to parse decrypted details",
+        "No data decrypted",
+    ]
+    .iter()
+    .any(|prefix| message.starts_with(prefix))
+    {
+        FailureCode::DecryptFailed
+    } else {
+        FailureCode::Other
+    }
+}
+
+fn classify_enclave_error(error: &EnclaveError) -> FailureCode {
+    match error {
+        EnclaveError::DecryptionFailed(_)
+        | EnclaveError::SealVersionMismatch(_)
+        | EnclaveError::KeyServerUnavailable(_) => FailureCode::DecryptFailed,
+        EnclaveError::InvalidInput(msg) if msg.starts_with("insufficient balance") => FailureCode::InsufficientBalance,
+        EnclaveError::InvalidInput(msg) if msg.starts_with("intent expired") => FailureCode::Expired,
+        EnclaveError::InvalidInput(_) => FailureCode::InvalidInput,
+        EnclaveError::GenericError(msg) if msg.starts_with("insufficient time to execute before deadline") => {
+            FailureCode::Expired
+        }
+        EnclaveError::GenericError(_) | EnclaveError::Unauthorized(_) => FailureCode::Other,
+    }
+}
+
+/// Classify the execution status of a failed transaction, as rendered in
+/// `Transaction failed: Failure { error: "MoveAbort(MoveLocation { .. }, code) .." }`
+fn classify_onchain_failure(message: &str) -> FailureCode {
+    let Some(abort) = message.split("MoveAbort(").nth(1) else {
+        return FailureCode::OnchainAbort;
+    };
+    let in_module = |name: &str| abort.contains(&format!("Identifier(\\\"{}\\\")", name));
+    let code = abort_code(abort);
+
+    if in_module("mist_protocol") {
+        match code {
+            Some(E_DEADLINE_PASSED) => FailureCode::Expired,
+            Some(E_INSUFFICIENT_BALANCE) => FailureCode::InsufficientBalance,
+            _ => FailureCode::OnchainAbort,
+        }
+    } else if in_module("swap_router") {
+        // FlowX's router aborts when the output is below `min_amount_out`
+        FailureCode::Slippage
+    } else {
+        FailureCode::OnchainAbort
+    }
+}
+
+/// The code after the location: `MoveLocation { .. }, 5)`
+fn abort_code(abort: &str) -> Option<u64> {
+    let after_location = &abort[abort.rfind("}, ")? + 3..];
+    let digits: String = after_location.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Effects status of a transaction that aborted in `module` with `code`
+    fn move_abort(module: &str, code: u64) -> anyhow::Error {
+        anyhow::anyhow!(
+            "Transaction failed: Failure {{ error: \"MoveAbort(MoveLocation {{ module: ModuleId {{ address: 721e, \
+             name: Identifier(\\\"{}\\\") }}, function: 4, instruction: 41, function_name: Some(\\\"f\\\") }}, {}) \
+             in command 2\" }}",
+            module,
+            code
+        )
+    }
+
+    #[test]
+    fn test_codes_for_each_failure_class() {
+        let cases: Vec<(anyhow::Error, &str)> = vec![
+            (anyhow::anyhow!("Intent expired: deadline 1 < now 2"), "expired"),
+            (
+                EnclaveError::GenericError(
+                    "insufficient time to execute before deadline: 900ms left, need 10000ms".to_string(),
+                )
+                .into(),
+                "expired",
+            ),
+            (
+                EnclaveError::InvalidInput("intent expired: deadline 1000000 passed 500ms ago".to_string()).into(),
+                "expired",
+            ),
+            (move_abort("mist_protocol", E_DEADLINE_PASSED), "expired"),
+            (
+                EnclaveError::InvalidInput("insufficient balance: have 5 need 10".to_string()).into(),
+                "insufficient_balance",
+            ),
+            (move_abort("mist_protocol", E_INSUFFICIENT_BALANCE), "insufficient_balance"),
+            (move_abort("swap_router", 6), "slippage"),
+            (move_abort("mist_protocol", 1), "onchain_abort"),
+            (anyhow::anyhow!("Transaction failed: Failure {{ error: \"InsufficientGas\" }}"), "onchain_abort"),
+            (EnclaveError::DecryptionFailed("insufficient key shares".to_string()).into(), "decrypt_failed"),
+            (EnclaveError::SealVersionMismatch("0.5.11".to_string()).into(), "decrypt_failed"),
+            (EnclaveError::KeyServerUnavailable("timed out".to_string()).into(), "decrypt_failed"),
+            (anyhow::anyhow!("Failed to parse decrypted details: EOF"), "decrypt_failed"),
+            (EnclaveError::InvalidInput("input amount is zero: nothing to swap".to_string()).into(), "invalid_input"),
+            (
+                anyhow::anyhow!("Transaction submission timed out after 30s (it may still be executed)"),
+                "timeout",
+            ),
+            (anyhow::anyhow!("connection reset"), "other"),
+        ];
+
+        for (error, expected) in cases {
+            assert_eq!(classify(&error).as_str(), expected, "for {}", error);
+        }
+    }
+
+    #[test]
+    fn test_failed_result_has_code() {
+        let error = move_abort("swap_router", 6);
+        let result = super::super::SwapExecutionResult::failed("0xintent", &error);
+        assert!(!result.success && !result.executed);
+        assert_eq!(result.error_code.as_deref(), Some("slippage"));
+        assert_eq!(result.error, Some(error.to_string()));
+    }
+
+    #[test]
+    fn test_typed_error_found_behind_context() {
+        let error =
+            anyhow::Error::from(EnclaveError::DecryptionFailed("x".to_string())).context("processing 0xintent");
+        assert_eq!(classify(&error), FailureCode::DecryptFailed);
+    }
+}
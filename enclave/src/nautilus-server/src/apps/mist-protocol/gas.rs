@@ -0,0 +1,153 @@
+//! Gas coin selection for swap transactions
+//!
+//! The backend address accumulates SUI coins of very different sizes. Which
+//! ones pay for gas is set by `MIST_GAS_COIN_STRATEGY`:
+//! - `smallest` (default): the smallest single coin covering the gas budget,
+//!   keeping large coins intact
+//! - `largest`: the largest coin
+//! - `merge`: the smallest coins that together cover the budget. Sui merges
+//!   all gas payment coins into the first one, so small coins get
+//!   consolidated as a side effect.
+
+use crate::EnclaveError;
+use anyhow::Result;
+use std::str::FromStr;
+
+/// Environment variable selecting the gas coin strategy
+pub const GAS_COIN_STRATEGY_ENV: &str = "MIST_GAS_COIN_STRATEGY";
+
+/// Upper bound on gas payment coins in one transaction (Sui protocol limit)
+pub const MAX_GAS_PAYMENT_COINS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GasCoinStrategy {
+    #[default]
+    Smallest,
+    Largest,
+    Merge,
+}
+
+impl FromStr for GasCoinStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "smallest" => Ok(Self::Smallest),
+            "largest" => Ok(Self::Largest),
+            "merge" => Ok(Self::Merge),
+            _ => Err(anyhow::anyhow!("Unknown gas coin strategy: {} (smallest | largest | merge)", s)),
+        }
+    }
+}
+
+impl GasCoinStrategy {
+    /// Read the strategy from `MIST_GAS_COIN_STRATEGY`, defaulting to `Smallest`
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(GAS_COIN_STRATEGY_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Pick the gas payment from `(coin, balance)` pairs for `budget`.
+    /// Every strategy requires the selected coins to cover the budget.
+    pub fn select<T: Clone>(&self, coins: &[(T, u64)], budget: u64) -> Result<Vec<T>, EnclaveError> {
+        let mut by_balance: Vec<&(T, u64)> = coins.iter().collect();
+        by_balance.sort_by_key(|(_, balance)| *balance);
+
+        let selected: Vec<T> = match self {
+            Self::Smallest => by_balance
+                .iter()
+                .find(|(_, balance)| *balance >= budget)
+                .map(|(coin, _)| vec![coin.clone()])
+                .unwrap_or_default(),
+            Self::Largest => by_balance
+                .last()
+                .filter(|(_, balance)| *balance >= budget)
+                .map(|(coin, _)| vec![coin.clone()])
+                .unwrap_or_default(),
+            Self::Merge => {
+                let mut total = 0u64;
+                let mut picked = Vec::new();
+                for (coin, balance) in by_balance.iter().take(MAX_GAS_PAYMENT_COINS) {
+                    picked.push(coin.clone());
+                    total = total.saturating_add(*balance);
+                    if total >= budget {
+                        break;
+                    }
+                }
+                if total >= budget {
+                    picked
+                } else {
+                    Vec::new()
+                }
+            }
+        };
+
+        if selected.is_empty() {
+            let total: u64 = coins.iter().map(|(_, balance)| *balance).fold(0, u64::saturating_add);
+            return Err(EnclaveError::GenericError(format!(
+                "no gas payment covers budget {} with {:?} ({} coins, {} total)",
+                budget,
+                self,
+                coins.len(),
+                total
+            )));
+        }
+        Ok(selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COINS: [(&str, u64); 5] = [
+        ("big", 5_000_000_000),
+        ("dust", 1_000),
+        ("mid", 150_000_000),
+        ("small", 60_000_000),
+        ("just_enough", 100_000_000),
+    ];
+
+    #[test]
+    fn test_parse_strategies() {
+        assert_eq!("Smallest".parse::<GasCoinStrategy>().unwrap(), GasCoinStrategy::Smallest);
+        assert_eq!("largest".parse::<GasCoinStrategy>().unwrap(), GasCoinStrategy::Largest);
+        assert_eq!(" merge ".parse::<GasCoinStrategy>().unwrap(), GasCoinStrategy::Merge);
+        assert!("random".parse::<GasCoinStrategy>().is_err());
+        assert_eq!(GasCoinStrategy::default(), GasCoinStrategy::Smallest);
+    }
+
+    #[test]
+    fn test_smallest_sufficient_coin() {
+        let budget = 100_000_000;
+        assert_eq!(GasCoinStrategy::Smallest.select(&COINS, budget).unwrap(), vec!["just_enough"]);
+        assert_eq!(GasCoinStrategy::Smallest.select(&COINS, budget + 1).unwrap(), vec!["mid"]);
+        assert_eq!(GasCoinStrategy::Largest.select(&COINS, budget).unwrap(), vec!["big"]);
+    }
+
+    #[test]
+    fn test_merge_consolidates_small_coins() {
+        // dust + small + just_enough cover the budget; big and mid stay untouched
+        assert_eq!(
+            GasCoinStrategy::Merge.select(&COINS, 120_000_000).unwrap(),
+            vec!["dust", "small", "just_enough"]
+        );
+        // Stops as soon as the budget is covered
+        assert_eq!(GasCoinStrategy::Merge.select(&COINS, 50_000_000).unwrap(), vec!["dust", "small"]);
+    }
+
+    #[test]
+    fn test_insufficient_gas() {
+        let coins = [("a", 40_000_000), ("b", 50_000_000)];
+        assert!(GasCoinStrategy::Smallest.select(&coins, 100_000_000).is_err());
+        assert!(GasCoinStrategy::Largest.select(&coins, 100_000_000).is_err());
+        // Only merging reaches 90M, still short
+        assert!(GasCoinStrategy::Merge.select(&coins, 100_000_000).is_err());
+        assert_eq!(GasCoinStrategy::Merge.select(&coins, 90_000_000).unwrap(), vec!["a", "b"]);
+
+        let err = GasCoinStrategy::Smallest.select::<&str>(&[], 1).unwrap_err().to_string();
+        assert_eq!(err, "no gas payment covers budget 1 with Smallest (0 coins, 0 total)");
+    }
+}
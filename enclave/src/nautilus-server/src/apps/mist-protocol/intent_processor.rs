@@ -14,18 +14,34 @@
 //! SECURITY: Signature verification prevents attacks where attacker steals
 //! the nullifier but doesn't have the wallet private key.
 
-use super::{DecryptedSwapDetails, SwapIntentObject, ENCRYPTION_KEYS, SEAL_CONFIG};
-use crate::AppState;
+use super::diagnostics::IntentStage;
+use super::pending_age::PendingAgeMonitor;
+use super::rpc_endpoints::{RpcEndpoints, FAILOVER_THRESHOLD};
+use super::seal_version::SealFormat;
+use super::summary::ProcessorSummary;
+use super::{
+    DecryptedSwapDetails, SwapIntentObject, LOG_PRIVACY, MAX_DECRYPTED_BYTES, PENDING_AGE_ALERT,
+    PREFETCH_QUOTES, PROCESSING_ORDER, RPC_CONNECT_ATTEMPTS, SEAL_CONFIG, SEAL_DECRYPT_TIMEOUT, SEAL_HEADERS,
+    SEAL_HTTP_CLIENT, SEAL_MAX_RESPONSE_AGE, SEAL_SESSION_TTL, SUI_RPC_URLS, SUMMARY_INTERVAL,
+};
+use crate::{AppState, EnclaveError};
 use anyhow::Result;
+use std::future::Future;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{error, info};
+use tracing::{debug, error, info, warn};
 
 #[cfg(feature = "mist-protocol")]
 use sui_sdk::rpc_types::SuiObjectDataOptions;
 #[cfg(feature = "mist-protocol")]
 use sui_sdk::{SuiClient, SuiClientBuilder};
 
+/// Sui fullnode used by the processor and the CLI
+pub const SUI_RPC_URL: &str = "https://fullnode.testnet.sui.io:443";
+/// Network of `SUI_RPC_URL`, reported by ping
+pub const SUI_NETWORK: &str = "testnet";
+
 /// Main polling loop - runs continuously in background
 pub async fn start_intent_processor(state: Arc<AppState>) {
     println!("\n========================================");
@@ -36,24 +52,23 @@ pub async fn start_intent_processor(state: Arc<AppState>) {
     println!("Registry ID: {}", SEAL_CONFIG.registry_id);
     println!("Poll interval: 5 seconds\n");
 
-    // Initialize Sui client with retry logic
-    let sui_client = loop {
-        match SuiClientBuilder::default()
-            .build("https://fullnode.testnet.sui.io:443")
-            .await
-        {
-            Ok(client) => {
-                println!("Sui client initialized\n");
-                break client;
-            }
-            Err(e) => {
-                error!("Failed to create Sui client: {} - retrying in 5s...", e);
-                tokio::time::sleep(Duration::from_secs(5)).await;
-            }
+    // Initialize Sui client with bounded backoff, trying every configured
+    // endpoint; on giving up, the supervisor restarts the processor
+    let mut endpoints = RpcEndpoints::new(SUI_RPC_URLS.clone(), FAILOVER_THRESHOLD);
+    let mut sui_client = match endpoints.connect_with_retry(*RPC_CONNECT_ATTEMPTS, connect_sui).await {
+        Ok(client) => {
+            println!("Sui client initialized ({})\n", endpoints.current());
+            client
+        }
+        Err(e) => {
+            error!("Failed to create Sui client: {:#}", e);
+            return;
         }
     };
 
     let mut cycle_count = 0u64;
+    let mut summary = ProcessorSummary::new(*SUMMARY_INTERVAL);
+    let mut pending_age = PendingAgeMonitor::new(*PENDING_AGE_ALERT);
 
     loop {
         cycle_count += 1;
@@ -61,44 +76,195 @@ pub async fn start_intent_processor(state: Arc<AppState>) {
 
         // Query for pending SwapIntent objects
         match get_pending_swap_intents(&sui_client).await {
-            Ok(intents) => {
+            Ok(mut intents) => {
+                endpoints.record_success();
+                summary.set_pending(intents.len());
+                state.backlog.polled(intents.len(), unix_ms());
+                if let Some(alert) = pending_age.observe(intents.iter().map(|i| i.id.as_str())) {
+                    warn!("{}", alert);
+                }
+
                 if intents.is_empty() {
                     println!("No pending swap intents\n");
                 } else {
                     println!("Found {} swap intent(s)", intents.len());
+                    PROCESSING_ORDER.sort(&mut intents);
 
-                    for intent in intents {
-                        match process_swap_intent(&intent, &sui_client, &state).await {
+                    let sui_client = &sui_client;
+                    let state = &state;
+                    let outcome = run_cycle(intents, state, &mut summary, |intent| async move {
+                        state.intent_diagnostics.begin(&intent.id);
+                        match process_swap_intent(&intent, sui_client, state).await {
                             Ok(result) => {
-                                println!("\nSwap executed successfully!");
-                                println!("  Intent: {}", result.intent_id);
-                                println!("  Output: {} -> {}", result.output_amount, result.output_stealth);
-                                if result.remainder_amount > 0 {
-                                    println!(
-                                        "  Remainder: {} -> {}",
-                                        result.remainder_amount, result.remainder_stealth
-                                    );
-                                }
-                                if let Some(digest) = &result.tx_digest {
-                                    println!("  TX: {}", digest);
-                                }
+                                state.intent_diagnostics.succeeded(&intent.id);
+                                log_swap_result(&intent, &result);
+                                true
                             }
                             Err(e) => {
-                                error!("Failed to process intent {}: {}", intent.id, e);
+                                // Error text can quote decrypted amounts
+                                let code = super::failure::classify(&e);
+                                let detail = LOG_PRIVACY.is_full().then(|| e.to_string());
+                                state.intent_diagnostics.failed(&intent.id, code.as_str(), detail.as_deref());
+                                state.recent_failures.record(&intent.id, code.as_str(), unix_ms());
+                                log_swap_failure(&intent, &e);
+                                false
                             }
                         }
+                    })
+                    .await;
+
+                    if !outcome.skipped.is_empty() {
+                        warn!(
+                            "Processing paused - skipped {} intent(s): {}",
+                            outcome.skipped.len(),
+                            outcome.skipped.join(", ")
+                        );
                     }
                 }
             }
             Err(e) => {
-                error!("Failed to query swap intents: {}", e);
+                error!("Failed to query swap intents via {}: {}", endpoints.current(), e);
+                if let Some(next) = endpoints.record_failure() {
+                    warn!("Sui RPC failing repeatedly; failing over to {}", next);
+                    match endpoints.connect(connect_sui).await {
+                        Ok(client) => sui_client = client,
+                        Err(e) => error!("Sui RPC failover failed: {}", e),
+                    }
+                }
             }
         }
 
+        if let Some(report) = summary.maybe_emit() {
+            info!("Processor summary: {}", report);
+        }
+
         tokio::time::sleep(Duration::from_secs(5)).await;
     }
 }
 
+/// Current Unix time in ms, for processor bookkeeping
+fn unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+async fn connect_sui(url: String) -> Result<SuiClient> {
+    Ok(SuiClientBuilder::default().build(url).await?)
+}
+
+/// Keep a processor task running, re-spawning it if it exits or panics.
+///
+/// Every spawn gets the same `AppState`, so shared state such as the SEAL
+/// circuit breaker and the pause flag carries over across restarts.
+/// `max_restarts` bounds the loop (tests); `None` supervises forever.
+pub async fn supervise<F, Fut>(
+    state: Arc<AppState>,
+    restart_delay: Duration,
+    max_restarts: Option<usize>,
+    mut spawn_processor: F,
+) where
+    F: FnMut(Arc<AppState>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut restarts = 0usize;
+
+    loop {
+        match tokio::spawn(spawn_processor(state.clone())).await {
+            Ok(()) => warn!("Intent processor exited unexpectedly"),
+            Err(e) => error!("Intent processor panicked: {}", e),
+        }
+
+        if max_restarts.is_some_and(|max| restarts >= max) {
+            error!("Intent processor restart limit reached; giving up");
+            return;
+        }
+        restarts += 1;
+
+        warn!(
+            "Restarting intent processor in {:?} (restart #{}, SEAL breaker {:?})",
+            restart_delay,
+            restarts,
+            state.seal_breaker.state()
+        );
+        tokio::time::sleep(restart_delay).await;
+    }
+}
+
+/// Outcome of a single poll cycle
+#[derive(Debug, Default)]
+pub struct CycleOutcome {
+    /// Number of intents handed to the executor
+    pub executed: usize,
+    /// IDs of intents skipped because processing is paused
+    pub skipped: Vec<String>,
+}
+
+/// Run one poll cycle over the pending intents.
+/// When the processor is paused, intents are skipped (not executed) and
+/// reported back so the caller can log them. `execute` returns whether the
+/// intent succeeded; results and latencies feed the periodic summary.
+async fn run_cycle<F, Fut>(
+    intents: Vec<SwapIntentObject>,
+    state: &AppState,
+    summary: &mut ProcessorSummary,
+    mut execute: F,
+) -> CycleOutcome
+where
+    F: FnMut(SwapIntentObject) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let mut outcome = CycleOutcome::default();
+
+    for intent in intents {
+        // Checked per intent so a pause takes effect mid-cycle
+        if state.paused.load(Ordering::SeqCst) {
+            outcome.skipped.push(intent.id);
+            continue;
+        }
+
+        let started = tokio::time::Instant::now();
+        state.backlog.started();
+        let succeeded = execute(intent).await;
+        state.backlog.finished(succeeded);
+        if succeeded {
+            summary.record_success(started.elapsed());
+        } else {
+            summary.record_failure(started.elapsed());
+        }
+        outcome.executed += 1;
+    }
+
+    summary.record_skipped(outcome.skipped.len());
+    outcome
+}
+
+/// Log a successful swap execution and emit its `SwapEvent`, honouring
+/// MIST_LOG_PRIVACY
+fn log_swap_result(intent: &SwapIntentObject, result: &super::SwapExecutionResult) {
+    if let Some(line) = LOG_PRIVACY.format_swap_result(result) {
+        println!("\nSwap executed successfully! {}", line);
+    }
+    emit_swap_event(intent, result);
+}
+
+/// Log a failed intent with its failure class and emit a failed `SwapEvent`
+fn log_swap_failure(intent: &SwapIntentObject, error: &anyhow::Error) {
+    let result = super::SwapExecutionResult::failed(&intent.id, error);
+    let code = result.error_code.as_deref().unwrap_or_default();
+    error!("Failed to process intent {} ({}): {}", intent.id, code, error);
+    emit_swap_event(intent, &result);
+}
+
+fn emit_swap_event(intent: &SwapIntentObject, result: &super::SwapExecutionResult) {
+    let event = LOG_PRIVACY.swap_event(&intent.token_in, &intent.token_out, result);
+    match serde_json::to_string(&event) {
+        Ok(json) => info!(target: super::log_privacy::SWAP_EVENT_TARGET, "{}", json),
+        Err(e) => warn!("Failed to serialize swap event for {}: {}", result.intent_id, e),
+    }
+}
+
 /// Query for pending SwapIntent objects using events
 #[cfg(feature = "mist-protocol")]
 async fn get_pending_swap_intents(sui_client: &SuiClient) -> Result<Vec<SwapIntentObject>> {
@@ -110,7 +276,8 @@ async fn get_pending_swap_intents(sui_client: &SuiClient) -> Result<Vec<SwapInte
     // Query for SwapIntentCreatedEvent events from our package
     let event_type = format!("{}::mist_protocol::SwapIntentCreatedEvent", SEAL_CONFIG.package_id);
 
-    let mut intent_ids: Vec<String> = Vec::new();
+    // Intent IDs with their creation time, in event order
+    let mut intent_ids: Vec<(String, Option<u64>)> = Vec::new();
     let mut cursor = None;
 
     // Get recent events (last 100)
@@ -128,7 +295,7 @@ async fn get_pending_swap_intents(sui_client: &SuiClient) -> Result<Vec<SwapInte
         for event in &events.data {
             // Extract intent_id from event
             if let Some(intent_id) = extract_intent_id_from_event(event) {
-                intent_ids.push(intent_id);
+                intent_ids.push((intent_id, event.timestamp_ms));
             }
         }
 
@@ -143,39 +310,15 @@ async fn get_pending_swap_intents(sui_client: &SuiClient) -> Result<Vec<SwapInte
     // Now fetch each SwapIntent object and filter out consumed ones
     let mut intents = Vec::new();
 
-    for intent_id_str in intent_ids {
+    for (intent_id_str, created_at_ms) in intent_ids {
         let intent_id = match ObjectID::from_hex_literal(&intent_id_str) {
             Ok(id) => id,
             Err(_) => continue,
         };
 
         // Try to fetch the object - if it doesn't exist, it was already consumed
-        let obj_response = sui_client
-            .read_api()
-            .get_object_with_options(
-                intent_id,
-                SuiObjectDataOptions {
-                    show_type: true,
-                    show_owner: true,
-                    show_content: true,
-                    show_bcs: false,
-                    show_display: false,
-                    show_previous_transaction: false,
-                    show_storage_rebate: false,
-                },
-            )
-            .await;
-
-        match obj_response {
-            Ok(response) => {
-                if let Some(intent) = parse_swap_intent_object(&response) {
-                    intents.push(intent);
-                }
-            }
-            Err(_) => {
-                // Object doesn't exist or was consumed - skip
-                continue;
-            }
+        if let Ok(Some(intent)) = fetch_intent_object(sui_client, intent_id).await {
+            intents.push(SwapIntentObject { created_at_ms, ..intent });
         }
     }
 
@@ -183,6 +326,51 @@ async fn get_pending_swap_intents(sui_client: &SuiClient) -> Result<Vec<SwapInte
     Ok(intents)
 }
 
+/// Fetch a SwapIntent object. `Ok(None)` if it is gone (consumed) or unparseable.
+#[cfg(feature = "mist-protocol")]
+pub(crate) async fn fetch_intent_object(
+    sui_client: &SuiClient,
+    intent_id: sui_sdk::types::base_types::ObjectID,
+) -> Result<Option<SwapIntentObject>> {
+    let response = sui_client
+        .read_api()
+        .get_object_with_options(
+            intent_id,
+            SuiObjectDataOptions {
+                show_type: true,
+                show_owner: true,
+                show_content: true,
+                show_bcs: false,
+                show_display: false,
+                show_previous_transaction: false,
+                show_storage_rebate: false,
+            },
+        )
+        .await?;
+
+    Ok(parse_swap_intent_object(&response))
+}
+
+/// Fetch and process one intent by ID, outside the polling loop.
+/// Used by `mist-cli process <intent_id>` to replay a failed intent.
+#[cfg(feature = "mist-protocol")]
+pub async fn process_single_intent(
+    intent_id: &str,
+    sui_client: &SuiClient,
+    state: &AppState,
+) -> Result<super::SwapExecutionResult> {
+    use sui_sdk::types::base_types::ObjectID;
+
+    let object_id = ObjectID::from_hex_literal(intent_id)
+        .map_err(|e| anyhow::anyhow!("Invalid intent ID {}: {}", intent_id, e))?;
+
+    let intent = fetch_intent_object(sui_client, object_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Intent {} not found or already consumed", intent_id))?;
+
+    process_swap_intent(&intent, sui_client, state).await
+}
+
 /// Extract intent_id from SwapIntentCreatedEvent
 #[cfg(feature = "mist-protocol")]
 fn extract_intent_id_from_event(event: &sui_sdk::rpc_types::SuiEvent) -> Option<String> {
@@ -248,11 +436,13 @@ fn parse_swap_intent_object(
         token_in,
         token_out,
         deadline,
+        created_at_ms: None,
     })
 }
 
 /// Process a single swap intent
 #[cfg(feature = "mist-protocol")]
+#[tracing::instrument(name = "process_intent", skip_all, fields(intent_id = %intent.id))]
 async fn process_swap_intent(
     intent: &SwapIntentObject,
     sui_client: &SuiClient,
@@ -275,17 +465,35 @@ async fn process_swap_intent(
         ));
     }
 
-    // Decrypt the encrypted_details using SEAL
-    let details = decrypt_swap_details(&intent.encrypted_details, state).await?;
+    // Decrypt the encrypted_details using SEAL, reading the DEX pools for the
+    // (public) token pair meanwhile
+    let prefetch = if *PREFETCH_QUOTES {
+        super::swap_executor::prefetch_flowx_pools(sui_client, intent)
+    } else {
+        None
+    };
+    let (DecryptOutcome { details, key_servers }, prefetched_pools) =
+        super::prefetch::decrypt_with_prefetch(
+            decrypt_swap_details(&intent.encrypted_details, state),
+            prefetch,
+        )
+        .await?;
+    state.intent_diagnostics.reached(&intent.id, IntentStage::Decrypted);
+
+    if let Some(line) =
+        LOG_PRIVACY.format_intent_details(&intent.id, &intent.token_in, &intent.token_out, &details)
+    {
+        info!("  {}", line);
+    }
 
-    info!("  Decrypted nullifier: {}...", &details.nullifier[..20.min(details.nullifier.len())]);
-    info!("  Input amount: {}", details.input_amount);
-    info!("  Output stealth: {}...", &details.output_stealth[..20.min(details.output_stealth.len())]);
+    // Reject malformed nullifiers before they reach the spent-set / on-chain call
+    let nullifier = super::types::normalize_nullifier(&details.nullifier)?;
 
     // SECURITY: Verify wallet signature
     // This prevents attacks where attacker steals nullifier but not wallet key
     let signer_address = verify_intent_signature(&details)?;
     info!("  Signature verified! Signer: {}", signer_address);
+    state.intent_diagnostics.reached(&intent.id, IntentStage::Verified);
 
     // TODO: In production, we should also verify that signer_address matches
     // the ownerAddress stored in the deposit's encrypted data. This requires:
@@ -298,17 +506,54 @@ async fn process_swap_intent(
     // - Even if they steal the nullifier, they can't sign without the wallet
 
     // Execute the swap
-    let result = super::swap_executor::execute_swap_v2(
+    let mut result = super::swap_executor::execute_swap_v2(
         intent,
         &details,
+        &nullifier,
         sui_client,
         state,
+        prefetched_pools,
     )
     .await?;
+    result.seal_servers = key_servers;
+    let executed_at_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_millis() as u64;
+    result.stamp_times(intent, executed_at_ms);
+    if let Some(latency) = result.latency_ms() {
+        info!("  Created-to-executed latency: {}ms", latency);
+    }
+    // Simulated swaps moved no funds, so there is nothing to attest to
+    if result.success && result.executed {
+        let signed_at_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as u64;
+        use fastcrypto::traits::{KeyPair as _, ToFromBytes as _};
+        // Computed once per enclave key; unavailable outside a Nitro enclave
+        let attestation_hash = state
+            .attestation
+            .hash(state.eph_kp.public().as_bytes(), crate::common::attestation_document)
+            .map_err(|e| debug!("  No attestation hash for receipt: {}", e))
+            .ok();
+        result.receipt = Some(super::receipt::sign_swap_receipt(
+            &state.eph_kp,
+            &result,
+            attestation_hash,
+            signed_at_ms,
+        ));
+    }
 
     Ok(result)
 }
 
+/// Decrypted details plus the SEAL key servers whose shares were used
+#[derive(Debug, Clone)]
+pub struct DecryptOutcome {
+    pub details: DecryptedSwapDetails,
+    /// Key server object IDs (public identifiers only, no key material)
+    pub key_servers: Vec<String>,
+}
+
 #[cfg(not(feature = "mist-protocol"))]
 async fn process_swap_intent(
     _intent: &SwapIntentObject,
@@ -320,40 +565,66 @@ async fn process_swap_intent(
 
 /// Decrypt swap intent details using SEAL threshold encryption
 #[cfg(feature = "mist-protocol")]
-async fn decrypt_swap_details(
+pub(super) async fn decrypt_swap_details(
     encrypted_bytes: &[u8],
     state: &AppState,
-) -> Result<DecryptedSwapDetails> {
-    use seal_sdk::{seal_decrypt_all_objects, EncryptedObject};
-    use seal_sdk::types::FetchKeyResponse;
-    use seal_sdk::{signed_message, signed_request};
-    use sui_sdk_types::{Argument, Command, Identifier, Input, MoveCall, ObjectId, PersonalMessage, ProgrammableTransaction};
-    use fastcrypto::ed25519::Ed25519KeyPair;
-    use fastcrypto::traits::{KeyPair as _, Signer};
-    use fastcrypto::encoding::{Base64, Encoding};
-
+) -> Result<DecryptOutcome> {
     // The frontend stores encrypted_details as UTF-8 bytes of base64 string
     let encrypted_str = String::from_utf8(encrypted_bytes.to_vec())
         .map_err(|e| anyhow::anyhow!("Invalid UTF-8 in encrypted_details: {}", e))?;
 
     info!("  Encrypted details length: {} chars", encrypted_str.len());
 
-    // Try plain JSON first (for testing without SEAL)
+    // Never larger than the plaintext it decrypts to
+    super::bcs_guard::check_decrypted_size(encrypted_str.as_bytes(), *MAX_DECRYPTED_BYTES)?;
+
+    // trusted-input builds (dev and tests only) skip SEAL for plain JSON
+    #[cfg(feature = "trusted-input")]
     if let Ok(details) = serde_json::from_str::<DecryptedSwapDetails>(&encrypted_str) {
-        info!("  Parsed as plain JSON (test mode)");
-        return Ok(details);
+        warn!("  INSECURE: trusted-input build, intent details taken as plaintext without SEAL");
+        return Ok(DecryptOutcome { details, key_servers: Vec::new() });
     }
 
     // Decode base64 to get SEAL encrypted object bytes
     let seal_bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &encrypted_str)
         .map_err(|e| anyhow::anyhow!("Failed to decode base64: {}", e))?;
 
-    // Parse SEAL encrypted object
-    let encrypted_obj: EncryptedObject = bcs::from_bytes(&seal_bytes)
+    // Parse SEAL encrypted object (attacker-controlled bytes: bounded and panic-safe)
+    let encrypted_obj = super::bcs_guard::parse_encrypted_object(&seal_bytes)
         .map_err(|e| anyhow::anyhow!("Failed to parse SEAL encrypted object: {}", e))?;
 
     info!("  SEAL encryption ID: {}", hex::encode(&encrypted_obj.id));
 
+    // Unknown format versions fail here, before any SEAL round-trip
+    let format = SealFormat::from_version(encrypted_obj.version)?;
+
+    // A retry of the same intent (the same encrypted bytes) reuses the earlier
+    // decryption. The key fetch and decryption together are bounded by
+    // MIST_SEAL_DECRYPT_TIMEOUT_SECS.
+    state
+        .decrypt_cache
+        .get_or_decrypt(&seal_bytes, || match format {
+            SealFormat::V0 => {
+                super::seal_fetch::with_decrypt_timeout(*SEAL_DECRYPT_TIMEOUT, seal_decrypt(encrypted_obj, state))
+            }
+        })
+        .await
+}
+
+/// Fetch key shares from the SEAL servers and decrypt a version 0 `encrypted_obj`
+#[cfg(feature = "mist-protocol")]
+async fn seal_decrypt(
+    encrypted_obj: seal_sdk::EncryptedObject,
+    state: &AppState,
+) -> Result<DecryptOutcome> {
+    use seal_sdk::seal_decrypt_all_objects;
+    use seal_sdk::types::FetchKeyResponse;
+    use seal_sdk::{signed_message, signed_request};
+    use sui_sdk_types::{Argument, Command, Identifier, Input, MoveCall, ObjectId, ProgrammableTransaction};
+    use fastcrypto::ed25519::Ed25519KeyPair;
+    use fastcrypto::traits::{KeyPair as _, Signer};
+    use fastcrypto::encoding::{Base64, Encoding};
+
     // Create session key
     let session_key = Ed25519KeyPair::generate(&mut rand::thread_rng());
     let session_vk = session_key.public();
@@ -362,7 +633,7 @@ async fn decrypt_swap_details(
         .duration_since(std::time::UNIX_EPOCH)?
         .as_millis() as u64;
 
-    let ttl_min = 10;
+    let ttl_min = *SEAL_SESSION_TTL;
 
     let message = signed_message(
         SEAL_CONFIG.package_id.to_string(),
@@ -371,31 +642,17 @@ async fn decrypt_swap_details(
         ttl_min,
     );
 
-    // Sign with TEE key
-    let sui_private_key = {
-        let priv_key_bytes = state.eph_kp.as_ref();
-        let key_bytes: [u8; 32] = priv_key_bytes
-            .try_into()
-            .expect("Invalid private key length");
-        sui_crypto::ed25519::Ed25519PrivateKey::new(key_bytes)
-    };
-
-    // Sign with TEE key - returns UserSignature directly
-    let user_signature = {
-        use sui_crypto::SuiSigner;
-        sui_private_key
-            .sign_personal_message(&PersonalMessage(message.as_bytes().into()))
-            .map_err(|e| anyhow::anyhow!("Failed to sign: {}", e))?
-    };
+    // Sign with the backend key - returns UserSignature directly
+    let user_signature = state.signer.sign_personal_message(message.as_bytes()).await?;
 
     // Create certificate
     let certificate = seal_sdk::Certificate {
-        user: sui_private_key.public_key().to_address(),
+        user: state.signer.address(),
         session_vk: session_vk.clone(),
         creation_time,
         ttl_min,
         signature: user_signature,
-        mvr_name: None,
+        mvr_name: SEAL_CONFIG.mvr_name.clone(),
     };
 
     info!("  TEE address: {}", certificate.user);
@@ -420,105 +677,190 @@ async fn decrypt_swap_details(
         ],
     };
 
-    // Create fetch request
-    let (_enc_secret, enc_key, enc_verification_key) = &*ENCRYPTION_KEYS;
+    // Fresh ElGamal key for this fetch only: shares are encrypted to it and it
+    // is dropped after decryption, so a captured or replayed key server
+    // response can't be decrypted by any other request
+    let (enc_secret, enc_key, enc_verification_key) = seal_sdk::genkey(&mut rand::thread_rng());
 
-    let request_message = signed_request(&ptb, enc_key, enc_verification_key);
+    // The session key signs the request together with enc_key, binding the
+    // fetch to it
+    let request_message = signed_request(&ptb, &enc_key, &enc_verification_key);
     let request_signature = session_key.sign(&request_message);
 
     let fetch_request = seal_sdk::types::FetchKeyRequest {
         ptb: Base64::encode(bcs::to_bytes(&ptb).unwrap()),
-        enc_key: enc_key.clone(),
-        enc_verification_key: enc_verification_key.clone(),
+        enc_key,
+        enc_verification_key,
         request_signature,
         certificate,
     };
 
-    // Fetch keys from SEAL servers
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()?;
-
-    let mut responses: Vec<(ObjectId, FetchKeyResponse)> = Vec::new();
-
-    for server_id in &SEAL_CONFIG.key_servers {
-        let server_url = if server_id.to_string() == "0x73d05d62c18d9374e3ea529e8e0ed6161da1a141a94d3f76ae3fe4e99356db75" {
-            "https://seal-key-server-testnet-1.mystenlabs.com"
-        } else {
-            "https://seal-key-server-testnet-2.mystenlabs.com"
-        };
+    // Don't hit the key servers while the breaker is open
+    if !state.seal_breaker.allow_request() {
+        return Err(anyhow::anyhow!(
+            "SEAL circuit breaker open after {} consecutive failures",
+            state.seal_breaker.consecutive_failures()
+        ));
+    }
 
-        let url = format!("{}/v1/fetch_key", server_url);
-        info!("  Calling SEAL server: {}", server_url);
+    // Fetch keys from SEAL servers over the shared, pooled client
+    let client = SEAL_HTTP_CLIENT.clone();
 
-        // Use to_json_string for proper signature serialization
-        let request_body = fetch_request.to_json_string()
-            .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
+    // Use to_json_string for proper signature serialization
+    let request_body = fetch_request.to_json_string()
+        .map_err(|e| anyhow::anyhow!("Failed to serialize request: {}", e))?;
 
-        match client.post(&url)
-            .header("Client-Sdk-Version", "0.5.11")
-            .header("Content-Type", "application/json")
-            .body(request_body.clone())
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let status = response.status();
-                if status.is_success() {
-                    match response.json::<FetchKeyResponse>().await {
-                        Ok(fetch_response) => {
-                            info!("  Got key from {}", server_url);
-                            responses.push((*server_id, fetch_response));
-                        }
-                        Err(e) => {
-                            error!("  Failed to parse response: {}", e);
-                        }
+    // Query every server at once; stop as soon as the threshold is met
+    let threshold = encrypted_obj.threshold as usize;
+    // A server refusing our SDK version is reported as such, not as missing shares
+    let version_rejected: Arc<std::sync::Mutex<Option<String>>> = Arc::default();
+    let requests = SEAL_CONFIG
+        .key_servers
+        .iter()
+        .map(|server_id| {
+            // Every key server has a URL; SealConfig validation guarantees it
+            let server_url = SEAL_CONFIG.server_url_map[server_id].clone();
+            let fetch = fetch_key_from_server(
+                client.clone(),
+                server_url,
+                request_body.clone(),
+                encrypted_obj.id.clone(),
+                creation_time,
+            );
+            let version_rejected = version_rejected.clone();
+            let request = async move {
+                let result = fetch.await;
+                if let Err(e) = &result {
+                    if let Some(EnclaveError::SealVersionMismatch(msg)) = e.downcast_ref::<EnclaveError>() {
+                        *version_rejected.lock().unwrap() = Some(msg.clone());
                     }
-                } else {
-                    let error_body = response.text().await.unwrap_or_default();
-                    error!("  Server error {}: {}", status, error_body);
                 }
-            }
-            Err(e) => {
-                error!("  Connection failed: {}", e);
-            }
+                result
+            };
+            (*server_id, request)
+        })
+        .collect();
+
+    let responses: Vec<(ObjectId, FetchKeyResponse)> =
+        super::seal_fetch::collect_threshold(requests, threshold).await;
+
+    if let Err(failure) = super::seal_fetch::check_threshold(responses.len(), threshold) {
+        state.seal_breaker.record_failure();
+        if let Some(msg) = version_rejected.lock().unwrap().take() {
+            return Err(EnclaveError::SealVersionMismatch(msg).into());
         }
+        return Err(EnclaveError::from(failure).into());
     }
+    state.seal_breaker.record_success();
 
-    if responses.is_empty() {
-        return Err(anyhow::anyhow!("Failed to fetch keys from any SEAL server"));
-    }
+    let key_servers = super::seal_fetch::contributing_servers(&responses);
+    info!("  Key shares from: {}", key_servers.join(", "));
 
     info!("  Got {} key responses", responses.len());
 
     // Decrypt
+    let objects = [encrypted_obj];
     let decrypted_results = seal_decrypt_all_objects(
-        _enc_secret,
+        &enc_secret,
         &responses,
-        &[encrypted_obj],
+        &objects,
         &SEAL_CONFIG.server_pk_map,
     )
-    .map_err(|e| anyhow::anyhow!("SEAL decryption failed: {}", e))?;
+    .map_err(|e| EnclaveError::from(super::seal_fetch::SealFailure::CombineFailed(e.to_string())))?;
 
-    if decrypted_results.is_empty() {
+    // One plaintext per object, or none are used
+    let decrypted = super::seal_fetch::pair_decrypted(&objects, decrypted_results).map_err(EnclaveError::from)?;
+    let Some((_, decrypted_bytes)) = decrypted.into_iter().next() else {
         return Err(anyhow::anyhow!("No data decrypted"));
-    }
+    };
 
-    // Parse decrypted JSON
-    let decrypted_bytes = &decrypted_results[0];
-    let details: DecryptedSwapDetails = serde_json::from_slice(decrypted_bytes)
+    // Parse decrypted JSON (attacker-chosen plaintext: bounded first)
+    super::bcs_guard::check_decrypted_size(&decrypted_bytes, *MAX_DECRYPTED_BYTES)?;
+    let details: DecryptedSwapDetails = serde_json::from_slice(&decrypted_bytes)
         .map_err(|e| anyhow::anyhow!("Failed to parse decrypted details: {}", e))?;
 
     info!("  Successfully decrypted swap details");
 
-    Ok(details)
+    Ok(DecryptOutcome { details, key_servers })
+}
+
+/// Build the HTTP client shared by all SEAL key server requests.
+///
+/// Idle connections are kept per server, so consecutive decryptions skip the
+/// TCP and TLS handshakes. HTTP/2 is negotiated through ALPN on servers that
+/// offer it, otherwise requests stay on keep-alive HTTP/1.1.
+#[cfg(feature = "mist-protocol")]
+pub fn seal_http_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(8)
+        .tcp_keepalive(Duration::from_secs(30))
+        .http2_keep_alive_interval(Duration::from_secs(30))
+        .http2_keep_alive_while_idle(true)
+        .http2_adaptive_window(true)
+        .build()
+}
+
+/// POST a fetch_key request to one SEAL key server. The response must be
+/// bound to this request (encryption ID, session age) or it is rejected.
+#[cfg(feature = "mist-protocol")]
+async fn fetch_key_from_server(
+    client: reqwest::Client,
+    server_url: String,
+    request_body: String,
+    encryption_id: Vec<u8>,
+    session_created_ms: u64,
+) -> Result<seal_sdk::types::FetchKeyResponse> {
+    let url = format!("{}/v1/fetch_key", server_url);
+    if SEAL_HEADERS.is_empty() {
+        info!("  Calling SEAL server: {}", server_url);
+    } else {
+        info!("  Calling SEAL server: {} (extra headers: {:?})", server_url, *SEAL_HEADERS);
+    }
+
+    let response = SEAL_HEADERS.apply(client.post(&url))
+        .header("Client-Sdk-Version", super::seal_fetch::SEAL_SDK_VERSION)
+        .header("Content-Type", "application/json")
+        .body(request_body)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Connection to {} failed: {}", server_url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        if let Some(mismatch) = super::seal_fetch::sdk_version_mismatch(status.as_u16(), &error_body) {
+            return Err(mismatch.into());
+        }
+        return Err(anyhow::anyhow!("Server error {}: {}", status, error_body));
+    }
+
+    let fetch_response: seal_sdk::types::FetchKeyResponse = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to parse response from {}: {}", server_url, e))?;
+
+    let received_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_millis() as u64;
+    super::seal_fetch::verify_response_binding(
+        &encryption_id,
+        fetch_response.decryption_keys.iter().map(|k| k.id.as_slice()),
+        session_created_ms,
+        received_ms,
+        *SEAL_MAX_RESPONSE_AGE,
+    )?;
+
+    info!("  Got key from {}", server_url);
+    Ok(fetch_response)
 }
 
 #[cfg(not(feature = "mist-protocol"))]
 async fn decrypt_swap_details(
     _encrypted_bytes: &[u8],
     _state: &AppState,
-) -> Result<DecryptedSwapDetails> {
+) -> Result<DecryptOutcome> {
     Err(anyhow::anyhow!("mist-protocol feature not enabled"))
 }
 
@@ -528,7 +870,7 @@ async fn decrypt_swap_details(
 /// SECURITY: This prevents attacks where attacker steals nullifier but not wallet key.
 /// The signature proves the wallet owner authorized this specific swap.
 #[cfg(feature = "mist-protocol")]
-fn verify_intent_signature(details: &DecryptedSwapDetails) -> Result<String> {
+pub(super) fn verify_intent_signature(details: &DecryptedSwapDetails) -> Result<String> {
     use fastcrypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
     use fastcrypto::secp256k1::{Secp256k1PublicKey, Secp256k1Signature};
     use fastcrypto::secp256r1::{Secp256r1PublicKey, Secp256r1Signature};
@@ -546,9 +888,12 @@ fn verify_intent_signature(details: &DecryptedSwapDetails) -> Result<String> {
         details.remainder_stealth
     );
 
-    println!("=== SIGNATURE VERIFICATION DEBUG ===");
-    println!("Full message: {}", message);
-    println!("Signature base64: {}", &details.signature);
+    // The signed message embeds amounts and stealth addresses
+    if LOG_PRIVACY.is_full() {
+        println!("=== SIGNATURE VERIFICATION DEBUG ===");
+        println!("Full message: {}", message);
+        println!("Signature base64: {}", &details.signature);
+    }
 
     // Decode the base64 signature from wallet
     // Sui wallet signature format: flag (1 byte) || signature || public_key
@@ -559,12 +904,13 @@ fn verify_intent_signature(details: &DecryptedSwapDetails) -> Result<String> {
         return Err(anyhow::anyhow!("Empty signature"));
     }
 
-    println!("Decoded sig length: {}", signature_bytes.len());
-
     let scheme_flag = signature_bytes[0];
     let sig_data = &signature_bytes[1..];
 
-    println!("Scheme flag: 0x{:02x}, sig_data length: {}", scheme_flag, sig_data.len());
+    if LOG_PRIVACY.is_full() {
+        println!("Decoded sig length: {}", signature_bytes.len());
+        println!("Scheme flag: 0x{:02x}, sig_data length: {}", scheme_flag, sig_data.len());
+    }
 
     // Create personal message with intent scope
     // Sui intent format: [scope, version, app_id] || bcs_encoded_message
@@ -579,9 +925,11 @@ fn verify_intent_signature(details: &DecryptedSwapDetails) -> Result<String> {
         data.extend_from_slice(&bcs_encoded);
         data
     };
-    println!("Intent message (first 20 bytes): {:?}", &intent_message[..20.min(intent_message.len())]);
     let digest = fastcrypto::hash::Blake2b256::digest(&intent_message);
-    println!("Digest: {}", hex::encode(digest.as_ref()));
+    if LOG_PRIVACY.is_full() {
+        println!("Intent message (first 20 bytes): {:?}", &intent_message[..20.min(intent_message.len())]);
+        println!("Digest: {}", hex::encode(digest.as_ref()));
+    }
 
     // Verify based on signature scheme
     // 0x00 = Ed25519, 0x01 = Secp256k1, 0x02 = Secp256r1
@@ -670,6 +1018,8 @@ fn verify_intent_signature(_details: &DecryptedSwapDetails) -> Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::mock_seal_server::MockSealServer;
+    use axum::http::StatusCode;
 
     #[test]
     fn test_parse_json_details() {
@@ -680,4 +1030,241 @@ mod tests {
         assert_eq!(details.input_amount, "1000");
         assert_eq!(details.signature, "AAAA");
     }
+
+    fn test_state() -> AppState {
+        use fastcrypto::traits::KeyPair;
+        AppState::new(
+            fastcrypto::ed25519::Ed25519KeyPair::generate(&mut rand::thread_rng()),
+            String::new(),
+        )
+    }
+
+    fn test_intent(id: &str) -> SwapIntentObject {
+        SwapIntentObject {
+            id: id.to_string(),
+            encrypted_details: vec![],
+            token_in: "0x2::sui::SUI".to_string(),
+            token_out: "0x2::sui::SUI".to_string(),
+            deadline: u64::MAX,
+            created_at_ms: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_plaintext_is_rejected_before_parsing() {
+        let state = test_state();
+        let details = serde_json::json!({
+            "nullifier": format!("0x{}", "ab".repeat(4096)),
+            "inputAmount": "100",
+            "outputStealth": "0x02",
+            "remainderStealth": "0x03",
+            "signature": "",
+        });
+
+        let err = decrypt_swap_details(details.to_string().as_bytes(), &state).await.unwrap_err();
+        match err.downcast_ref::<EnclaveError>() {
+            Some(EnclaveError::DecryptionFailed(msg)) => assert!(msg.starts_with("decrypted details too large")),
+            other => panic!("expected DecryptionFailed, got {:?}", other),
+        }
+    }
+
+    #[cfg(not(feature = "trusted-input"))]
+    #[tokio::test]
+    async fn test_plaintext_details_need_trusted_input() {
+        use fastcrypto::traits::KeyPair;
+
+        let state = test_state();
+        let wallet = fastcrypto::ed25519::Ed25519KeyPair::generate(&mut rand::thread_rng());
+        let (details, _) = super::super::canary::canary_details(&wallet);
+        let plaintext = serde_json::to_vec(&details).unwrap();
+
+        let err = decrypt_swap_details(&plaintext, &state).await.unwrap_err();
+        assert!(err.to_string().starts_with("Failed to decode base64"), "{}", err);
+    }
+
+    #[cfg(feature = "trusted-input")]
+    #[tokio::test]
+    async fn test_trusted_input_runs_validation_without_seal() {
+        use fastcrypto::traits::KeyPair;
+
+        // No key servers behind this state: SEAL is never reached
+        let state = test_state();
+        let wallet = fastcrypto::ed25519::Ed25519KeyPair::generate(&mut rand::thread_rng());
+        let (details, address) = super::super::canary::canary_details(&wallet);
+        let plaintext = serde_json::to_vec(&details).unwrap();
+
+        let outcome = decrypt_swap_details(&plaintext, &state).await.unwrap();
+        assert!(outcome.key_servers.is_empty());
+        assert_eq!(outcome.details.nullifier, details.nullifier);
+        super::super::types::normalize_nullifier(&outcome.details.nullifier).unwrap();
+        assert_eq!(verify_intent_signature(&outcome.details).unwrap(), address);
+        assert_eq!(super::super::balance::parse_input_amount(&outcome.details.input_amount).unwrap(), 1);
+
+        // Tampered details still fail validation
+        let mut tampered = details;
+        tampered.input_amount = "2".to_string();
+        let outcome = decrypt_swap_details(&serde_json::to_vec(&tampered).unwrap(), &state).await.unwrap();
+        assert!(verify_intent_signature(&outcome.details).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_paused_processor_skips_execution() {
+        use std::sync::atomic::AtomicUsize;
+
+        let state = test_state();
+        let mut summary = ProcessorSummary::new(Duration::ZERO);
+        let executed = AtomicUsize::new(0);
+        let intents = vec![test_intent("0x1"), test_intent("0x2")];
+
+        // Paused: nothing executes, every intent is reported as skipped
+        state.paused.store(true, Ordering::SeqCst);
+        let outcome = run_cycle(intents.clone(), &state, &mut summary, |_| {
+            executed.fetch_add(1, Ordering::SeqCst);
+            async { true }
+        })
+        .await;
+        assert_eq!(executed.load(Ordering::SeqCst), 0);
+        assert_eq!(outcome.executed, 0);
+        assert_eq!(outcome.skipped, vec!["0x1".to_string(), "0x2".to_string()]);
+
+        // Resumed: everything executes
+        state.paused.store(false, Ordering::SeqCst);
+        let outcome = run_cycle(intents, &state, &mut summary, |_| {
+            executed.fetch_add(1, Ordering::SeqCst);
+            async { true }
+        })
+        .await;
+        assert_eq!(executed.load(Ordering::SeqCst), 2);
+        assert_eq!(outcome.executed, 2);
+        assert!(outcome.skipped.is_empty());
+
+        let report = summary.maybe_emit().unwrap();
+        assert_eq!((report.processed, report.failed, report.skipped), (2, 0, 2));
+
+        // Skipped intents never count as in flight or finished
+        let status = state.backlog.snapshot();
+        assert_eq!((status.in_flight_count, status.processed_total, status.failed_total), (0, 2, 0));
+    }
+
+    #[tokio::test]
+    async fn test_breaker_survives_processor_respawn() {
+        use crate::circuit_breaker::{BreakerState, CircuitBreaker};
+        use std::sync::Mutex;
+
+        let mut state = test_state();
+        state.seal_breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let state = Arc::new(state);
+        let observed = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = observed.clone();
+        supervise(state.clone(), Duration::ZERO, Some(1), move |state| {
+            let seen = seen.clone();
+            async move {
+                // Record what this incarnation sees on startup
+                seen.lock().unwrap().push(state.seal_breaker.state());
+                state.seal_breaker.record_failure();
+                state.seal_breaker.record_failure();
+                panic!("simulated processor crash");
+            }
+        })
+        .await;
+
+        // The first spawn starts closed; the re-spawned one sees it still open
+        assert_eq!(
+            *observed.lock().unwrap(),
+            vec![BreakerState::Closed, BreakerState::Open]
+        );
+        assert!(!state.seal_breaker.allow_request());
+    }
+
+    fn session_now_ms() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    #[tokio::test]
+    async fn test_key_server_error_is_reported() {
+        let server = MockSealServer::builder()
+            .fail_with(StatusCode::FORBIDDEN, "policy denied")
+            .start()
+            .await;
+
+        let err = fetch_key_from_server(
+            reqwest::Client::new(),
+            server.url.clone(),
+            r#"{"ptb":"AA=="}"#.to_string(),
+            vec![1, 2, 3],
+            session_now_ms(),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("Server error 403"), "{}", err);
+        assert!(err.contains("policy denied"), "{}", err);
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_server_response_without_keys_is_rejected() {
+        let server = MockSealServer::builder()
+            .fail_with(StatusCode::OK, r#"{"decryption_keys":[]}"#)
+            .start()
+            .await;
+
+        let url = server.url.clone();
+        let err = fetch_key_from_server(reqwest::Client::new(), url, "{}".to_string(), vec![1, 2, 3], session_now_ms())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("returned no keys"), "{}", err);
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_server_version_mismatch_is_typed() {
+        let server = MockSealServer::builder().require_sdk_version("0.6.0").start().await;
+
+        let url = server.url.clone();
+        let err = fetch_key_from_server(reqwest::Client::new(), url, "{}".to_string(), vec![1, 2, 3], session_now_ms())
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<EnclaveError>() {
+            Some(EnclaveError::SealVersionMismatch(msg)) => {
+                assert!(msg.contains("Client-Sdk-Version 0.5.11"), "{}", msg);
+                assert!(msg.contains("DeprecatedSDKVersion"), "{}", msg);
+            }
+            other => panic!("expected SealVersionMismatch, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seal_client_reuses_connections() {
+        let server = MockSealServer::builder()
+            .fail_with(StatusCode::FORBIDDEN, "policy denied")
+            .start()
+            .await;
+
+        // Sequential decryptions share one pooled connection
+        let url = &server.url;
+        let client = seal_http_client().unwrap();
+        for _ in 0..5 {
+            let result =
+                fetch_key_from_server(client.clone(), url.clone(), "{}".to_string(), vec![1], session_now_ms()).await;
+            assert!(result.is_err());
+        }
+        assert_eq!(server.requests(), 5);
+        assert_eq!(server.connections(), 1);
+
+        // A client per request, as before, connects every time
+        for _ in 0..3 {
+            let client = seal_http_client().unwrap();
+            let result =
+                fetch_key_from_server(client, url.clone(), "{}".to_string(), vec![1], session_now_ms()).await;
+            assert!(result.is_err());
+        }
+        assert_eq!(server.connections(), 4);
+    }
 }
@@ -0,0 +1,88 @@
+//! Latency percentiles for intent processing benchmarks
+//!
+//! Used by the `bench`-feature benchmark (`tests/intent_latency.rs`) to
+//! report p50/p95 per-intent latency and hold it to a budget
+//! (`MIST_BENCH_P95_BUDGET_MS`).
+
+use anyhow::Result;
+use std::fmt;
+use std::time::Duration;
+
+/// Environment variable overriding the benchmark's p95 budget in milliseconds
+pub const BENCH_P95_BUDGET_ENV: &str = "MIST_BENCH_P95_BUDGET_MS";
+
+/// Read the p95 budget from `MIST_BENCH_P95_BUDGET_MS`, falling back to `default`
+pub fn p95_budget_from_env(default: Duration) -> Result<Duration> {
+    match std::env::var(BENCH_P95_BUDGET_ENV) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(ms) if ms > 0 => Ok(Duration::from_millis(ms)),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", BENCH_P95_BUDGET_ENV, value),
+        },
+        Err(_) => Ok(default),
+    }
+}
+
+/// Percentiles over a set of latency samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Nearest-rank percentiles; `None` without samples
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        Some(Self {
+            count: samples.len(),
+            p50: percentile(&samples, 50),
+            p95: percentile(&samples, 95),
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty samples
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl fmt::Display for LatencyStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "n={} p50={}ms p95={}ms max={}ms",
+            self.count,
+            self.p50.as_millis(),
+            self.p95.as_millis(),
+            self.max.as_millis()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let samples: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_samples(samples).unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p95, Duration::from_millis(95));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.to_string(), "n=100 p50=50ms p95=95ms max=100ms");
+
+        let single = LatencyStats::from_samples(vec![Duration::from_millis(7)]).unwrap();
+        assert_eq!((single.p50, single.p95), (Duration::from_millis(7), Duration::from_millis(7)));
+
+        assert!(LatencyStats::from_samples(Vec::new()).is_none());
+    }
+}
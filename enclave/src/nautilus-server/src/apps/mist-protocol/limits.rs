@@ -0,0 +1,101 @@
+//! Sanity caps on computed swap outputs
+//!
+//! Defense in depth: a decryption or parsing bug could produce an absurd
+//! output amount. Anything above the configured cap is rejected before the
+//! transaction is signed, so the enclave never authorizes it on-chain.
+//!
+//! - `MIST_MAX_OUTPUT_AMOUNT`: absolute cap on output + remainder (base units
+//!   of the output token)
+//! - `MIST_MAX_OUTPUT_MULTIPLE`: cap on output as a multiple of the input amount,
+//!   i.e. the highest plausible price
+//!
+//! Mixer swaps check their SUI output; FlowX swaps check the quoted output.
+
+use crate::EnclaveError;
+
+/// Environment variable for the absolute output cap
+pub const MAX_OUTPUT_AMOUNT_ENV: &str = "MIST_MAX_OUTPUT_AMOUNT";
+/// Environment variable for the output/input multiple cap
+pub const MAX_OUTPUT_MULTIPLE_ENV: &str = "MIST_MAX_OUTPUT_MULTIPLE";
+
+/// Output caps; `None` disables a check
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputCap {
+    pub max_amount: Option<u64>,
+    pub max_multiple: Option<u64>,
+}
+
+impl OutputCap {
+    /// Read both caps from the environment. Unset means no cap; a value that
+    /// isn't a positive integer is an error.
+    pub fn from_env() -> Result<Self, EnclaveError> {
+        Ok(Self {
+            max_amount: read_env_u64(MAX_OUTPUT_AMOUNT_ENV)?,
+            max_multiple: read_env_u64(MAX_OUTPUT_MULTIPLE_ENV)?,
+        })
+    }
+
+    /// Reject `output` if it exceeds either cap. An output exactly at the cap passes.
+    pub fn check(&self, output: u64, input: u64) -> Result<(), EnclaveError> {
+        if let Some(max) = self.max_amount {
+            if output > max {
+                return Err(EnclaveError::GenericError(format!(
+                    "Output amount {} exceeds {} ({})",
+                    output, MAX_OUTPUT_AMOUNT_ENV, max
+                )));
+            }
+        }
+
+        if let Some(multiple) = self.max_multiple {
+            let limit = input.saturating_mul(multiple);
+            if output > limit {
+                return Err(EnclaveError::GenericError(format!(
+                    "Output amount {} exceeds {}x input {} ({})",
+                    output, multiple, input, MAX_OUTPUT_MULTIPLE_ENV
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn read_env_u64(name: &str) -> Result<Option<u64>, EnclaveError> {
+    match std::env::var(name) {
+        Ok(value) => match value.trim().parse::<u64>() {
+            Ok(v) if v > 0 => Ok(Some(v)),
+            _ => Err(EnclaveError::InvalidInput(format!(
+                "{} must be a positive integer, got {:?}",
+                name, value
+            ))),
+        },
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_cap() {
+        let cap = OutputCap { max_amount: Some(1_000), max_multiple: None };
+        assert!(cap.check(999, 1).is_ok());
+        assert!(cap.check(1_000, 1).is_ok());
+        assert!(matches!(cap.check(1_001, 1), Err(EnclaveError::GenericError(_))));
+    }
+
+    #[test]
+    fn test_multiple_cap() {
+        let cap = OutputCap { max_amount: None, max_multiple: Some(10) };
+        assert!(cap.check(500, 50).is_ok());
+        assert!(matches!(cap.check(501, 50), Err(EnclaveError::GenericError(_))));
+        // No overflow on large inputs
+        assert!(cap.check(u64::MAX, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_no_cap_by_default() {
+        assert!(OutputCap::default().check(u64::MAX, 0).is_ok());
+    }
+}
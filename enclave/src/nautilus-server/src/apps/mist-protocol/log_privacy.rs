@@ -0,0 +1,298 @@
+//! Privacy levels for logging decrypted intent data
+//!
+//! Decrypted intents carry amounts, stealth addresses and nullifiers; logging
+//! them verbatim defeats the point of encrypting them. `MIST_LOG_PRIVACY`
+//! controls what ends up in the logs:
+//! - `full`: everything (local debugging only)
+//! - `redacted` (default): intent ID, venue and token symbols only. Amounts
+//!   and addresses are left out rather than hashed: an amount has so little
+//!   entropy that its hash is as good as the plaintext
+//! - `none`: nothing about decrypted contents is logged
+//!
+//! The same rules apply to the per-intent `SwapEvent`s the processor emits.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::str::FromStr;
+
+use super::{DecryptedSwapDetails, SwapExecutionResult};
+
+/// Tracing target for per-intent swap events (`RUST_LOG=mist_events=info`)
+pub const SWAP_EVENT_TARGET: &str = "mist_events";
+
+/// Environment variable selecting the log privacy level
+pub const LOG_PRIVACY_ENV: &str = "MIST_LOG_PRIVACY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogPrivacy {
+    Full,
+    #[default]
+    Redacted,
+    None,
+}
+
+impl FromStr for LogPrivacy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "redacted" => Ok(Self::Redacted),
+            "none" => Ok(Self::None),
+            _ => Err(anyhow::anyhow!("Unknown log privacy level: {}", s)),
+        }
+    }
+}
+
+impl LogPrivacy {
+    /// Read the level from `MIST_LOG_PRIVACY`, defaulting to `Redacted`
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(LOG_PRIVACY_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Whether sensitive debug output (signed messages, signatures) may be printed
+    pub fn is_full(&self) -> bool {
+        *self == Self::Full
+    }
+
+    /// Summary line for a freshly decrypted intent, or `None` if nothing should be logged
+    pub fn format_intent_details(
+        &self,
+        intent_id: &str,
+        token_in: &str,
+        token_out: &str,
+        details: &DecryptedSwapDetails,
+    ) -> Option<String> {
+        let line = format!("Intent {} ({} -> {})", intent_id, token_symbol(token_in), token_symbol(token_out));
+        match self {
+            Self::None => None,
+            Self::Redacted => Some(line),
+            Self::Full => Some(format!(
+                "{}: nullifier={} input={} output_stealth={} remainder_stealth={}",
+                line, details.nullifier, details.input_amount, details.output_stealth, details.remainder_stealth,
+            )),
+        }
+    }
+
+    /// Summary line for an executed swap, or `None` if nothing should be logged
+    pub fn format_swap_result(&self, result: &SwapExecutionResult) -> Option<String> {
+        if *self == Self::None {
+            return None;
+        }
+        let mut line = format!("Intent {} via {}", result.intent_id, result.venue);
+        if self.is_full() {
+            line.push_str(&format!(": output {} -> {}", result.output_amount, result.output_stealth));
+            if result.remainder_amount > 0 {
+                line.push_str(&format!(
+                    ", remainder {} -> {}",
+                    result.remainder_amount, result.remainder_stealth
+                ));
+            }
+        }
+        if let Some(digest) = &result.tx_digest {
+            line.push_str(&format!(", tx {}", digest));
+        }
+        if result.simulated {
+            line.push_str(" (simulated, not submitted)");
+        }
+        // Key server IDs are public; logged at every level for audit
+        if !result.seal_servers.is_empty() {
+            line.push_str(&format!(", seal servers [{}]", result.seal_servers.join(", ")));
+        }
+        Some(line)
+    }
+
+    /// Per-intent event for an executed or failed swap. Amounts and stealth
+    /// addresses are only included at `full` (and only for successful swaps);
+    /// `none` still emits the event, since nothing in it comes from the
+    /// decrypted details.
+    pub fn swap_event(&self, token_in: &str, token_out: &str, result: &SwapExecutionResult) -> SwapEvent {
+        let full = self.is_full() && result.success;
+        SwapEvent {
+            intent_id: result.intent_id.clone(),
+            venue: result.venue.clone(),
+            token_in: token_symbol(token_in).to_string(),
+            token_out: token_symbol(token_out).to_string(),
+            tx_digest: result.tx_digest.clone(),
+            simulated: result.simulated,
+            error_code: result.error_code.clone(),
+            output_amount: full.then_some(result.output_amount),
+            remainder_amount: full.then_some(result.remainder_amount),
+            output_stealth: full.then(|| result.output_stealth.clone()),
+            remainder_stealth: full.then(|| result.remainder_stealth.clone()),
+        }
+    }
+}
+
+/// Structured record of one executed swap, emitted as JSON under
+/// `SWAP_EVENT_TARGET`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapEvent {
+    pub intent_id: String,
+    pub venue: String,
+    pub token_in: String,
+    pub token_out: String,
+    pub tx_digest: Option<String>,
+    pub simulated: bool,
+    /// Set for failed intents; the error text itself is left out since it
+    /// may quote decrypted amounts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_amount: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remainder_amount: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_stealth: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remainder_stealth: Option<String>,
+}
+
+/// Last path segment of a Move type, e.g. `0x2::sui::SUI` -> `SUI`
+pub fn token_symbol(coin_type: &str) -> &str {
+    coin_type.rsplit("::").next().unwrap_or(coin_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details() -> DecryptedSwapDetails {
+        DecryptedSwapDetails {
+            nullifier: "0x".to_string() + &"ab".repeat(32),
+            input_amount: "123456789".to_string(),
+            output_stealth: "0xoutputstealth".to_string(),
+            remainder_stealth: "0xremainderstealth".to_string(),
+            signature: "AAAA".to_string(),
+        }
+    }
+
+    fn result() -> SwapExecutionResult {
+        SwapExecutionResult {
+            success: true,
+            intent_id: "0xintent".to_string(),
+            nullifier_hash: String::new(),
+            output_amount: 987654321,
+            remainder_amount: 55555,
+            output_stealth: "0xoutputstealth".to_string(),
+            remainder_stealth: "0xremainderstealth".to_string(),
+            venue: "flowx".to_string(),
+            tx_digest: Some("digest".to_string()),
+            error: None,
+            error_code: None,
+            executed: true,
+            simulated: false,
+            seal_servers: vec!["0xserver1".to_string()],
+            receipt: None,
+            created_at_ms: None,
+            executed_at_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_levels() {
+        assert_eq!("Full".parse::<LogPrivacy>().unwrap(), LogPrivacy::Full);
+        assert_eq!("redacted".parse::<LogPrivacy>().unwrap(), LogPrivacy::Redacted);
+        assert_eq!("NONE".parse::<LogPrivacy>().unwrap(), LogPrivacy::None);
+        assert!("verbose".parse::<LogPrivacy>().is_err());
+        assert_eq!(LogPrivacy::default(), LogPrivacy::Redacted);
+    }
+
+    #[test]
+    fn test_redacted_hides_amounts_and_addresses() {
+        let d = details();
+        let line = LogPrivacy::Redacted
+            .format_intent_details("0xintent", "0x2::sui::SUI", "0xabc::usdc::USDC", &d)
+            .unwrap();
+        assert!(line.contains("0xintent"));
+        assert!(line.contains("SUI -> USDC"));
+        assert!(!line.contains(&d.input_amount));
+        assert!(!line.contains(&d.nullifier));
+        assert!(!line.contains(&d.output_stealth));
+        assert!(!line.contains(&d.remainder_stealth));
+
+        let r = result();
+        let line = LogPrivacy::Redacted.format_swap_result(&r).unwrap();
+        assert!(line.contains("0xintent via flowx"));
+        assert!(!line.contains(&r.output_amount.to_string()));
+        assert!(!line.contains(&r.remainder_amount.to_string()));
+        assert!(!line.contains(&r.output_stealth));
+        assert!(line.contains("seal servers [0xserver1]"));
+        assert!(!line.contains("simulated"));
+
+        let simulated = SwapExecutionResult { executed: false, simulated: true, ..r };
+        let line = LogPrivacy::Redacted.format_swap_result(&simulated).unwrap();
+        assert!(line.contains("(simulated, not submitted)"));
+    }
+
+    #[test]
+    fn test_failed_swap_event_carries_error_code() {
+        let failed = SwapExecutionResult {
+            success: false,
+            tx_digest: None,
+            error: Some("insufficient balance: have 5 need 123456789".to_string()),
+            error_code: Some("insufficient_balance".to_string()),
+            executed: false,
+            ..result()
+        };
+        let event = LogPrivacy::Full.swap_event("0x2::sui::SUI", "0x2::sui::SUI", &failed);
+        assert_eq!(event.error_code.as_deref(), Some("insufficient_balance"));
+        assert_eq!(event.output_amount, None);
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains(r#""error_code":"insufficient_balance""#));
+        assert!(!json.contains("123456789"));
+
+        // Successful swaps carry no code
+        let json = serde_json::to_string(&LogPrivacy::Full.swap_event("0x2::sui::SUI", "0x2::sui::SUI", &result()));
+        assert!(!json.unwrap().contains("error_code"));
+    }
+
+    #[test]
+    fn test_swap_event_omits_amounts_in_privacy_mode() {
+        let r = result();
+        let sensitive = [
+            r.output_amount.to_string(),
+            r.remainder_amount.to_string(),
+            r.output_stealth.clone(),
+            r.remainder_stealth.clone(),
+        ];
+
+        for level in [LogPrivacy::Redacted, LogPrivacy::None] {
+            let event = level.swap_event("0x2::sui::SUI", "0xabc::usdc::USDC", &r);
+            assert_eq!(
+                (event.intent_id.as_str(), event.venue.as_str()),
+                ("0xintent", "flowx")
+            );
+            assert_eq!((event.token_in.as_str(), event.token_out.as_str()), ("SUI", "USDC"));
+
+            let json = serde_json::to_string(&event).unwrap();
+            let line = level.format_swap_result(&r).unwrap_or_default();
+            for value in &sensitive {
+                assert!(!json.contains(value.as_str()), "{} leaked into event {}", value, json);
+                assert!(!line.contains(value.as_str()), "{} leaked into log {}", value, line);
+            }
+        }
+
+        let event = LogPrivacy::Full.swap_event("0x2::sui::SUI", "0xabc::usdc::USDC", &r);
+        assert_eq!(event.output_amount, Some(r.output_amount));
+        assert_eq!(event.output_stealth.as_deref(), Some("0xoutputstealth"));
+    }
+
+    #[test]
+    fn test_full_and_none() {
+        let d = details();
+        let line = LogPrivacy::Full
+            .format_intent_details("0xintent", "0x2::sui::SUI", "0x2::sui::SUI", &d)
+            .unwrap();
+        assert!(line.contains(&d.input_amount));
+
+        assert!(LogPrivacy::None
+            .format_intent_details("0xintent", "0x2::sui::SUI", "0x2::sui::SUI", &d)
+            .is_none());
+        assert!(LogPrivacy::None.format_swap_result(&result()).is_none());
+    }
+}
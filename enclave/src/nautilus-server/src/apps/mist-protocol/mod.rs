@@ -10,6 +10,12 @@
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(all(feature = "trusted-input", not(debug_assertions)))]
+compile_error!("the trusted-input feature bypasses SEAL and must not be enabled in release builds");
+
+// Core protocol types (nullifiers, stealth addresses, request/response types)
+pub mod types;
+
 // Intent processor for polling and processing swap intents
 #[cfg(feature = "mist-protocol")]
 pub mod intent_processor;
@@ -22,6 +28,100 @@ pub mod swap_executor;
 #[cfg(feature = "mist-protocol")]
 pub mod seal_types;
 
+// Admin endpoints (pause/resume processing)
+#[cfg(feature = "mist-protocol")]
+pub mod admin;
+
+// Backend key signing (local key or external KMS endpoint)
+#[cfg(feature = "mist-protocol")]
+pub mod signer;
+
+// Short-lived cache of SEAL decryptions for retried intents
+pub mod decrypt_cache;
+
+// Quote prefetching concurrent with SEAL decryption
+pub mod prefetch;
+
+// Privacy levels for logging decrypted intent data
+pub mod log_privacy;
+
+// Sanity caps on computed outputs, checked before signing
+pub mod limits;
+
+// Pool balance check before building a swap
+pub mod balance;
+
+// Gas coin selection for swap transactions
+pub mod gas;
+
+// Extra headers (auth) for SEAL key server requests
+pub mod seal_headers;
+
+// Enclave-signed receipts for executed swaps
+pub mod receipt;
+
+// Size-bounded, panic-safe BCS parsing of untrusted intent bytes
+pub mod bcs_guard;
+
+// EncryptedObject format versions and their decryption routines
+pub mod seal_version;
+
+// Simulate-only mode: dry-run swaps instead of submitting them
+pub mod simulate;
+
+// Timeout for on-chain transaction submission
+pub mod submit;
+
+// Periodic throughput summary for the intent processor
+pub mod summary;
+
+// Alert on intents stuck in the pending set
+pub mod pending_age;
+
+// Concurrent SEAL key fetching with early exit at threshold
+pub mod seal_fetch;
+
+// Latency percentiles for the intent processing benchmark
+pub mod latency;
+
+// Minimum time left before an intent's deadline to submit its swap
+pub mod deadline;
+
+// Move Registry name resolution for the package ID
+pub mod mvr;
+
+// Failover across Sui RPC endpoints (SUI_RPC_URLS)
+pub mod rpc_endpoints;
+
+// Order of processing pending intents (creation or deadline)
+pub mod processing_order;
+
+// Failure classes reported in failed results and swap events
+pub mod failure;
+
+// Per-intent attempt records for the admin diagnostics endpoint
+pub mod diagnostics;
+
+// Live backlog counters for the status endpoint
+pub mod backlog;
+
+// Ring buffer of the latest intent failures for the admin endpoint
+pub mod recent_failures;
+
+// Startup check of the on-chain Enclave object's registered public key
+#[cfg(feature = "mist-protocol")]
+pub mod registration;
+
+// Startup self-test: a synthetic intent through SEAL decryption and validation
+#[cfg(feature = "mist-protocol")]
+pub mod canary;
+
+// Mock SEAL key server shared with the integration tests (tests/common)
+#[cfg(all(test, feature = "mist-protocol"))]
+#[allow(dead_code)]
+#[path = "../../../tests/common/mock_seal_server.rs"]
+mod mock_seal_server;
+
 // ============ DATA STRUCTURES ============
 
 /// Decrypted deposit data (from SEAL encrypted blob on Deposit object)
@@ -70,6 +170,9 @@ pub struct SwapIntentObject {
     pub token_out: String,
     /// Deadline (unix timestamp in ms)
     pub deadline: u64,
+    /// Checkpoint time (ms) of its SwapIntentCreatedEvent; `None` when the
+    /// object was fetched by ID rather than found through the event
+    pub created_at_ms: Option<u64>,
 }
 
 /// On-chain Deposit object structure
@@ -102,29 +205,273 @@ pub struct SwapExecutionResult {
     pub output_stealth: String,
     /// Remainder stealth address
     pub remainder_stealth: String,
+    /// Where the swap was routed: `mixer` (same token) or `flowx`
+    #[serde(default)]
+    pub venue: String,
     /// Transaction digest (if executed)
     pub tx_digest: Option<String>,
     /// Error message (if failed)
     pub error: Option<String>,
+    /// Failure class for aggregation (`expired`, `slippage`, ...; see `failure`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// Whether the transaction was submitted on-chain
+    #[serde(default)]
+    pub executed: bool,
+    /// Whether the transaction was only dry-run (MIST_SIMULATE_ONLY)
+    #[serde(default)]
+    pub simulated: bool,
+    /// SEAL key server object IDs that provided decryption shares
+    #[serde(default)]
+    pub seal_servers: Vec<String>,
+    /// Enclave-signed receipt of where the funds went (successful swaps only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<receipt::SignedSwapReceipt>,
+    /// Checkpoint time (ms) the intent was created on-chain, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at_ms: Option<u64>,
+    /// Unix time (ms) the swap was executed or simulated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executed_at_ms: Option<u64>,
 }
 
-// ============ SEAL CONFIG ============
+impl SwapExecutionResult {
+    /// Result for an intent that failed with `error`
+    pub fn failed(intent_id: &str, error: &anyhow::Error) -> Self {
+        Self {
+            success: false,
+            intent_id: intent_id.to_string(),
+            nullifier_hash: String::new(),
+            output_amount: 0,
+            remainder_amount: 0,
+            output_stealth: String::new(),
+            remainder_stealth: String::new(),
+            venue: String::new(),
+            tx_digest: None,
+            error: Some(error.to_string()),
+            error_code: Some(failure::classify(error).as_str().to_string()),
+            executed: false,
+            simulated: false,
+            seal_servers: Vec::new(),
+            receipt: None,
+            created_at_ms: None,
+            executed_at_ms: None,
+        }
+    }
 
-#[cfg(feature = "mist-protocol")]
-use seal_sdk::ElGamalSecretKey;
+    /// Record when `intent` was created on-chain and when its swap finished.
+    /// The enclave clock may trail the checkpoint's, so execution is never
+    /// put before creation.
+    pub fn stamp_times(&mut self, intent: &SwapIntentObject, executed_at_ms: u64) {
+        self.created_at_ms = intent.created_at_ms;
+        self.executed_at_ms = Some(executed_at_ms.max(intent.created_at_ms.unwrap_or(0)));
+    }
+
+    /// Time from on-chain creation to execution, when both are known
+    pub fn latency_ms(&self) -> Option<u64> {
+        Some(self.executed_at_ms? - self.created_at_ms?)
+    }
+}
+
+// ============ BACKEND KEY ============
+
+/// Load backend keypair from environment variable
+///
+/// Expects BACKEND_PRIVATE_KEY in Bech32 format (suiprivkey1...)
+pub fn load_backend_keypair() -> anyhow::Result<fastcrypto::ed25519::Ed25519KeyPair> {
+    let private_key_str = std::env::var("BACKEND_PRIVATE_KEY")
+        .map_err(|_| anyhow::anyhow!(
+            "BACKEND_PRIVATE_KEY not found in environment.\n\
+             Generate a wallet with: sui client new-address ed25519\n\
+             Then set BACKEND_PRIVATE_KEY=<private_key>"
+        ))?;
+
+    // Decode Bech32 private key
+    use bech32::FromBase32;
+    let (hrp, data, _variant) = bech32::decode(&private_key_str)
+        .map_err(|e| anyhow::anyhow!("Invalid Bech32 private key: {}", e))?;
+
+    if hrp != "suiprivkey" {
+        return Err(anyhow::anyhow!("Invalid HRP: expected 'suiprivkey', got '{}'", hrp));
+    }
+
+    let decoded_bytes = Vec::<u8>::from_base32(&data)
+        .map_err(|e| anyhow::anyhow!("Failed to decode base32: {}", e))?;
+
+    // First byte is the scheme (0x00 for ed25519), rest is the 32-byte private key
+    if decoded_bytes.len() != 33 {
+        return Err(anyhow::anyhow!("Invalid key length: expected 33 bytes, got {}", decoded_bytes.len()));
+    }
+
+    if decoded_bytes[0] != 0x00 {
+        return Err(anyhow::anyhow!("Invalid key scheme: expected ed25519 (0x00), got 0x{:02x}", decoded_bytes[0]));
+    }
+
+    let key_bytes: [u8; 32] = decoded_bytes[1..33]
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Failed to extract 32 bytes"))?;
+
+    // Use ToFromBytes trait
+    use fastcrypto::traits::ToFromBytes;
+    let keypair = fastcrypto::ed25519::Ed25519KeyPair::from_bytes(&key_bytes)
+        .map_err(|e| anyhow::anyhow!("Failed to create keypair: {}", e))?;
+
+    Ok(keypair)
+}
+
+/// Environment variable holding the backend address authorized in the contract
+pub const EXPECTED_BACKEND_ADDRESS_ENV: &str = "MIST_EXPECTED_BACKEND_ADDRESS";
+
+/// Fail fast if the loaded key doesn't derive the address the contract expects.
+///
+/// Without this, deploying with the wrong key only shows up later as every
+/// transaction failing authorization. `expected = None` skips the check.
+pub fn check_backend_address(derived: &str, expected: Option<&str>) -> anyhow::Result<()> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+
+    if normalize_address(derived) != normalize_address(expected) {
+        return Err(anyhow::anyhow!(
+            "Backend address mismatch: BACKEND_PRIVATE_KEY derives {} but {} is {}",
+            derived,
+            EXPECTED_BACKEND_ADDRESS_ENV,
+            expected.trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Lowercase, strip `0x` and left-pad to 32 bytes so short forms compare equal
+fn normalize_address(address: &str) -> String {
+    let address = address.trim();
+    let hex = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+    format!("{:0>64}", hex.to_lowercase())
+}
+
+// ============ SEAL CONFIG ============
 
 #[cfg(feature = "mist-protocol")]
 lazy_static::lazy_static! {
-    /// SEAL encryption keys generated on startup
-    pub static ref ENCRYPTION_KEYS: (ElGamalSecretKey, seal_sdk::types::ElGamalPublicKey, seal_sdk::types::ElgamalVerificationKey) = {
-        seal_sdk::genkey(&mut rand::thread_rng())
+    /// SEAL configuration: the file at SEAL_CONFIG_PATH, else the embedded seal_config.yaml
+    pub static ref SEAL_CONFIG: seal_types::SealConfig = {
+        seal_types::SealConfig::from_env().expect("Invalid SEAL configuration")
     };
 
-    /// SEAL configuration loaded from seal_config.yaml
-    pub static ref SEAL_CONFIG: seal_types::SealConfig = {
-        let config_str = include_str!("seal_config.yaml");
-        serde_yaml::from_str(config_str)
-            .expect("Failed to parse seal_config.yaml")
+    /// FlowX pool selection strategy from MIST_POOL_SELECTION.
+    /// Initialized at startup so a bad value fails fast instead of per swap.
+    pub static ref POOL_SELECTION_STRATEGY: crate::flowx::PoolSelectionStrategy = {
+        crate::flowx::PoolSelectionStrategy::from_env()
+            .expect("Invalid MIST_POOL_SELECTION")
+    };
+
+    /// Rounding of computed outputs to base units, from MIST_OUTPUT_ROUNDING
+    pub static ref OUTPUT_ROUNDING: crate::flowx::utils::math::RoundingPolicy = {
+        crate::flowx::utils::math::RoundingPolicy::from_env()
+            .expect("Invalid MIST_OUTPUT_ROUNDING")
+    };
+
+    /// Sui fullnodes for the intent processor, from SUI_RPC_URLS (primary first)
+    pub static ref SUI_RPC_URLS: Vec<String> = {
+        rpc_endpoints::urls_from_env(intent_processor::SUI_RPC_URL).expect("Invalid SUI_RPC_URLS")
+    };
+
+    /// Startup RPC connection rounds before giving up, from MIST_RPC_CONNECT_ATTEMPTS
+    pub static ref RPC_CONNECT_ATTEMPTS: u32 = {
+        rpc_endpoints::connect_attempts_from_env().expect("Invalid MIST_RPC_CONNECT_ATTEMPTS")
+    };
+
+    /// Cap on decrypted intent plaintext, from MIST_MAX_DECRYPTED_BYTES
+    pub static ref MAX_DECRYPTED_BYTES: usize = {
+        bcs_guard::max_decrypted_from_env().expect("Invalid MIST_MAX_DECRYPTED_BYTES")
+    };
+
+    /// Order pending intents are processed in, from MIST_PROCESSING_ORDER
+    pub static ref PROCESSING_ORDER: processing_order::ProcessingOrder = {
+        processing_order::ProcessingOrder::from_env().expect("Invalid MIST_PROCESSING_ORDER")
+    };
+
+    /// How much decrypted intent data may be logged, from MIST_LOG_PRIVACY
+    pub static ref LOG_PRIVACY: log_privacy::LogPrivacy = {
+        log_privacy::LogPrivacy::from_env().expect("Invalid MIST_LOG_PRIVACY")
+    };
+
+    /// Output sanity caps from MIST_MAX_OUTPUT_AMOUNT / MIST_MAX_OUTPUT_MULTIPLE
+    pub static ref OUTPUT_CAP: limits::OutputCap = {
+        limits::OutputCap::from_env().expect("Invalid output cap configuration")
+    };
+
+    /// Extra SEAL request headers from MIST_SEAL_HEADERS
+    pub static ref SEAL_HEADERS: seal_headers::SealHeaders = {
+        seal_headers::SealHeaders::from_env().expect("Invalid MIST_SEAL_HEADERS")
+    };
+
+    /// HTTP client shared by all SEAL key server requests, so connections are reused
+    pub static ref SEAL_HTTP_CLIENT: reqwest::Client = {
+        intent_processor::seal_http_client().expect("Failed to build SEAL HTTP client")
+    };
+
+    /// Interval between processor summaries, from MIST_SUMMARY_INTERVAL_SECS
+    pub static ref SUMMARY_INTERVAL: std::time::Duration = {
+        summary::interval_from_env().expect("Invalid MIST_SUMMARY_INTERVAL_SECS")
+    };
+
+    /// Pending age that triggers a stuck-intent warning, from MIST_PENDING_AGE_ALERT_SECS
+    pub static ref PENDING_AGE_ALERT: std::time::Duration = {
+        pending_age::threshold_from_env().expect("Invalid MIST_PENDING_AGE_ALERT_SECS")
+    };
+
+    /// Minimum time left before an intent's deadline to submit, from MIST_DEADLINE_MARGIN_SECS
+    pub static ref DEADLINE_MARGIN: std::time::Duration = {
+        deadline::margin_from_env().expect("Invalid MIST_DEADLINE_MARGIN_SECS")
+    };
+
+    /// Maximum age of a SEAL key response, from MIST_SEAL_MAX_RESPONSE_AGE_SECS
+    pub static ref SEAL_MAX_RESPONSE_AGE: std::time::Duration = {
+        seal_fetch::max_response_age_from_env().expect("Invalid MIST_SEAL_MAX_RESPONSE_AGE_SECS")
+    };
+
+    /// Bound on a whole SEAL key fetch and decryption, from MIST_SEAL_DECRYPT_TIMEOUT_SECS
+    pub static ref SEAL_DECRYPT_TIMEOUT: std::time::Duration = {
+        seal_fetch::decrypt_timeout_from_env().expect("Invalid MIST_SEAL_DECRYPT_TIMEOUT_SECS")
+    };
+
+    /// SEAL session certificate TTL in minutes, from MIST_SEAL_SESSION_TTL_MIN
+    pub static ref SEAL_SESSION_TTL: u16 = {
+        seal_fetch::session_ttl_from_env(*SEAL_DECRYPT_TIMEOUT).expect("Invalid MIST_SEAL_SESSION_TTL_MIN")
+    };
+
+    /// Transaction submission timeout, from MIST_SUBMIT_TIMEOUT_SECS
+    pub static ref SUBMIT_TIMEOUT: std::time::Duration = {
+        submit::timeout_from_env().expect("Invalid MIST_SUBMIT_TIMEOUT_SECS")
+    };
+
+    /// Gas coin selection, from MIST_GAS_COIN_STRATEGY
+    pub static ref GAS_COIN_STRATEGY: gas::GasCoinStrategy = {
+        gas::GasCoinStrategy::from_env().expect("Invalid MIST_GAS_COIN_STRATEGY")
+    };
+
+    /// Limit on simultaneous transaction submissions, from MIST_MAX_CONCURRENT_SUBMITS
+    pub static ref SUBMIT_LIMITER: submit::SubmitLimiter = {
+        submit::SubmitLimiter::from_env().expect("Invalid MIST_MAX_CONCURRENT_SUBMITS")
+    };
+
+    /// Dry-run swaps instead of submitting them, from MIST_SIMULATE_ONLY
+    pub static ref SIMULATE_ONLY: bool = {
+        simulate::simulate_only_from_env().expect("Invalid MIST_SIMULATE_ONLY")
+    };
+
+    /// Startup self-test mode, from MIST_STARTUP_CANARY
+    pub static ref STARTUP_CANARY: canary::CanaryMode = {
+        canary::CanaryMode::from_env().expect("Invalid MIST_STARTUP_CANARY")
+    };
+
+    /// Read DEX pools while decrypting, from MIST_PREFETCH_QUOTES
+    pub static ref PREFETCH_QUOTES: bool = {
+        prefetch::prefetch_quotes_from_env().expect("Invalid MIST_PREFETCH_QUOTES")
     };
 }
 
@@ -153,6 +500,42 @@ mod tests {
         assert_eq!(details.signature, "BASE64_SIGNATURE_HERE");
     }
 
+    #[test]
+    fn test_result_timestamps() {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let mut intent = SwapIntentObject {
+            id: "0xintent".to_string(),
+            encrypted_details: vec![],
+            token_in: "0x2::sui::SUI".to_string(),
+            token_out: "0x2::sui::SUI".to_string(),
+            deadline: u64::MAX,
+            created_at_ms: Some(now_ms - 4_000),
+        };
+        let mut result = SwapExecutionResult::failed("0xintent", &anyhow::anyhow!("unused"));
+        result.stamp_times(&intent, now_ms);
+
+        let (created, executed) = (result.created_at_ms.unwrap(), result.executed_at_ms.unwrap());
+        assert!(executed >= created);
+        assert_eq!(result.latency_ms(), Some(4_000));
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!((json["created_at_ms"].as_u64(), json["executed_at_ms"].as_u64()), (Some(created), Some(executed)));
+
+        // Checkpoint ahead of the enclave clock
+        intent.created_at_ms = Some(now_ms + 500);
+        result.stamp_times(&intent, now_ms);
+        assert_eq!(result.executed_at_ms, intent.created_at_ms);
+        assert_eq!(result.latency_ms(), Some(0));
+
+        // Fetched by ID: creation unknown
+        intent.created_at_ms = None;
+        result.stamp_times(&intent, now_ms);
+        assert_eq!((result.created_at_ms, result.executed_at_ms), (None, Some(now_ms)));
+        assert_eq!(result.latency_ms(), None);
+    }
+
     #[test]
     fn test_decrypted_deposit_data_parsing() {
         // v2: Now includes ownerAddress field
@@ -167,4 +550,26 @@ mod tests {
         assert_eq!(data.nullifier, "0xfedcba0987654321");
         assert_eq!(data.owner_address, "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef");
     }
+
+    #[test]
+    fn test_check_backend_address() {
+        let derived = "0x00ab1234567890abcdef1234567890abcdef1234567890abcdef1234567890ab";
+
+        // Not configured: no check
+        assert!(check_backend_address(derived, None).is_ok());
+
+        // Matching, including case and short-form differences
+        assert!(check_backend_address(derived, Some(derived)).is_ok());
+        assert!(check_backend_address(
+            derived,
+            Some("0xAB1234567890ABCDEF1234567890ABCDEF1234567890ABCDEF1234567890AB")
+        )
+        .is_ok());
+
+        // Mismatch names both addresses
+        let other = "0x1111111111111111111111111111111111111111111111111111111111111111";
+        let err = check_backend_address(derived, Some(other)).unwrap_err().to_string();
+        assert!(err.contains(derived));
+        assert!(err.contains(other));
+    }
 }
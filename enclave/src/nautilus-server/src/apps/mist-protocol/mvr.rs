@@ -0,0 +1,129 @@
+//! Move Registry (MVR) name resolution
+//!
+//! `seal_config.yaml` may give the MVR name of the Mist Protocol package
+//! (e.g. `@mist/protocol`) alongside `package_id`. The name is resolved once
+//! when the config is loaded, through the MVR API at `MIST_MVR_URL`
+//! (default: the testnet MVR API), and must resolve to `package_id`. The
+//! lookup is unauthenticated, so it only ever cross-checks the configured ID.
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::time::Duration;
+use sui_sdk_types::ObjectId as ObjectID;
+
+/// Environment variable overriding the MVR API base URL
+pub const MVR_URL_ENV: &str = "MIST_MVR_URL";
+/// Default MVR API, matching the testnet contracts in seal_config.yaml
+pub const DEFAULT_MVR_URL: &str = "https://testnet.mvr.mystenlabs.com";
+
+/// `GET /v1/resolution/{name}` response
+#[derive(Debug, Deserialize)]
+struct ResolutionResponse {
+    package_id: String,
+}
+
+/// Reject anything that isn't shaped like an MVR name (`@org/app`,
+/// `org.sui/app`, optionally with `/version`) before it goes into a URL
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '/' | '.' | '-' | '_'));
+    let segments = name.split('/').count();
+    let bad_segment = name.split('/').any(|segment| segment.is_empty() || segment.chars().all(|c| c == '.'));
+    if !valid_chars || !(2..=3).contains(&segments) || bad_segment {
+        anyhow::bail!("Invalid MVR name: {:?}", name);
+    }
+    Ok(())
+}
+
+/// Resolve `name` to a package ID through the MVR API at `base_url`
+pub async fn resolve_package(client: &reqwest::Client, base_url: &str, name: &str) -> Result<ObjectID> {
+    validate_name(name)?;
+    let url = format!("{}/v1/resolution/{}", base_url.trim_end_matches('/'), name);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("MVR request to {} failed: {}", url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("MVR could not resolve {}: {} {}", name, status, body);
+    }
+
+    let resolution: ResolutionResponse = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Invalid MVR response for {}: {}", name, e))?;
+    ObjectID::from_str(&resolution.package_id)
+        .map_err(|e| anyhow::anyhow!("MVR returned invalid package ID for {}: {}", name, e))
+}
+
+/// Resolve `name` through `MIST_MVR_URL` from synchronous code.
+///
+/// The config is loaded inside a `lazy_static`, possibly on a runtime
+/// thread, so the request runs on its own thread with its own runtime.
+pub fn resolve_package_blocking(name: &str) -> Result<ObjectID> {
+    let base_url = std::env::var(MVR_URL_ENV).unwrap_or_else(|_| DEFAULT_MVR_URL.to_string());
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+                let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?;
+                runtime.block_on(resolve_package(&client, &base_url, name))
+            })
+            .join()
+            .map_err(|_| anyhow::anyhow!("MVR resolution of {} panicked", name))?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Path;
+    use axum::routing::get;
+
+    const PACKAGE: &str = "0x721e4d1a9c221974966e8e6768c88e313e0bf7d580a3514239efaf4635943109";
+
+    /// MVR API stand-in that knows `@mist/protocol` only
+    async fn mock_mvr() -> String {
+        let app = axum::Router::new().route(
+            "/v1/resolution/*name",
+            get(|Path(name): Path<String>| async move {
+                if name == "@mist/protocol" {
+                    (axum::http::StatusCode::OK, format!(r#"{{"package_id":"{}"}}"#, PACKAGE))
+                } else {
+                    (axum::http::StatusCode::NOT_FOUND, "name not found".to_string())
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_resolve_against_mock_mvr() {
+        let url = mock_mvr().await;
+        let client = reqwest::Client::new();
+
+        let resolved = resolve_package(&client, &url, "@mist/protocol").await.unwrap();
+        assert_eq!(resolved, ObjectID::from_str(PACKAGE).unwrap());
+
+        let err = resolve_package(&client, &url, "@mist/unknown").await.unwrap_err().to_string();
+        assert!(err.contains("404"), "{}", err);
+    }
+
+    #[test]
+    fn test_name_validation() {
+        assert!(validate_name("@mist/protocol").is_ok());
+        assert!(validate_name("mist.sui/protocol/2").is_ok());
+        assert!(validate_name("protocol").is_err());
+        assert!(validate_name("@mist/../admin").is_err());
+        assert!(validate_name("@mist/protocol?x=1").is_err());
+        assert!(validate_name("@mist//protocol").is_err());
+    }
+}
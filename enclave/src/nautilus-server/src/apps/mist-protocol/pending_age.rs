@@ -0,0 +1,147 @@
+//! Alert on intents that stay pending too long
+//!
+//! An intent that keeps showing up on every poll long after it should have
+//! been executed points at a failure the loop isn't recovering from, even
+//! when each individual attempt only logs a routine error. The processor
+//! tracks when each pending intent was first seen and warns once per intent
+//! when the oldest one exceeds `MIST_PENDING_AGE_ALERT_SECS` (default 900).
+//!
+//! Ages are measured from the first poll that saw the intent, so they restart
+//! with the processor.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Environment variable for the alert threshold in seconds
+pub const PENDING_AGE_ALERT_ENV: &str = "MIST_PENDING_AGE_ALERT_SECS";
+/// Default alert threshold
+pub const DEFAULT_PENDING_AGE_ALERT: Duration = Duration::from_secs(15 * 60);
+
+/// Read the threshold from `MIST_PENDING_AGE_ALERT_SECS`, defaulting to 15 minutes
+pub fn threshold_from_env() -> Result<Duration> {
+    match std::env::var(PENDING_AGE_ALERT_ENV) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", PENDING_AGE_ALERT_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_PENDING_AGE_ALERT),
+    }
+}
+
+/// The oldest pending intent crossed the threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingAgeAlert {
+    pub intent_id: String,
+    pub age: Duration,
+    pub threshold: Duration,
+}
+
+impl fmt::Display for PendingAgeAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Intent {} pending for {}s (alert threshold {}s); processing may be stuck",
+            self.intent_id,
+            self.age.as_secs(),
+            self.threshold.as_secs()
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct PendingAgeMonitor {
+    threshold: Duration,
+    first_seen: HashMap<String, Instant>,
+    alerted: HashSet<String>,
+}
+
+impl PendingAgeMonitor {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            first_seen: HashMap::new(),
+            alerted: HashSet::new(),
+        }
+    }
+
+    /// Record the intents pending on this poll. Returns an alert when the
+    /// oldest of them is over the threshold and hasn't been alerted on yet.
+    pub fn observe<'a>(&mut self, pending: impl IntoIterator<Item = &'a str>) -> Option<PendingAgeAlert> {
+        let now = Instant::now();
+        let pending: HashSet<&str> = pending.into_iter().collect();
+
+        // Intents that left the pending set are forgotten
+        self.first_seen.retain(|id, _| pending.contains(id.as_str()));
+        self.alerted.retain(|id| pending.contains(id.as_str()));
+        for id in pending {
+            self.first_seen.entry(id.to_string()).or_insert(now);
+        }
+
+        let (intent_id, age) = self.oldest()?;
+        if age <= self.threshold || self.alerted.contains(&intent_id) {
+            return None;
+        }
+        self.alerted.insert(intent_id.clone());
+        Some(PendingAgeAlert {
+            intent_id,
+            age,
+            threshold: self.threshold,
+        })
+    }
+
+    /// Oldest pending intent and how long it has been pending
+    pub fn oldest(&self) -> Option<(String, Duration)> {
+        self.first_seen
+            .iter()
+            .min_by_key(|(_, seen)| **seen)
+            .map(|(id, seen)| (id.clone(), seen.elapsed()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_alert_fires_for_old_intent() {
+        let mut monitor = PendingAgeMonitor::new(Duration::from_secs(600));
+
+        assert_eq!(monitor.observe(["0xold"]), None);
+        tokio::time::advance(Duration::from_secs(300)).await;
+        assert_eq!(monitor.observe(["0xold", "0xnew"]), None);
+
+        // 0xold crosses the threshold
+        tokio::time::advance(Duration::from_secs(301)).await;
+        let alert = monitor.observe(["0xold", "0xnew"]).expect("alert");
+        assert_eq!(alert.intent_id, "0xold");
+        assert_eq!(alert.age, Duration::from_secs(601));
+        assert!(alert.to_string().contains("0xold pending for 601s"));
+
+        // Only once per intent
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(monitor.observe(["0xold", "0xnew"]), None);
+
+        // Once 0xold is gone, 0xnew becomes the oldest and alerts in turn
+        tokio::time::advance(Duration::from_secs(300)).await;
+        let alert = monitor.observe(["0xnew"]).expect("alert");
+        assert_eq!(alert.intent_id, "0xnew");
+        assert_eq!(monitor.oldest().unwrap().0, "0xnew");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_processed_intents_are_forgotten() {
+        let mut monitor = PendingAgeMonitor::new(Duration::from_secs(60));
+        monitor.observe(["0x1"]);
+        tokio::time::advance(Duration::from_secs(120)).await;
+        // Executed before the next poll: nothing pending, nothing to alert on
+        assert_eq!(monitor.observe([]), None);
+        assert!(monitor.oldest().is_none());
+
+        // Seen again later, its age starts over
+        monitor.observe(["0x1"]);
+        assert_eq!(monitor.oldest().unwrap().1, Duration::ZERO);
+    }
+}
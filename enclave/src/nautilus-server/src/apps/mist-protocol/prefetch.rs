@@ -0,0 +1,119 @@
+//! Overlap SEAL decryption with quote prefetching
+//!
+//! The token pair is public on the `SwapIntentObject`, so the DEX pool state
+//! needed for the quote can be read while the SEAL round-trips are still in
+//! flight. Only the final pool choice waits for the decrypted amount.
+//! `MIST_PREFETCH_QUOTES=false` restores the strictly serial order.
+
+use anyhow::Result;
+use std::future::Future;
+use tracing::warn;
+
+use super::simulate::parse_flag;
+
+/// Environment variable toggling the prefetch
+pub const PREFETCH_QUOTES_ENV: &str = "MIST_PREFETCH_QUOTES";
+
+/// Read `MIST_PREFETCH_QUOTES`; unset means the prefetch is on
+pub fn prefetch_quotes_from_env() -> Result<bool> {
+    match std::env::var(PREFETCH_QUOTES_ENV) {
+        Ok(value) => parse_flag(&value)
+            .ok_or_else(|| anyhow::anyhow!("{} must be true or false, got {:?}", PREFETCH_QUOTES_ENV, value)),
+        Err(_) => Ok(true),
+    }
+}
+
+/// Run `decrypt` and, if given, `prefetch` concurrently.
+///
+/// A decryption failure fails the whole step. A failed prefetch only costs
+/// the overlap: it is logged and `None` is returned, so the executor fetches
+/// the quote itself once the amount is known.
+pub async fn decrypt_with_prefetch<T, U, D, P>(decrypt: D, prefetch: Option<P>) -> Result<(T, Option<U>)>
+where
+    D: Future<Output = Result<T>>,
+    P: Future<Output = Result<U>>,
+{
+    let Some(prefetch) = prefetch else {
+        return Ok((decrypt.await?, None));
+    };
+
+    let (decrypted, prefetched) = tokio::join!(decrypt, prefetch);
+    let decrypted = decrypted?;
+    let prefetched = match prefetched {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Quote prefetch failed, quoting after decryption: {}", e);
+            None
+        }
+    };
+    Ok((decrypted, prefetched))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_decrypt_and_prefetch_overlap() {
+        let events = Mutex::new(Vec::new());
+        let decrypt = async {
+            events.lock().unwrap().push("decrypt started");
+            sleep(Duration::from_millis(300)).await;
+            events.lock().unwrap().push("decrypt finished");
+            Ok("details")
+        };
+        let prefetch = async {
+            events.lock().unwrap().push("prefetch started");
+            sleep(Duration::from_millis(100)).await;
+            events.lock().unwrap().push("prefetch finished");
+            Ok("pools")
+        };
+
+        let started = tokio::time::Instant::now();
+        let (details, pools) = decrypt_with_prefetch(decrypt, Some(prefetch)).await.unwrap();
+        assert_eq!((details, pools), ("details", Some("pools")));
+
+        // Both start before either finishes, and the total is the slower one
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "decrypt started",
+                "prefetch started",
+                "prefetch finished",
+                "decrypt finished"
+            ]
+        );
+        assert_eq!(started.elapsed(), Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_failure_falls_back() {
+        let (details, pools) = decrypt_with_prefetch(
+            async { Ok("details") },
+            Some(async { Err::<(), _>(anyhow::anyhow!("RPC down")) }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(details, "details");
+        assert!(pools.is_none());
+
+        // Disabled: no prefetch future at all
+        let (_, pools) = decrypt_with_prefetch(async { Ok(1) }, None::<std::future::Ready<Result<()>>>)
+            .await
+            .unwrap();
+        assert!(pools.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_failure_fails() {
+        let result = decrypt_with_prefetch(
+            async { Err::<(), _>(anyhow::anyhow!("SEAL unavailable")) },
+            Some(async { Ok("pools") }),
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("SEAL unavailable"));
+    }
+}
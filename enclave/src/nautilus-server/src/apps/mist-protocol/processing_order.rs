@@ -0,0 +1,108 @@
+//! Order in which a poll cycle works through the pending intents
+//!
+//! `MIST_PROCESSING_ORDER` selects it:
+//! - `fifo` (default): by creation, oldest first. Creation time is the
+//!   checkpoint timestamp of the intent's `SwapIntentCreatedEvent`; intents
+//!   created in the same checkpoint keep their event order, and intents with
+//!   no known creation time go last.
+//! - `deadline`: earliest deadline first, creation order breaking ties.
+
+use anyhow::Result;
+use std::str::FromStr;
+
+use super::SwapIntentObject;
+
+/// Environment variable selecting the processing order
+pub const PROCESSING_ORDER_ENV: &str = "MIST_PROCESSING_ORDER";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProcessingOrder {
+    #[default]
+    Fifo,
+    Deadline,
+}
+
+impl FromStr for ProcessingOrder {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Ok(Self::Fifo),
+            "deadline" => Ok(Self::Deadline),
+            _ => Err(anyhow::anyhow!("Unknown processing order: {}", s)),
+        }
+    }
+}
+
+impl ProcessingOrder {
+    /// Read the order from `MIST_PROCESSING_ORDER`, defaulting to `Fifo`
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(PROCESSING_ORDER_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Sort intents (given in event order) into processing order
+    pub fn sort(&self, intents: &mut [SwapIntentObject]) {
+        // Stable, so equal keys keep their event order
+        let created = |intent: &SwapIntentObject| intent.created_at_ms.unwrap_or(u64::MAX);
+        match self {
+            Self::Fifo => intents.sort_by_key(created),
+            Self::Deadline => intents.sort_by_key(|intent| (intent.deadline, created(intent))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(id: &str, created_at_ms: Option<u64>, deadline: u64) -> SwapIntentObject {
+        SwapIntentObject {
+            id: id.to_string(),
+            encrypted_details: vec![],
+            token_in: "0x2::sui::SUI".to_string(),
+            token_out: "0x2::sui::SUI".to_string(),
+            deadline,
+            created_at_ms,
+        }
+    }
+
+    fn ids(intents: &[SwapIntentObject]) -> Vec<&str> {
+        intents.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    /// Returned out of creation order, with deadlines in yet another order
+    fn pending() -> Vec<SwapIntentObject> {
+        vec![
+            intent("third", Some(3_000), 10_000),
+            intent("unknown", None, 5_000),
+            intent("first", Some(1_000), 30_000),
+            intent("second-a", Some(2_000), 20_000),
+            intent("second-b", Some(2_000), 10_000),
+        ]
+    }
+
+    #[test]
+    fn test_fifo_processes_by_creation() {
+        let mut intents = pending();
+        ProcessingOrder::Fifo.sort(&mut intents);
+        assert_eq!(ids(&intents), ["first", "second-a", "second-b", "third", "unknown"]);
+    }
+
+    #[test]
+    fn test_deadline_order() {
+        let mut intents = pending();
+        ProcessingOrder::Deadline.sort(&mut intents);
+        assert_eq!(ids(&intents), ["unknown", "second-b", "third", "second-a", "first"]);
+    }
+
+    #[test]
+    fn test_parse_order() {
+        assert_eq!("FIFO".parse::<ProcessingOrder>().unwrap(), ProcessingOrder::Fifo);
+        assert_eq!("deadline".parse::<ProcessingOrder>().unwrap(), ProcessingOrder::Deadline);
+        assert!("lifo".parse::<ProcessingOrder>().is_err());
+        assert_eq!(ProcessingOrder::default(), ProcessingOrder::Fifo);
+    }
+}
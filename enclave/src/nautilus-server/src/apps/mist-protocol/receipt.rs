@@ -0,0 +1,211 @@
+//! Signed swap receipts
+//!
+//! After a swap executes, the enclave signs a `SwapReceipt` under its own
+//! intent scope so the user can later prove where their funds were sent,
+//! checking the signature against the attested enclave public key.
+//! `attestation_hash` names the attestation document (see
+//! `common::attestation_hash`) so a client can also check which enclave build
+//! signed it. `signing_key_id` names the key itself, so a client holding
+//! several attested keys (e.g. across enclave reboots) can pick the one to
+//! verify with.
+
+use crate::common::{to_signed_response, IntentMessage, IntentScope, ProcessedDataResponse};
+use crate::EnclaveError;
+use fastcrypto::ed25519::{Ed25519KeyPair, Ed25519PublicKey, Ed25519Signature};
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use fastcrypto::traits::{ToFromBytes, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use super::SwapExecutionResult;
+
+/// What the enclave attests to for an executed swap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapReceipt {
+    pub intent_id: String,
+    pub nullifier_hash: String,
+    pub output_stealth: String,
+    pub output_amount: u64,
+    pub remainder_stealth: String,
+    pub remainder_amount: u64,
+    pub tx_digest: Option<String>,
+    /// Hash of the signing enclave's attestation document; `None` when no
+    /// attestation was available (outside a Nitro enclave)
+    pub attestation_hash: Option<String>,
+    /// `signing_key_id` of the key that signed this receipt
+    pub signing_key_id: String,
+}
+
+/// Receipt wrapped in an `IntentMessage` plus the enclave's hex signature
+pub type SignedSwapReceipt = ProcessedDataResponse<IntentMessage<SwapReceipt>>;
+
+impl From<&SwapExecutionResult> for SwapReceipt {
+    fn from(result: &SwapExecutionResult) -> Self {
+        Self {
+            intent_id: result.intent_id.clone(),
+            nullifier_hash: result.nullifier_hash.clone(),
+            output_stealth: result.output_stealth.clone(),
+            output_amount: result.output_amount,
+            remainder_stealth: result.remainder_stealth.clone(),
+            remainder_amount: result.remainder_amount,
+            tx_digest: result.tx_digest.clone(),
+            attestation_hash: None,
+            signing_key_id: String::new(),
+        }
+    }
+}
+
+/// Hex Blake2b-256 of an enclave public key, identifying it in receipts
+pub fn signing_key_id(pk: &Ed25519PublicKey) -> String {
+    Hex::encode(Blake2b256::digest(pk.as_bytes()).digest)
+}
+
+/// Sign a receipt for `result` with the enclave key
+pub fn sign_swap_receipt(
+    kp: &Ed25519KeyPair,
+    result: &SwapExecutionResult,
+    attestation_hash: Option<String>,
+    timestamp_ms: u64,
+) -> SignedSwapReceipt {
+    use fastcrypto::traits::KeyPair;
+
+    let receipt = SwapReceipt {
+        attestation_hash,
+        signing_key_id: signing_key_id(kp.public()),
+        ..SwapReceipt::from(result)
+    };
+    to_signed_response(kp, receipt, timestamp_ms, IntentScope::SwapReceipt)
+}
+
+/// Check a receipt's scope, key ID and signature against the enclave public key
+pub fn verify_swap_receipt(
+    pk: &Ed25519PublicKey,
+    receipt: &SignedSwapReceipt,
+) -> Result<(), EnclaveError> {
+    if receipt.response.intent != IntentScope::SwapReceipt {
+        return Err(EnclaveError::InvalidInput(format!(
+            "not a swap receipt: scope {:?}",
+            receipt.response.intent
+        )));
+    }
+    let key_id = signing_key_id(pk);
+    if receipt.response.data.signing_key_id != key_id {
+        return Err(EnclaveError::InvalidInput(format!(
+            "receipt signed by key {}, not {}",
+            receipt.response.data.signing_key_id, key_id
+        )));
+    }
+    let signing_payload = bcs::to_bytes(&receipt.response)
+        .map_err(|e| EnclaveError::GenericError(format!("Failed to serialize receipt: {}", e)))?;
+    let sig_bytes = Hex::decode(&receipt.signature)
+        .map_err(|e| EnclaveError::InvalidInput(format!("Invalid receipt signature hex: {}", e)))?;
+    let signature = Ed25519Signature::from_bytes(&sig_bytes)
+        .map_err(|e| EnclaveError::InvalidInput(format!("Invalid receipt signature: {}", e)))?;
+    pk.verify(&signing_payload, &signature)
+        .map_err(|_| EnclaveError::Unauthorized("receipt signature does not verify".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::traits::KeyPair;
+    use rand::thread_rng;
+
+    fn result() -> SwapExecutionResult {
+        SwapExecutionResult {
+            success: true,
+            intent_id: "0xintent".to_string(),
+            nullifier_hash: "0xnullifier".to_string(),
+            output_amount: 987654321,
+            remainder_amount: 55555,
+            output_stealth: "0xoutputstealth".to_string(),
+            remainder_stealth: "0xremainderstealth".to_string(),
+            venue: "flowx".to_string(),
+            tx_digest: Some("digest".to_string()),
+            error: None,
+            error_code: None,
+            executed: true,
+            simulated: false,
+            seal_servers: vec!["0xserver1".to_string()],
+            receipt: None,
+            created_at_ms: None,
+            executed_at_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_receipt_roundtrip_and_verify() {
+        let kp = Ed25519KeyPair::generate(&mut thread_rng());
+        let signed = sign_swap_receipt(&kp, &result(), Some("ab".repeat(32)), 1_700_000_000_000);
+        assert_eq!(signed.response.intent, IntentScope::SwapReceipt);
+        assert_eq!(
+            signed.response.data,
+            SwapReceipt {
+                attestation_hash: Some("ab".repeat(32)),
+                signing_key_id: signing_key_id(kp.public()),
+                ..SwapReceipt::from(&result())
+            }
+        );
+
+        // What the user stores and later presents
+        let json = serde_json::to_string(&signed).unwrap();
+        let parsed: SignedSwapReceipt = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.response.data, signed.response.data);
+        assert!(verify_swap_receipt(kp.public(), &parsed).is_ok());
+
+        // Another key
+        let other = Ed25519KeyPair::generate(&mut thread_rng());
+        assert!(matches!(
+            verify_swap_receipt(other.public(), &parsed),
+            Err(EnclaveError::InvalidInput(_))
+        ));
+
+        // Key ID swapped for another key's: the signature no longer verifies
+        let mut rekeyed = parsed.clone();
+        rekeyed.response.data.signing_key_id = signing_key_id(other.public());
+        assert!(verify_swap_receipt(other.public(), &rekeyed).is_err());
+
+        // Claimed to come from another enclave build
+        let mut reattested = parsed.clone();
+        reattested.response.data.attestation_hash = Some("cd".repeat(32));
+        assert!(verify_swap_receipt(kp.public(), &reattested).is_err());
+
+        // Tampered amount
+        let mut tampered = parsed.clone();
+        tampered.response.data.output_amount += 1;
+        assert!(matches!(
+            verify_swap_receipt(kp.public(), &tampered),
+            Err(EnclaveError::Unauthorized(_))
+        ));
+
+        // Same payload under another scope is not a receipt
+        let mut rescoped = parsed;
+        rescoped.response.intent = IntentScope::ProcessData;
+        assert!(matches!(
+            verify_swap_receipt(kp.public(), &rescoped),
+            Err(EnclaveError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_signing_key_id_follows_key() {
+        let kp = Ed25519KeyPair::generate(&mut thread_rng());
+        let signed = sign_swap_receipt(&kp, &result(), None, 1_700_000_000_000);
+        assert_eq!(signed.response.data.signing_key_id, signing_key_id(kp.public()));
+        assert_eq!(signed.response.data.signing_key_id.len(), 64);
+
+        // Rotated key: new ID, and each receipt picks out the key that verifies it
+        let rotated = Ed25519KeyPair::generate(&mut thread_rng());
+        let resigned = sign_swap_receipt(&rotated, &result(), None, 1_700_000_000_000);
+        assert_ne!(resigned.response.data.signing_key_id, signed.response.data.signing_key_id);
+
+        let keys = [kp.public().clone(), rotated.public().clone()];
+        for receipt in [&signed, &resigned] {
+            let key = keys
+                .iter()
+                .find(|pk| signing_key_id(pk) == receipt.response.data.signing_key_id)
+                .unwrap();
+            assert!(verify_swap_receipt(key, receipt).is_ok());
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Most recent intent failures, for `GET /admin/recent-failures`
+//!
+//! A bounded ring buffer of the last `MIST_RECENT_FAILURES` failures (default
+//! 50), so a burst of failures can be triaged without searching the logs.
+//! Only the intent ID, the time and the failure class are kept; error
+//! messages are left out since they can quote decrypted details.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Environment variable for the number of failures kept
+pub const RECENT_FAILURES_ENV: &str = "MIST_RECENT_FAILURES";
+/// Default number of failures kept
+pub const DEFAULT_CAPACITY: usize = 50;
+
+/// One failed intent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureRecord {
+    pub intent_id: String,
+    /// Unix time of the failure, in ms
+    pub failed_at_ms: u64,
+    /// Failure class, as in failed results and swap events
+    pub error_code: String,
+}
+
+#[derive(Debug)]
+pub struct RecentFailures {
+    capacity: usize,
+    records: Mutex<VecDeque<FailureRecord>>,
+}
+
+impl Default for RecentFailures {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl RecentFailures {
+    /// Keep the last `capacity` failures; 0 keeps none
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Capacity from `MIST_RECENT_FAILURES`, defaulting to 50
+    pub fn from_env() -> Self {
+        let capacity = std::env::var(RECENT_FAILURES_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        Self::new(capacity)
+    }
+
+    /// Record a failure, evicting the oldest once full
+    pub fn record(&self, intent_id: &str, error_code: &str, failed_at_ms: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(FailureRecord {
+            intent_id: intent_id.to_string(),
+            failed_at_ms,
+            error_code: error_code.to_string(),
+        });
+    }
+
+    /// Kept failures, most recent first
+    pub fn recent(&self) -> Vec<FailureRecord> {
+        self.records.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_most_recent_in_order() {
+        let failures = RecentFailures::new(3);
+        assert!(failures.recent().is_empty());
+
+        for (i, code) in ["decrypt_failed", "timeout", "invalid_input", "other", "timeout"].iter().enumerate() {
+            failures.record(&format!("0x{}", i), code, 1_700_000_000_000 + i as u64);
+        }
+
+        let recent = failures.recent();
+        let ids: Vec<&str> = recent.iter().map(|r| r.intent_id.as_str()).collect();
+        assert_eq!(ids, ["0x4", "0x3", "0x2"]);
+        assert_eq!(
+            recent[0],
+            FailureRecord {
+                intent_id: "0x4".to_string(),
+                failed_at_ms: 1_700_000_000_004,
+                error_code: "timeout".to_string(),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&recent[2]).unwrap(),
+            serde_json::json!({
+                "intent_id": "0x2",
+                "failed_at_ms": 1_700_000_000_002u64,
+                "error_code": "invalid_input",
+            })
+        );
+    }
+
+    #[test]
+    fn test_zero_capacity_keeps_nothing() {
+        let failures = RecentFailures::new(0);
+        failures.record("0x1", "other", 1);
+        assert!(failures.recent().is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+//! Startup check of the on-chain `Enclave` registration
+//!
+//! `enclave::register_enclave` stores the enclave's public key in an
+//! `Enclave<T>` object. When `MIST_ENCLAVE_OBJECT_ID` is set the server reads
+//! that object before serving and refuses to start if the registered `pk`
+//! isn't the loaded keypair's public key: anything it signs would otherwise
+//! fail verification against the registration.
+
+use crate::EnclaveError;
+use sui_sdk::rpc_types::{SuiObjectDataOptions, SuiParsedData};
+use sui_sdk::types::base_types::ObjectID;
+use sui_sdk::SuiClient;
+
+/// Env var naming the `Enclave<T>` object to check against
+pub const ENCLAVE_OBJECT_ENV: &str = "MIST_ENCLAVE_OBJECT_ID";
+
+/// Name of the public key field in the Move struct
+pub const ENCLAVE_PK_FIELD: &str = "pk";
+
+/// Reject a registration whose public key isn't ours
+pub fn check_registered_pk(registered: &[u8], own: &[u8]) -> Result<(), EnclaveError> {
+    if registered != own {
+        return Err(EnclaveError::GenericError(format!(
+            "enclave not registered with this key: on-chain pk {} but loaded keypair is {}",
+            hex::encode(registered),
+            hex::encode(own)
+        )));
+    }
+    Ok(())
+}
+
+/// Read a `vector<u8>` field from Move object fields. Sui JSON-RPC renders
+/// it as an array of numbers; a base64 string is accepted as well.
+pub fn bytes_field(fields: &serde_json::Value, name: &str) -> Option<Vec<u8>> {
+    use base64::Engine;
+
+    match fields.get(name)? {
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect(),
+        serde_json::Value::String(s) => base64::engine::general_purpose::STANDARD.decode(s).ok(),
+        _ => None,
+    }
+}
+
+/// Fetch the `Enclave<T>` object and check its registered key against `own_pk`
+pub async fn verify_registration(
+    sui_client: &SuiClient,
+    enclave_id: &str,
+    own_pk: &[u8],
+) -> Result<(), EnclaveError> {
+    let object_id = ObjectID::from_hex_literal(enclave_id.trim())
+        .map_err(|e| EnclaveError::InvalidInput(format!("invalid {}: {}", ENCLAVE_OBJECT_ENV, e)))?;
+    let response = sui_client
+        .read_api()
+        .get_object_with_options(object_id, SuiObjectDataOptions::new().with_content())
+        .await
+        .map_err(|e| EnclaveError::GenericError(format!("failed to read enclave object {}: {}", object_id, e)))?;
+
+    let fields = match response.data.and_then(|data| data.content) {
+        Some(SuiParsedData::MoveObject(obj)) => serde_json::to_value(&obj.fields).ok(),
+        _ => None,
+    };
+    let registered = fields
+        .as_ref()
+        .and_then(|fields| bytes_field(fields, ENCLAVE_PK_FIELD))
+        .ok_or_else(|| {
+            EnclaveError::GenericError(format!("enclave object {} has no readable `pk` field", object_id))
+        })?;
+    check_registered_pk(&registered, own_pk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::ed25519::Ed25519KeyPair;
+    use fastcrypto::traits::{KeyPair, ToFromBytes};
+    use serde_json::json;
+
+    #[test]
+    fn test_matching_and_mismatching_registered_pk() {
+        let kp = Ed25519KeyPair::generate(&mut rand::thread_rng());
+        let own = kp.public().as_bytes().to_vec();
+        let fields = json!({ "pk": own, "config_version": "0" });
+        let registered = bytes_field(&fields, ENCLAVE_PK_FIELD).unwrap();
+        assert!(check_registered_pk(&registered, &own).is_ok());
+
+        // Registered by a previous deployment's key
+        let other = Ed25519KeyPair::generate(&mut rand::thread_rng());
+        let stale = other.public().as_bytes().to_vec();
+        match check_registered_pk(&stale, &own) {
+            Err(EnclaveError::GenericError(msg)) => {
+                assert!(msg.starts_with("enclave not registered with this key"));
+                assert!(msg.contains(&hex::encode(&stale)) && msg.contains(&hex::encode(&own)));
+            }
+            other => panic!("expected GenericError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_bytes_field_forms() {
+        assert_eq!(bytes_field(&json!({ "pk": [1, 2, 255] }), "pk"), Some(vec![1, 2, 255]));
+        assert_eq!(bytes_field(&json!({ "pk": "AQL/" }), "pk"), Some(vec![1, 2, 255]));
+        assert_eq!(bytes_field(&json!({ "pk": [1, 256] }), "pk"), None);
+        assert_eq!(bytes_field(&json!({ "pk": true }), "pk"), None);
+        assert_eq!(bytes_field(&json!({}), "pk"), None);
+    }
+}
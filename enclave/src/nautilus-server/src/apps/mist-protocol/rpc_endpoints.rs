@@ -0,0 +1,277 @@
+//! Sui RPC endpoint failover for the intent processor
+//!
+//! `SUI_RPC_URLS` lists fullnodes as comma-separated URLs, the first being
+//! the primary. The processor sticks with one endpoint until it fails
+//! `FAILOVER_THRESHOLD` poll cycles in a row, then moves to the next in
+//! round-robin order, so a degraded fullnode doesn't stall processing.
+//! Unset, the built-in testnet fullnode is the only endpoint.
+//!
+//! At startup the client is built with exponential backoff (1s doubling up
+//! to 30s) for at most `MIST_RPC_CONNECT_ATTEMPTS` rounds over all endpoints
+//! (default 10). Then the processor gives up and is restarted by its
+//! supervisor, so an RPC outage delays processing instead of crashing it.
+
+use anyhow::Result;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// Environment variable listing the RPC endpoints
+pub const SUI_RPC_URLS_ENV: &str = "SUI_RPC_URLS";
+/// Consecutive failures before moving to the next endpoint
+pub const FAILOVER_THRESHOLD: u32 = 3;
+
+/// Environment variable for the number of startup connection rounds
+pub const CONNECT_ATTEMPTS_ENV: &str = "MIST_RPC_CONNECT_ATTEMPTS";
+/// Default number of startup connection rounds
+pub const DEFAULT_CONNECT_ATTEMPTS: u32 = 10;
+/// First backoff delay; doubled after every failed round
+pub const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Backoff ceiling
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Read the attempt limit from `MIST_RPC_CONNECT_ATTEMPTS`, defaulting to 10
+pub fn connect_attempts_from_env() -> Result<u32> {
+    match std::env::var(CONNECT_ATTEMPTS_ENV) {
+        Ok(value) => match value.parse::<u32>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", CONNECT_ATTEMPTS_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_CONNECT_ATTEMPTS),
+    }
+}
+
+/// Read the endpoints from `SUI_RPC_URLS`, defaulting to `default`
+pub fn urls_from_env(default: &str) -> Result<Vec<String>> {
+    match std::env::var(SUI_RPC_URLS_ENV) {
+        Ok(value) => parse_urls(&value),
+        Err(_) => Ok(vec![default.to_string()]),
+    }
+}
+
+fn parse_urls(value: &str) -> Result<Vec<String>> {
+    let urls: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(str::to_string)
+        .collect();
+    if urls.is_empty() {
+        anyhow::bail!("{} must list at least one URL", SUI_RPC_URLS_ENV);
+    }
+    if let Some(url) = urls.iter().find(|url| !url.starts_with("http://") && !url.starts_with("https://")) {
+        anyhow::bail!("{} entry is not an http(s) URL: {}", SUI_RPC_URLS_ENV, url);
+    }
+    Ok(urls)
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcEndpoints {
+    urls: Vec<String>,
+    current: usize,
+    failures: u32,
+    threshold: u32,
+}
+
+impl RpcEndpoints {
+    /// `urls` must not be empty
+    pub fn new(urls: Vec<String>, threshold: u32) -> Self {
+        assert!(!urls.is_empty(), "at least one RPC endpoint is required");
+        Self {
+            urls,
+            current: 0,
+            failures: 0,
+            threshold: threshold.max(1),
+        }
+    }
+
+    /// Endpoint currently in use
+    pub fn current(&self) -> &str {
+        &self.urls[self.current]
+    }
+
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+    }
+
+    /// Count a failed call. Returns the next endpoint when the threshold is
+    /// reached and there is another one to move to.
+    pub fn record_failure(&mut self) -> Option<&str> {
+        self.failures += 1;
+        if self.failures < self.threshold || self.urls.len() == 1 {
+            return None;
+        }
+        self.failures = 0;
+        self.current = (self.current + 1) % self.urls.len();
+        Some(self.current())
+    }
+
+    /// Connect to the current endpoint, trying the others in order if it
+    /// can't be reached. The endpoint that connected becomes current.
+    pub async fn connect<T, F, Fut>(&mut self, mut connect: F) -> Result<T>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        for offset in 0..self.urls.len() {
+            let index = (self.current + offset) % self.urls.len();
+            match connect(self.urls[index].clone()).await {
+                Ok(client) => {
+                    self.current = index;
+                    self.failures = 0;
+                    return Ok(client);
+                }
+                Err(e) => last_error = Some(anyhow::anyhow!("{}: {}", self.urls[index], e)),
+            }
+        }
+        Err(last_error.expect("at least one endpoint was tried"))
+    }
+
+    /// `connect` with up to `attempts` rounds over the endpoints, backing off
+    /// exponentially between rounds. Returns the last error once they're used up.
+    pub async fn connect_with_retry<T, F, Fut>(&mut self, attempts: u32, mut connect: F) -> Result<T>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            match self.connect(&mut connect).await {
+                Ok(client) => return Ok(client),
+                Err(e) if attempt >= attempts => {
+                    return Err(e.context(format!("Sui RPC unreachable after {} attempt(s)", attempts)));
+                }
+                Err(e) => {
+                    warn!(
+                        "Sui RPC connection attempt {}/{} failed: {} - retrying in {:?}",
+                        attempt, attempts, e, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints() -> RpcEndpoints {
+        RpcEndpoints::new(
+            vec!["https://primary.example".to_string(), "https://secondary.example".to_string()],
+            FAILOVER_THRESHOLD,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_primary_down_secondary_connects() {
+        let mut endpoints = endpoints();
+        let mut tried = Vec::new();
+        let client = endpoints
+            .connect(|url| {
+                tried.push(url.clone());
+                async move {
+                    match url.as_str() {
+                        "https://primary.example" => Err(anyhow::anyhow!("connection refused")),
+                        _ => Ok(format!("client for {}", url)),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(client, "client for https://secondary.example");
+        assert_eq!(tried, ["https://primary.example", "https://secondary.example"]);
+        assert_eq!(endpoints.current(), "https://secondary.example");
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoints_down() {
+        let mut endpoints = endpoints();
+        let err = endpoints
+            .connect(|_| async { Err::<(), _>(anyhow::anyhow!("timeout")) })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "https://secondary.example: timeout");
+        assert_eq!(endpoints.current(), "https://primary.example");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_until_rpc_recovers() {
+        use std::cell::Cell;
+        use tokio::time::Instant;
+
+        let mut endpoints = RpcEndpoints::new(vec!["https://only.example".to_string()], FAILOVER_THRESHOLD);
+        let calls = Cell::new(0);
+        let started = Instant::now();
+        let client = endpoints
+            .connect_with_retry(5, |_| {
+                calls.set(calls.get() + 1);
+                let call = calls.get();
+                async move {
+                    match call {
+                        1 | 2 => Err(anyhow::anyhow!("503 Service Unavailable")),
+                        _ => Ok("client"),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(client, "client");
+        assert_eq!(calls.get(), 3);
+        // Backed off 1s, then 2s
+        assert_eq!(started.elapsed(), Duration::from_secs(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_gives_up() {
+        let mut endpoints = endpoints();
+        let started = tokio::time::Instant::now();
+        let err = endpoints
+            .connect_with_retry(7, |_| async { Err::<(), _>(anyhow::anyhow!("timeout")) })
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Sui RPC unreachable after 7 attempt(s)");
+        assert_eq!(err.root_cause().to_string(), "https://secondary.example: timeout");
+        // 1 + 2 + 4 + 8 + 16 + 30
+        assert_eq!(started.elapsed(), Duration::from_secs(61));
+    }
+
+    #[test]
+    fn test_failover_after_repeated_failures() {
+        let mut endpoints = endpoints();
+        assert_eq!(endpoints.record_failure(), None);
+        endpoints.record_success();
+        assert_eq!(endpoints.record_failure(), None);
+        assert_eq!(endpoints.record_failure(), None);
+        assert_eq!(endpoints.record_failure(), Some("https://secondary.example"));
+
+        // Round-robin back to the primary
+        for _ in 1..FAILOVER_THRESHOLD {
+            assert_eq!(endpoints.record_failure(), None);
+        }
+        assert_eq!(endpoints.record_failure(), Some("https://primary.example"));
+
+        // A single endpoint has nowhere to go
+        let mut single = RpcEndpoints::new(vec!["https://only.example".to_string()], 1);
+        assert_eq!(single.record_failure(), None);
+        assert_eq!(single.current(), "https://only.example");
+    }
+
+    #[test]
+    fn test_parse_urls() {
+        assert_eq!(
+            parse_urls(" https://a.example , https://b.example,").unwrap(),
+            ["https://a.example", "https://b.example"]
+        );
+        assert!(parse_urls(" , ").is_err());
+        assert!(parse_urls("https://a.example,fullnode.example").is_err());
+    }
+}
@@ -0,0 +1,598 @@
+//! Concurrent SEAL key fetching
+//!
+//! Key servers are queried in parallel and collection stops as soon as
+//! `threshold` servers have answered, so a slow or dead server doesn't add
+//! its full timeout to every decryption. Outstanding requests are cancelled.
+//! Servers that failed are still reported at WARN when the threshold was met,
+//! so a degrading server shows up before it costs a decryption.
+//!
+//! Each request is bounded by the HTTP client's timeout; the whole fetch and
+//! decrypt is additionally bounded by `MIST_SEAL_DECRYPT_TIMEOUT_SECS`
+//! (default 30), so slow servers plus decryption can't eat an intent's
+//! budget unpredictably.
+//!
+//! Every decryption signs a fresh session certificate valid for
+//! `MIST_SEAL_SESSION_TTL_MIN` minutes (default 10). A shorter TTL narrows
+//! the window in which a captured certificate and session key could be
+//! replayed to the key servers; one that is too short lets the certificate
+//! expire mid-decryption under load. It must be at least twice the
+//! fetch-and-decrypt timeout, and key servers refuse TTLs over 30 minutes.
+
+use crate::EnclaveError;
+use anyhow::Result;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::{error, warn};
+
+/// Environment variable bounding how long after the session certificate's
+/// creation a key server response is still accepted
+pub const MAX_RESPONSE_AGE_ENV: &str = "MIST_SEAL_MAX_RESPONSE_AGE_SECS";
+/// Default maximum response age
+pub const DEFAULT_MAX_RESPONSE_AGE: Duration = Duration::from_secs(60);
+
+/// Read the maximum response age from `MIST_SEAL_MAX_RESPONSE_AGE_SECS`
+pub fn max_response_age_from_env() -> Result<Duration> {
+    match std::env::var(MAX_RESPONSE_AGE_ENV) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", MAX_RESPONSE_AGE_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_MAX_RESPONSE_AGE),
+    }
+}
+
+/// Environment variable bounding a whole SEAL fetch and decrypt
+pub const DECRYPT_TIMEOUT_ENV: &str = "MIST_SEAL_DECRYPT_TIMEOUT_SECS";
+/// Default bound on a SEAL fetch and decrypt
+pub const DEFAULT_DECRYPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Read the fetch-and-decrypt timeout from `MIST_SEAL_DECRYPT_TIMEOUT_SECS`
+pub fn decrypt_timeout_from_env() -> Result<Duration> {
+    match std::env::var(DECRYPT_TIMEOUT_ENV) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", DECRYPT_TIMEOUT_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_DECRYPT_TIMEOUT),
+    }
+}
+
+/// Environment variable for the session certificate TTL in minutes
+pub const SESSION_TTL_ENV: &str = "MIST_SEAL_SESSION_TTL_MIN";
+/// Default session certificate TTL in minutes
+pub const DEFAULT_SESSION_TTL_MIN: u16 = 10;
+/// Shortest accepted session TTL in minutes
+pub const MIN_SESSION_TTL_MIN: u16 = 1;
+/// Longest session TTL the key servers accept, in minutes
+pub const MAX_SESSION_TTL_MIN: u16 = 30;
+
+/// Read the session TTL from `MIST_SEAL_SESSION_TTL_MIN`, defaulting to 10,
+/// and check it against the fetch-and-decrypt timeout
+pub fn session_ttl_from_env(decrypt_timeout: Duration) -> Result<u16> {
+    let ttl_min = match std::env::var(SESSION_TTL_ENV) {
+        Ok(value) => value
+            .parse::<u16>()
+            .map_err(|_| anyhow::anyhow!("{} must be a whole number of minutes, got {:?}", SESSION_TTL_ENV, value))?,
+        Err(_) => DEFAULT_SESSION_TTL_MIN,
+    };
+    check_session_ttl(ttl_min, decrypt_timeout)
+}
+
+/// Accept `ttl_min` if it is within bounds and at least twice `decrypt_timeout`
+pub fn check_session_ttl(ttl_min: u16, decrypt_timeout: Duration) -> Result<u16> {
+    if !(MIN_SESSION_TTL_MIN..=MAX_SESSION_TTL_MIN).contains(&ttl_min) {
+        anyhow::bail!(
+            "{} must be between {} and {} minutes, got {}",
+            SESSION_TTL_ENV,
+            MIN_SESSION_TTL_MIN,
+            MAX_SESSION_TTL_MIN,
+            ttl_min
+        );
+    }
+    if Duration::from_secs(ttl_min as u64 * 60) < decrypt_timeout * 2 {
+        anyhow::bail!(
+            "{} of {} minute(s) is too short for a {:?} fetch-and-decrypt timeout (needs at least twice it)",
+            SESSION_TTL_ENV,
+            ttl_min,
+            decrypt_timeout
+        );
+    }
+    Ok(ttl_min)
+}
+
+/// Run a fetch and decrypt, failing with `KeyServerUnavailable` if it takes
+/// longer than `limit`. Outstanding key server requests are dropped.
+pub async fn with_decrypt_timeout<T>(
+    limit: Duration,
+    fetch_and_decrypt: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match tokio::time::timeout(limit, fetch_and_decrypt).await {
+        Ok(result) => result,
+        Err(_) => Err(EnclaveError::KeyServerUnavailable(format!(
+            "SEAL fetch and decrypt did not finish within {:?}",
+            limit
+        ))
+        .into()),
+    }
+}
+
+/// `Client-Sdk-Version` sent with every fetch_key request
+pub const SEAL_SDK_VERSION: &str = "0.5.11";
+
+/// Key server error names meaning the `Client-Sdk-Version` was refused
+const SDK_VERSION_ERRORS: [&str; 2] = ["DeprecatedSDKVersion", "InvalidSDKVersion"];
+
+/// Recognize a non-success key server response that refuses our SDK version.
+/// Any other error stays a generic server error.
+pub fn sdk_version_mismatch(status: u16, body: &str) -> Option<EnclaveError> {
+    if !SDK_VERSION_ERRORS.iter().any(|name| body.contains(name)) {
+        return None;
+    }
+    Some(EnclaveError::SealVersionMismatch(format!(
+        "seal_sdk_version_rejected: key server refused Client-Sdk-Version {} ({}: {}); \
+         set SEAL_SDK_VERSION to a version the key servers accept",
+        SEAL_SDK_VERSION,
+        status,
+        body.trim()
+    )))
+}
+
+/// Check that a key server response belongs to the request we just made.
+///
+/// SEAL responses carry no nonce or timestamp. Replay protection comes from
+/// the ElGamal key: each decryption generates its own and drops it afterwards,
+/// so shares captured from any other request don't decrypt. On top of that:
+/// - every returned key must be for the requested encryption ID, and
+/// - the response must arrive within `max_age` of the session certificate's
+///   `creation_time`. Both times are read from the local clock, so this only
+///   bounds how long a fetch may take; it is not replay protection.
+pub fn verify_response_binding<'a>(
+    requested_id: &[u8],
+    key_ids: impl IntoIterator<Item = &'a [u8]>,
+    session_created_ms: u64,
+    received_ms: u64,
+    max_age: Duration,
+) -> Result<(), EnclaveError> {
+    let mut count = 0;
+    for key_id in key_ids {
+        if key_id != requested_id {
+            return Err(EnclaveError::DecryptionFailed(format!(
+                "key server returned key for {} instead of {}",
+                hex::encode(key_id),
+                hex::encode(requested_id)
+            )));
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Err(EnclaveError::DecryptionFailed(
+            "key server returned no keys".to_string(),
+        ));
+    }
+
+    let age_ms = received_ms.saturating_sub(session_created_ms);
+    if received_ms < session_created_ms || age_ms > max_age.as_millis() as u64 {
+        return Err(EnclaveError::DecryptionFailed(format!(
+            "stale key server response: {}ms after session creation (max {}ms)",
+            age_ms,
+            max_age.as_millis()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Run every request concurrently and return the first `threshold` successes.
+///
+/// Returns fewer than `threshold` results only when too many requests failed;
+/// the caller decides whether that is fatal.
+pub async fn collect_threshold<K, T, Fut>(requests: Vec<(K, Fut)>, threshold: usize) -> Vec<(K, T)>
+where
+    K: Display + Send + 'static,
+    T: Send + 'static,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let total = requests.len();
+    let mut pending = JoinSet::new();
+    for (key, request) in requests {
+        pending.spawn(async move { (key, request.await) });
+    }
+
+    let mut responses = Vec::new();
+    let mut failed = Vec::new();
+    while let Some(joined) = pending.join_next().await {
+        match joined {
+            Ok((key, Ok(response))) => {
+                responses.push((key, response));
+                if responses.len() >= threshold {
+                    break;
+                }
+            }
+            Ok((key, Err(e))) => {
+                warn!("  Key server {} failed: {:#}", key, e);
+                failed.push(key.to_string());
+            }
+            Err(e) => {
+                error!("  Key server task failed: {}", e);
+                failed.push("<task>".to_string());
+            }
+        }
+    }
+
+    // Cancel the stragglers
+    pending.abort_all();
+
+    if responses.len() >= threshold && !failed.is_empty() {
+        warn!(
+            "  SEAL threshold met, but {} of {} key servers failed: {}",
+            failed.len(),
+            total,
+            failed.join(", ")
+        );
+    }
+    responses
+}
+
+/// Why SEAL decryption failed, split by what the integrator should do about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SealFailure {
+    /// Too few key servers answered. Usually an outage or rate limit; the
+    /// same request can succeed later.
+    InsufficientShares { got: usize, need: usize },
+    /// Enough shares arrived but combining them failed: the configured key
+    /// server public keys, the encryption, or the policy don't match.
+    /// Retrying won't help.
+    CombineFailed(String),
+    /// Decryption returned a different number of plaintexts than objects
+    /// were passed in, so none can be attributed to its object
+    ResultCountMismatch { decrypted: usize, objects: usize },
+}
+
+impl SealFailure {
+    /// Stable identifier, leading the error message
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InsufficientShares { .. } => "seal_insufficient_shares",
+            Self::CombineFailed(_) => "seal_combine_failed",
+            Self::ResultCountMismatch { .. } => "seal_result_count_mismatch",
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::InsufficientShares { .. })
+    }
+}
+
+impl std::fmt::Display for SealFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InsufficientShares { got, need } => write!(
+                f,
+                "{}: insufficient key shares (got {}, need {}); key servers unavailable, retry later",
+                self.code(),
+                got,
+                need
+            ),
+            Self::CombineFailed(reason) => write!(
+                f,
+                "{}: key shares arrived but decryption failed ({}); \
+                 check key server public keys and the seal_approve policy",
+                self.code(),
+                reason
+            ),
+            Self::ResultCountMismatch { decrypted, objects } => write!(
+                f,
+                "{}: decrypted {} plaintext(s) for {} encrypted object(s); results can't be matched to their objects",
+                self.code(),
+                decrypted,
+                objects
+            ),
+        }
+    }
+}
+
+impl From<SealFailure> for EnclaveError {
+    fn from(failure: SealFailure) -> Self {
+        EnclaveError::DecryptionFailed(failure.to_string())
+    }
+}
+
+/// Fail with `InsufficientShares` unless `got` reaches `need`
+pub fn check_threshold(got: usize, need: usize) -> Result<(), SealFailure> {
+    if got < need {
+        return Err(SealFailure::InsufficientShares { got, need });
+    }
+    Ok(())
+}
+
+/// Pair each encrypted object with its plaintext. `seal_decrypt_all_objects`
+/// returns one plaintext per object, in input order; any other count (e.g.
+/// one object failed to decrypt) fails the whole batch rather than
+/// attributing a plaintext to the wrong object.
+pub fn pair_decrypted<T>(objects: &[T], decrypted: Vec<Vec<u8>>) -> Result<Vec<(&T, Vec<u8>)>, SealFailure> {
+    if decrypted.len() != objects.len() {
+        return Err(SealFailure::ResultCountMismatch { decrypted: decrypted.len(), objects: objects.len() });
+    }
+    Ok(objects.iter().zip(decrypted).collect())
+}
+
+/// IDs of the servers whose responses were collected, for logs and audit.
+/// Only the public identifiers; never the key material itself.
+pub fn contributing_servers<K: Display, T>(responses: &[(K, T)]) -> Vec<String> {
+    responses.iter().map(|(key, _)| key.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+    use tokio::time::{sleep, Instant};
+
+    type MockRequest = Pin<Box<dyn Future<Output = Result<u32>> + Send>>;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_returns_once_threshold_met() {
+        let started = Instant::now();
+        let requests: Vec<(&str, MockRequest)> = vec![
+            ("slow", Box::pin(async {
+                sleep(Duration::from_secs(3600)).await;
+                Ok(2)
+            })),
+            ("fast", Box::pin(async {
+                sleep(Duration::from_millis(50)).await;
+                Ok(1)
+            })),
+        ];
+
+        let responses = collect_threshold(requests, 1).await;
+        assert_eq!(responses, vec![("fast", 1)]);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failures_do_not_count() {
+        let requests: Vec<(&str, MockRequest)> = vec![
+            ("broken", Box::pin(async { Err(anyhow::anyhow!("503")) })),
+            ("a", Box::pin(async {
+                sleep(Duration::from_millis(10)).await;
+                Ok(1)
+            })),
+            ("b", Box::pin(async {
+                sleep(Duration::from_millis(20)).await;
+                Ok(2)
+            })),
+        ];
+
+        let mut responses = collect_threshold(requests, 2).await;
+        responses.sort();
+        assert_eq!(responses, vec![("a", 1), ("b", 2)]);
+
+        // Only the servers that answered are reported
+        let mut servers = contributing_servers(&responses);
+        servers.sort();
+        assert_eq!(servers, vec!["a".to_string(), "b".to_string()]);
+
+        // Not enough healthy servers: returns what it got
+        let requests: Vec<(&str, MockRequest)> = vec![
+            ("broken", Box::pin(async { Err(anyhow::anyhow!("503")) })),
+            ("a", Box::pin(async { Ok(1) })),
+        ];
+        assert_eq!(collect_threshold(requests, 2).await, vec![("a", 1)]);
+    }
+
+    /// Collects everything logged through `tracing` while it is alive
+    #[derive(Clone, Default)]
+    struct LogCapture(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogCapture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl LogCapture {
+        fn install(&self) -> tracing::subscriber::DefaultGuard {
+            let writer = self.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .finish();
+            tracing::subscriber::set_default(subscriber)
+        }
+
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).lines().map(str::to_string).collect()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_warns_when_threshold_met_despite_failures() {
+        let logs = LogCapture::default();
+        let _guard = logs.install();
+
+        let requests: Vec<(&str, MockRequest)> = vec![
+            ("degraded", Box::pin(async { Err(anyhow::anyhow!("503")) })),
+            ("healthy", Box::pin(async {
+                sleep(Duration::from_millis(10)).await;
+                Ok(1)
+            })),
+        ];
+        assert_eq!(collect_threshold(requests, 1).await, vec![("healthy", 1)]);
+
+        let warning = logs
+            .lines()
+            .into_iter()
+            .find(|line| line.contains("SEAL threshold met"))
+            .expect("no degraded-server warning logged");
+        assert!(warning.contains("WARN"), "{}", warning);
+        assert!(warning.contains("1 of 2 key servers failed: degraded"), "{}", warning);
+
+        // All servers healthy: no warning
+        let logs = LogCapture::default();
+        let _guard = logs.install();
+        let requests: Vec<(&str, MockRequest)> = vec![("a", Box::pin(async { Ok(1) })), ("b", Box::pin(async { Ok(2) }))];
+        assert_eq!(collect_threshold(requests, 2).await.len(), 2);
+        assert!(!logs.lines().iter().any(|line| line.contains("SEAL threshold met")));
+    }
+
+    #[test]
+    fn test_response_binding() {
+        let id = [7u8; 40];
+        let other = [8u8; 40];
+        let max_age = Duration::from_secs(60);
+
+        assert!(verify_response_binding(&id, [&id[..]], 1_000, 2_000, max_age).is_ok());
+
+        // Key for a different encryption ID
+        assert!(matches!(
+            verify_response_binding(&id, [&id[..], &other[..]], 1_000, 2_000, max_age),
+            Err(EnclaveError::DecryptionFailed(_))
+        ));
+
+        // Empty response
+        assert!(matches!(
+            verify_response_binding(&id, [], 1_000, 2_000, max_age),
+            Err(EnclaveError::DecryptionFailed(_))
+        ));
+
+        // Too old, and from before the session existed
+        assert!(matches!(
+            verify_response_binding(&id, [&id[..]], 1_000, 61_001, max_age),
+            Err(EnclaveError::DecryptionFailed(_))
+        ));
+        assert!(matches!(
+            verify_response_binding(&id, [&id[..]], 5_000, 4_000, max_age),
+            Err(EnclaveError::DecryptionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_insufficient_shares() {
+        assert!(check_threshold(2, 2).is_ok());
+        assert!(check_threshold(3, 2).is_ok());
+
+        let failure = check_threshold(1, 2).unwrap_err();
+        assert_eq!(failure, SealFailure::InsufficientShares { got: 1, need: 2 });
+        assert!(failure.is_retryable());
+        match EnclaveError::from(failure) {
+            EnclaveError::DecryptionFailed(msg) => {
+                assert!(msg.starts_with("seal_insufficient_shares: insufficient key shares (got 1, need 2)"), "{}", msg)
+            }
+            other => panic!("expected DecryptionFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sdk_version_mismatch_detected() {
+        let body = r#"{"error":"DeprecatedSDKVersion","message":"SDK version is deprecated"}"#;
+        match sdk_version_mismatch(400, body) {
+            Some(EnclaveError::SealVersionMismatch(msg)) => {
+                assert!(msg.starts_with("seal_sdk_version_rejected: key server refused Client-Sdk-Version 0.5.11 (400: "));
+                assert!(msg.contains("DeprecatedSDKVersion"), "{}", msg);
+            }
+            other => panic!("expected SealVersionMismatch, got {:?}", other),
+        }
+        assert!(sdk_version_mismatch(400, r#"{"error":"InvalidSDKVersion"}"#).is_some());
+
+        // Other errors are not version problems
+        assert!(sdk_version_mismatch(403, r#"{"error":"NoAccess"}"#).is_none());
+        assert!(sdk_version_mismatch(503, "").is_none());
+    }
+
+    #[test]
+    fn test_combine_failure() {
+        let failure = SealFailure::CombineFailed("invalid share".to_string());
+        assert!(!failure.is_retryable());
+        let msg = failure.to_string();
+        assert!(
+            msg.starts_with("seal_combine_failed: key shares arrived but decryption failed (invalid share); check"),
+            "{}",
+            msg
+        );
+        assert!(!msg.contains("retry"));
+        assert_ne!(failure.code(), SealFailure::InsufficientShares { got: 0, need: 2 }.code());
+    }
+
+    #[test]
+    fn test_decrypted_results_paired_with_objects() {
+        let objects = ["intent-a", "intent-b", "intent-c"];
+        let pairs = pair_decrypted(&objects, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]).unwrap();
+        let paired: Vec<(&str, &[u8])> = pairs.iter().map(|(object, plaintext)| (**object, plaintext.as_slice())).collect();
+        assert_eq!(paired, vec![("intent-a", &b"a"[..]), ("intent-b", &b"b"[..]), ("intent-c", &b"c"[..])]);
+
+        // intent-b failed to decrypt: without pairing, intent-c's plaintext would land on intent-b
+        let failure = pair_decrypted(&objects, vec![b"a".to_vec(), b"c".to_vec()]).unwrap_err();
+        assert_eq!(failure, SealFailure::ResultCountMismatch { decrypted: 2, objects: 3 });
+        assert!(!failure.is_retryable());
+        assert_eq!(
+            failure.to_string(),
+            "seal_result_count_mismatch: decrypted 2 plaintext(s) for 3 encrypted object(s); \
+             results can't be matched to their objects"
+        );
+
+        // Nothing at all for a single object
+        assert!(pair_decrypted(&["intent-a"], Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_session_ttl_bounds() {
+        let timeout = DEFAULT_DECRYPT_TIMEOUT;
+        assert_eq!(check_session_ttl(DEFAULT_SESSION_TTL_MIN, timeout).unwrap(), 10);
+        assert_eq!(check_session_ttl(MIN_SESSION_TTL_MIN, timeout).unwrap(), 1);
+        assert_eq!(check_session_ttl(MAX_SESSION_TTL_MIN, timeout).unwrap(), 30);
+
+        let err = check_session_ttl(0, timeout).unwrap_err().to_string();
+        assert_eq!(err, "MIST_SEAL_SESSION_TTL_MIN must be between 1 and 30 minutes, got 0");
+        assert!(check_session_ttl(31, timeout).is_err());
+
+        // In bounds, but the certificate could expire before a slow decryption ends
+        let err = check_session_ttl(2, Duration::from_secs(90)).unwrap_err().to_string();
+        assert!(err.contains("too short for a 90s fetch-and-decrypt timeout"), "{}", err);
+        assert!(check_session_ttl(3, Duration::from_secs(90)).is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_servers_trip_overall_timeout() {
+        // Each server answers within its own request timeout, but not together
+        let fetch_and_decrypt = |delay: Duration| async move {
+            let requests: Vec<(&str, MockRequest)> = vec![
+                ("a", Box::pin(async move {
+                    sleep(delay).await;
+                    Ok(1)
+                })),
+                ("b", Box::pin(async move {
+                    sleep(delay * 2).await;
+                    Ok(2)
+                })),
+            ];
+            let responses = collect_threshold(requests, 2).await;
+            check_threshold(responses.len(), 2).map_err(EnclaveError::from)?;
+            Ok(responses.iter().map(|(_, share)| share).sum::<u32>())
+        };
+
+        let started = Instant::now();
+        let err = with_decrypt_timeout(Duration::from_secs(15), fetch_and_decrypt(Duration::from_secs(8)))
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<EnclaveError>() {
+            Some(EnclaveError::KeyServerUnavailable(msg)) => {
+                assert_eq!(msg, "SEAL fetch and decrypt did not finish within 15s")
+            }
+            other => panic!("expected KeyServerUnavailable, got {:?}", other),
+        }
+        assert_eq!(started.elapsed(), Duration::from_secs(15));
+
+        // Fast enough: the result passes through
+        let shares = with_decrypt_timeout(Duration::from_secs(15), fetch_and_decrypt(Duration::from_secs(1)))
+            .await
+            .unwrap();
+        assert_eq!(shares, 3);
+    }
+}
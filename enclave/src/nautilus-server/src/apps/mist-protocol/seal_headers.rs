@@ -0,0 +1,106 @@
+//! Extra HTTP headers for SEAL key server requests
+//!
+//! Private key server deployments may require an API key or auth header.
+//! `MIST_SEAL_HEADERS` holds `;`-separated `Name: value` pairs, e.g.
+//! `MIST_SEAL_HEADERS="Authorization: Bearer abc; X-Api-Key: xyz"`.
+//! Values are secrets: `Debug` prints header names only.
+
+use anyhow::Result;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::RequestBuilder;
+use std::fmt;
+
+/// Environment variable holding extra SEAL request headers
+pub const SEAL_HEADERS_ENV: &str = "MIST_SEAL_HEADERS";
+
+#[derive(Clone, Default)]
+pub struct SealHeaders(Vec<(HeaderName, HeaderValue)>);
+
+impl SealHeaders {
+    /// Read headers from `MIST_SEAL_HEADERS`; unset means none
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(SEAL_HEADERS_ENV) {
+            Ok(value) => Self::parse(&value),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut headers = Vec::new();
+        for entry in s.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, value) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid SEAL header (expected `Name: value`)"))?;
+            let name = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|e| anyhow::anyhow!("Invalid SEAL header name {:?}: {}", name.trim(), e))?;
+            // The value is never echoed back in errors
+            let mut value = HeaderValue::from_str(value.trim())
+                .map_err(|_| anyhow::anyhow!("Invalid value for SEAL header {}", name))?;
+            value.set_sensitive(true);
+            headers.push((name, value));
+        }
+        Ok(Self(headers))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Attach every configured header to a SEAL request
+    pub fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        for (name, value) in &self.0 {
+            request = request.header(name.clone(), value.clone());
+        }
+        request
+    }
+}
+
+impl fmt::Debug for SealHeaders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, _) in &self.0 {
+            map.entry(&name.as_str(), &"<redacted>");
+        }
+        map.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let headers = SealHeaders::parse("Authorization: Bearer abc; X-Api-Key: k=v==").unwrap();
+        assert_eq!(headers.0.len(), 2);
+        assert_eq!(headers.0[0].0, "authorization");
+        assert_eq!(headers.0[1].1, "k=v==");
+
+        assert!(SealHeaders::parse("").unwrap().is_empty());
+        assert!(SealHeaders::parse("no-colon").is_err());
+        assert!(SealHeaders::parse("bad name: v").is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_values() {
+        let headers = SealHeaders::parse("X-Api-Key: super-secret").unwrap();
+        let printed = format!("{:?}", headers);
+        assert!(printed.contains("x-api-key"));
+        assert!(!printed.contains("super-secret"));
+    }
+
+    #[test]
+    fn test_headers_attached_to_request() {
+        let headers = SealHeaders::parse("Authorization: Bearer abc; X-Api-Key: xyz").unwrap();
+        let client = reqwest::Client::new();
+        let request = headers
+            .apply(client.post("https://seal.example.com/v1/fetch_key"))
+            .header("Content-Type", "application/json")
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["authorization"], "Bearer abc");
+        assert_eq!(request.headers()["x-api-key"], "xyz");
+        assert_eq!(request.headers()["content-type"], "application/json");
+    }
+}
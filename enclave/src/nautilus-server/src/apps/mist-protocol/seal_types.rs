@@ -6,8 +6,17 @@ use fastcrypto::serde_helpers::ToFromByteArray;
 use seal_sdk::IBEPublicKey;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use std::str::FromStr;
 use sui_sdk_types::ObjectId as ObjectID;
+use super::balance::normalize_coin_type;
+
+/// Environment variable naming a seal_config.yaml to use instead of the
+/// one compiled into the binary
+pub const SEAL_CONFIG_PATH_ENV: &str = "SEAL_CONFIG_PATH";
+
+/// seal_config.yaml as shipped with the binary
+pub const EMBEDDED_SEAL_CONFIG: &str = include_str!("seal_config.yaml");
 
 /// Custom deserializer for hex string to ObjectID
 fn deserialize_object_id<'de, D>(deserializer: D) -> Result<ObjectID, D::Error>
@@ -61,12 +70,268 @@ pub struct SealConfig {
     pub public_keys: Vec<IBEPublicKey>,
     /// Mist Protocol v2 package ID
     pub package_id: ObjectID,
+    /// MVR name of the package, when configured; passed to the key servers
+    /// in the SEAL certificate
+    pub mvr_name: Option<String>,
     /// LiquidityPool object ID (holds all deposits)
     pub pool_id: ObjectID,
     /// NullifierRegistry object ID (double-spend protection)
     pub registry_id: ObjectID,
     /// Map from server ID to public key
     pub server_pk_map: HashMap<ObjectID, IBEPublicKey>,
+    /// Map from server ID to the base URL its `/v1/fetch_key` is served under
+    pub server_url_map: HashMap<ObjectID, String>,
+    /// DEX configuration (optional)
+    pub dex: Option<DexConfig>,
+}
+
+impl SealConfig {
+    /// Load `path` if given, otherwise the embedded config. Either way the
+    /// result goes through the same validation. An `mvr_name` is checked
+    /// against `package_id` through the MVR API.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        Self::load_with(path, super::mvr::resolve_package_blocking)
+    }
+
+    /// `load`, resolving `mvr_name` with `resolve`. The package is always
+    /// the configured `package_id`: an unauthenticated lookup can't pick it,
+    /// and MVR names resolve to the latest version while SEAL identities are
+    /// bound to the original package. The resolution only has to agree.
+    pub fn load_with(
+        path: Option<&Path>,
+        resolve: impl FnOnce(&str) -> anyhow::Result<ObjectID>,
+    ) -> anyhow::Result<Self> {
+        let (yaml, source) = match path {
+            Some(path) => (
+                std::fs::read_to_string(path)
+                    .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?,
+                path.display().to_string(),
+            ),
+            None => (EMBEDDED_SEAL_CONFIG.to_string(), "embedded seal_config.yaml".to_string()),
+        };
+        let mut value: serde_yaml::Value =
+            serde_yaml::from_str(&yaml).map_err(|e| anyhow::anyhow!("Invalid {}: {}", source, e))?;
+
+        if let Some(name) = value.get("mvr_name").and_then(|name| name.as_str()).map(str::to_string) {
+            let Some(configured) = value.get("package_id").and_then(|id| id.as_str()) else {
+                anyhow::bail!("Invalid {}: mvr_name {} requires an explicit package_id", source, name);
+            };
+            let configured = ObjectID::from_str(configured)
+                .map_err(|e| anyhow::anyhow!("Invalid {}: package_id: {}", source, e))?;
+            let resolved = resolve(&name).map_err(|e| anyhow::anyhow!("Failed to resolve mvr_name {}: {}", name, e))?;
+            if configured != resolved {
+                anyhow::bail!(
+                    "Invalid {}: package_id {} does not match mvr_name {} ({})",
+                    source,
+                    configured,
+                    name,
+                    resolved
+                );
+            }
+        }
+
+        serde_yaml::from_value(value).map_err(|e| anyhow::anyhow!("Invalid {}: {}", source, e))
+    }
+
+    /// Load the file named by `SEAL_CONFIG_PATH`, or the embedded config when unset
+    pub fn from_env() -> anyhow::Result<Self> {
+        let path = std::env::var_os(SEAL_CONFIG_PATH_ENV).map(std::path::PathBuf::from);
+        Self::load(path.as_deref())
+    }
+}
+
+/// Default FlowX fee tier (0.3%) used when no pools are configured
+pub const DEFAULT_FLOWX_FEE_RATE: u64 = 3000;
+
+fn default_flowx_fee_rate() -> u64 {
+    DEFAULT_FLOWX_FEE_RATE
+}
+
+/// Maximum slippage a venue default may allow (50%)
+pub const MAX_SLIPPAGE_BPS: u64 = 5000;
+
+/// `dex` section of seal_config.yaml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DexConfig {
+    #[serde(default)]
+    pub flowx: Option<FlowXDexConfig>,
+    /// DEX packages the executor may call. A swap into any other package is
+    /// refused, even if a venue section points at it.
+    #[serde(default)]
+    pub allowed_packages: Vec<String>,
+    /// Per-pair caps on the slippage a swap may tolerate, whatever minimum
+    /// output it was built with
+    #[serde(default)]
+    pub slippage_limits: Vec<SlippageLimit>,
+}
+
+/// Maximum slippage for swaps between two coin types, in either direction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlippageLimit {
+    pub token_a: String,
+    pub token_b: String,
+    pub max_slippage_bps: u64,
+}
+
+impl SlippageLimit {
+    fn matches(&self, token_in: &str, token_out: &str) -> bool {
+        let (a, b) = (normalize_coin_type(&self.token_a), normalize_coin_type(&self.token_b));
+        let (token_in, token_out) = (normalize_coin_type(token_in), normalize_coin_type(token_out));
+        (a == token_in && b == token_out) || (a == token_out && b == token_in)
+    }
+}
+
+impl DexConfig {
+    /// Reject a swap MoveCall into a package not in `allowed_packages`
+    pub fn check_package(&self, package_id: &str) -> Result<(), crate::EnclaveError> {
+        let normalized = super::normalize_address(package_id);
+        if self
+            .allowed_packages
+            .iter()
+            .any(|allowed| super::normalize_address(allowed) == normalized)
+        {
+            return Ok(());
+        }
+        Err(crate::EnclaveError::InvalidInput(format!(
+            "DEX package {} is not in dex.allowed_packages",
+            package_id
+        )))
+    }
+
+    /// Reject a swap whose `min_amount_out` tolerates more slippage below
+    /// `estimated_out` than its pair's limit. Pairs without a limit pass;
+    /// a swap with no quote tolerates any output and fails a listed pair.
+    pub fn check_slippage(
+        &self,
+        token_in: &str,
+        token_out: &str,
+        estimated_out: u64,
+        min_amount_out: u64,
+    ) -> Result<(), crate::EnclaveError> {
+        let Some(limit) = self.slippage_limits.iter().find(|limit| limit.matches(token_in, token_out)) else {
+            return Ok(());
+        };
+        let tolerated_bps = tolerated_slippage_bps(estimated_out, min_amount_out);
+        if tolerated_bps > limit.max_slippage_bps {
+            return Err(crate::EnclaveError::InvalidInput(format!(
+                "slippage tolerance {} bps exceeds the {} bps limit for {}/{}",
+                tolerated_bps, limit.max_slippage_bps, token_in, token_out
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Basis points `min_amount_out` lies below `estimated_out`, rounded down so
+/// a minimum derived from the same bps (which rounds down) stays within it
+fn tolerated_slippage_bps(estimated_out: u64, min_amount_out: u64) -> u64 {
+    if estimated_out == 0 {
+        return 10_000;
+    }
+    let shortfall = estimated_out.saturating_sub(min_amount_out) as u128;
+    (shortfall * 10_000 / estimated_out as u128) as u64
+}
+
+/// FlowX venue configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowXDexConfig {
+    pub package_id: String,
+    pub pool_registry_id: String,
+    pub versioned_id: String,
+    /// Fee tier used when no candidate pools are listed
+    #[serde(default = "default_flowx_fee_rate")]
+    pub default_fee_rate: u64,
+    /// Candidate pools for the pair (one per fee tier), evaluated by the
+    /// pool selection strategy
+    #[serde(default)]
+    pub pools: Vec<FlowXPoolEntry>,
+    /// Slippage (basis points below the quoted output) applied when the
+    /// intent doesn't carry its own minimum
+    #[serde(default)]
+    pub default_slippage_bps: Option<u64>,
+    /// Allow swaps that accept any output (no default slippage, or no quote).
+    /// Off by default: such swaps are refused.
+    #[serde(default)]
+    pub allow_unprotected_output: bool,
+    /// Known `Pool<X, Y>` coin ordering per pair. The swap direction is
+    /// checked against the ordering listed for its pair.
+    #[serde(default)]
+    pub pool_orderings: Vec<PoolOrdering>,
+    /// Refuse default-fee-tier swaps for pairs without a listed ordering,
+    /// instead of inferring the direction from type-name ordering
+    #[serde(default)]
+    pub require_pool_ordering: bool,
+}
+
+/// Coin ordering of a FlowX pool: `Pool<coin_x, coin_y>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolOrdering {
+    pub coin_x: String,
+    pub coin_y: String,
+}
+
+impl PoolOrdering {
+    fn matches(&self, token_in: &str, token_out: &str) -> bool {
+        let (x, y) = (normalize_coin_type(&self.coin_x), normalize_coin_type(&self.coin_y));
+        let (token_in, token_out) = (normalize_coin_type(token_in), normalize_coin_type(token_out));
+        (x == token_in && y == token_out) || (x == token_out && y == token_in)
+    }
+}
+
+impl FlowXDexConfig {
+    /// `min_amount_out` for a swap quoted at `estimated_out`.
+    ///
+    /// Without a venue default or a quote (e.g. no candidate pools configured)
+    /// there is no protected minimum: that is refused unless
+    /// `allow_unprotected_output` is set, in which case 1 (any output) is used.
+    pub fn min_amount_out(&self, estimated_out: u64) -> Result<u64, crate::EnclaveError> {
+        match self.default_slippage_bps {
+            Some(bps) if estimated_out > 0 => {
+                Ok(crate::flowx::utils::math::min_amount_out(estimated_out, bps).max(1))
+            }
+            _ if self.allow_unprotected_output => Ok(1),
+            _ => Err(crate::EnclaveError::InvalidInput(
+                "min_amount_out would accept any output; slippage protection disabled \
+                 (set dex.flowx.allow_unprotected_output to allow)"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Listed pool ordering for the `token_in`/`token_out` pair, either way round
+    pub fn pool_ordering(&self, token_in: &str, token_out: &str) -> Option<&PoolOrdering> {
+        self.pool_orderings.iter().find(|ordering| ordering.matches(token_in, token_out))
+    }
+}
+
+/// Reason the listed pool orderings can't be used: a pool of one coin, or a
+/// pair listed twice in opposite orders
+fn pool_ordering_error(orderings: &[PoolOrdering]) -> Option<String> {
+    for (i, ordering) in orderings.iter().enumerate() {
+        if normalize_coin_type(&ordering.coin_x) == normalize_coin_type(&ordering.coin_y) {
+            return Some(format!(
+                "dex.flowx.pool_orderings lists {} on both sides",
+                ordering.coin_x
+            ));
+        }
+        let conflict = orderings[..i].iter().any(|earlier| {
+            earlier.matches(&ordering.coin_x, &ordering.coin_y)
+                && normalize_coin_type(&earlier.coin_x) != normalize_coin_type(&ordering.coin_x)
+        });
+        if conflict {
+            return Some(format!(
+                "dex.flowx.pool_orderings lists {}/{} in both orders",
+                ordering.coin_x, ordering.coin_y
+            ));
+        }
+    }
+    None
+}
+
+/// A candidate FlowX pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowXPoolEntry {
+    pub pool_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -75,18 +340,26 @@ struct SealConfigRaw {
     key_servers: Vec<ObjectID>,
     #[serde(deserialize_with = "deserialize_ibe_public_keys")]
     public_keys: Vec<IBEPublicKey>,
+    key_server_urls: Vec<String>,
     #[serde(deserialize_with = "deserialize_object_id")]
     package_id: ObjectID,
+    #[serde(default)]
+    mvr_name: Option<String>,
     #[serde(deserialize_with = "deserialize_object_id")]
     pool_id: ObjectID,
     #[serde(deserialize_with = "deserialize_object_id")]
     registry_id: ObjectID,
+    #[serde(default)]
+    dex: Option<DexConfig>,
 }
 
 impl TryFrom<SealConfigRaw> for SealConfig {
     type Error = String;
 
     fn try_from(raw: SealConfigRaw) -> Result<Self, Self::Error> {
+        if raw.key_servers.is_empty() {
+            return Err("key_servers must not be empty".to_string());
+        }
         if raw.key_servers.len() != raw.public_keys.len() {
             return Err(format!(
                 "key_servers and public_keys length mismatch: {} vs {}",
@@ -102,13 +375,316 @@ impl TryFrom<SealConfigRaw> for SealConfig {
             .map(|(id, pk)| (*id, *pk))
             .collect();
 
+        if raw.key_servers.len() != raw.key_server_urls.len() {
+            return Err(format!(
+                "key_servers and key_server_urls length mismatch: {} vs {}",
+                raw.key_servers.len(),
+                raw.key_server_urls.len()
+            ));
+        }
+        let server_url_map: HashMap<ObjectID, String> = raw
+            .key_servers
+            .iter()
+            .zip(raw.key_server_urls)
+            .map(|(id, url)| (*id, url.trim_end_matches('/').to_string()))
+            .collect();
+
+        if let Some(bps) = raw
+            .dex
+            .as_ref()
+            .and_then(|dex| dex.flowx.as_ref())
+            .and_then(|flowx| flowx.default_slippage_bps)
+        {
+            if bps > MAX_SLIPPAGE_BPS {
+                return Err(format!(
+                    "dex.flowx.default_slippage_bps must be at most {}, got {}",
+                    MAX_SLIPPAGE_BPS, bps
+                ));
+            }
+        }
+
+        if let Some(err) = raw
+            .dex
+            .as_ref()
+            .and_then(|dex| dex.flowx.as_ref())
+            .and_then(|flowx| pool_ordering_error(&flowx.pool_orderings))
+        {
+            return Err(err);
+        }
+
+        if let Some(limit) = raw
+            .dex
+            .iter()
+            .flat_map(|dex| &dex.slippage_limits)
+            .find(|limit| limit.max_slippage_bps > MAX_SLIPPAGE_BPS)
+        {
+            return Err(format!(
+                "dex.slippage_limits for {}/{} must be at most {} bps, got {}",
+                limit.token_a, limit.token_b, MAX_SLIPPAGE_BPS, limit.max_slippage_bps
+            ));
+        }
+
         Ok(SealConfig {
             key_servers: raw.key_servers,
             public_keys: raw.public_keys,
             package_id: raw.package_id,
+            mvr_name: raw.mvr_name,
             pool_id: raw.pool_id,
             registry_id: raw.registry_id,
             server_pk_map,
+            server_url_map,
+            dex: raw.dex,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flowx(yaml: &str) -> FlowXDexConfig {
+        let base = "package_id: \"0x1\"\npool_registry_id: \"0x2\"\nversioned_id: \"0x3\"\n";
+        serde_yaml::from_str(&format!("{}{}", base, yaml)).unwrap()
+    }
+
+    #[test]
+    fn test_flowx_default_slippage_applied() {
+        let venue = flowx("default_slippage_bps: 100\n");
+        assert_eq!(venue.default_slippage_bps, Some(100));
+        assert_eq!(venue.min_amount_out(1_000_000).unwrap(), 990_000);
+        // No quote (no candidate pools): nothing to apply slippage to
+        assert!(venue.min_amount_out(0).is_err());
+        // Tiny quotes never round down to zero
+        assert_eq!(venue.min_amount_out(1).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_unlisted_dex_package_rejected() {
+        let dex: DexConfig = serde_yaml::from_str(
+            "allowed_packages:\n  - \"0x6cc1ce379acd35203f856f1dd0e063023caf091c47ce19b4695299de8b5fcb17\"\n",
+        )
+        .unwrap();
+
+        assert!(dex
+            .check_package("0x6CC1CE379ACD35203F856F1DD0E063023CAF091C47CE19B4695299DE8B5FCB17")
+            .is_ok());
+        assert!(matches!(
+            dex.check_package("0xbad0000000000000000000000000000000000000000000000000000000000bad"),
+            Err(crate::EnclaveError::InvalidInput(_))
+        ));
+
+        // No allowlist: no DEX calls at all
+        assert!(DexConfig::default().check_package("0x1").is_err());
+    }
+
+    #[test]
+    fn test_config_path_overrides_embedded() {
+        let embedded = SealConfig::load(None).unwrap();
+
+        let pool_id = "0x00000000000000000000000000000000000000000000000000000000000000aa";
+        let mut overridden: serde_yaml::Value = serde_yaml::from_str(EMBEDDED_SEAL_CONFIG).unwrap();
+        overridden["pool_id"] = pool_id.into();
+        let path = std::env::temp_dir().join(format!("seal_config_override_{}.yaml", std::process::id()));
+        std::fs::write(&path, serde_yaml::to_string(&overridden).unwrap()).unwrap();
+
+        let loaded = SealConfig::load(Some(&path));
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.pool_id, ObjectID::from_str(pool_id).unwrap());
+        assert_ne!(loaded.pool_id, embedded.pool_id);
+        assert_eq!(loaded.key_servers, embedded.key_servers);
+    }
+
+    #[test]
+    fn test_bad_config_path_is_rejected() {
+        let missing = std::env::temp_dir().join("seal_config_does_not_exist.yaml");
+        let err = SealConfig::load(Some(&missing)).unwrap_err().to_string();
+        assert!(err.starts_with("Failed to read"), "{}", err);
+
+        // Parses as YAML but fails validation
+        let path = std::env::temp_dir().join(format!("seal_config_invalid_{}.yaml", std::process::id()));
+        let mut invalid: serde_yaml::Value = serde_yaml::from_str(EMBEDDED_SEAL_CONFIG).unwrap();
+        invalid["public_keys"] = serde_yaml::Value::Sequence(Vec::new());
+        std::fs::write(&path, serde_yaml::to_string(&invalid).unwrap()).unwrap();
+
+        let err = SealConfig::load(Some(&path)).unwrap_err().to_string();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.contains("length mismatch"), "{}", err);
+    }
+
+    const EMBEDDED_SEAL_CONFIG_PACKAGE: &str = "0x721e4d1a9c221974966e8e6768c88e313e0bf7d580a3514239efaf4635943109";
+
+    fn write_config(name: &str, yaml: &serde_yaml::Value) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("seal_config_{}_{}.yaml", name, std::process::id()));
+        std::fs::write(&path, serde_yaml::to_string(yaml).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_mvr_name_cross_checks_package() {
+        let resolve = |name: &str| {
+            assert_eq!(name, "@mist/protocol");
+            Ok(ObjectID::from_str(EMBEDDED_SEAL_CONFIG_PACKAGE).unwrap())
+        };
+
+        // Alongside a package_id that agrees
+        let mut yaml: serde_yaml::Value = serde_yaml::from_str(EMBEDDED_SEAL_CONFIG).unwrap();
+        yaml["mvr_name"] = "@mist/protocol".into();
+        let path = write_config("mvr_match", &yaml);
+        let loaded = SealConfig::load_with(Some(&path), resolve);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.package_id, ObjectID::from_str(EMBEDDED_SEAL_CONFIG_PACKAGE).unwrap());
+        assert_eq!(loaded.mvr_name.as_deref(), Some("@mist/protocol"));
+
+        // A later version (or another package) is refused, not adopted
+        let upgraded = |_: &str| {
+            Ok(ObjectID::from_str("0x00000000000000000000000000000000000000000000000000000000000000bb").unwrap())
+        };
+        let path = write_config("mvr_mismatch", &yaml);
+        let err = SealConfig::load_with(Some(&path), upgraded).unwrap_err().to_string();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.contains("does not match mvr_name @mist/protocol"), "{}", err);
+
+        // mvr_name can't stand in for package_id: nothing is looked up
+        let mut name_only = yaml.clone();
+        name_only.as_mapping_mut().unwrap().remove("package_id");
+        let path = write_config("mvr_only", &name_only);
+        let err = SealConfig::load_with(Some(&path), |_| panic!("unexpected MVR lookup"))
+            .unwrap_err()
+            .to_string();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.ends_with("mvr_name @mist/protocol requires an explicit package_id"), "{}", err);
+
+        // Resolution failures stop the load
+        let path = write_config("mvr_unresolved", &yaml);
+        let err = SealConfig::load_with(Some(&path), |_| anyhow::bail!("404 Not Found"))
+            .unwrap_err()
+            .to_string();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err, "Failed to resolve mvr_name @mist/protocol: 404 Not Found");
+
+        // No mvr_name: nothing is resolved
+        let embedded = SealConfig::load_with(None, |_| panic!("unexpected MVR lookup")).unwrap();
+        assert_eq!(embedded.mvr_name, None);
+    }
+
+    #[test]
+    fn test_key_server_urls() {
+        let config = SealConfig::load(None).unwrap();
+        assert_eq!(config.server_url_map.len(), config.key_servers.len());
+        assert!(config.key_servers.iter().all(|id| config.server_url_map[id].starts_with("https://")));
+
+        let mut yaml: serde_yaml::Value = serde_yaml::from_str(EMBEDDED_SEAL_CONFIG).unwrap();
+        yaml["key_server_urls"] = serde_yaml::Value::Sequence(vec!["http://127.0.0.1:9000/".into()]);
+        let err = serde_yaml::from_value::<SealConfig>(yaml).unwrap_err().to_string();
+        assert!(err.contains("key_servers and key_server_urls length mismatch"), "{}", err);
+    }
+
+    const USDC: &str = "0xa1ec7fc00a6f40db9693ad1415d0c193ad3906494428cf252621037bd7117e29::usdc::USDC";
+    const USDT: &str = "0x0588cff9a50e0eaf4cd50d337c1a36570bc1517793fd3303e1513e8ad4d2aa96::usdt::USDT";
+
+    fn dex_with_limits() -> DexConfig {
+        serde_yaml::from_str(&format!(
+            "slippage_limits:\n  - token_a: \"{}\"\n    token_b: \"{}\"\n    max_slippage_bps: 100\n  \
+             - token_a: \"0x2::sui::SUI\"\n    token_b: \"{}\"\n    max_slippage_bps: 500\n",
+            USDC, USDT, USDC
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_stable_pair_tight_slippage_limit() {
+        let dex = dex_with_limits();
+        // 1% is allowed, in either direction
+        assert!(dex.check_slippage(USDC, USDT, 1_000_000, 990_000).is_ok());
+        assert!(dex.check_slippage(USDT, USDC, 1_000_000, 990_000).is_ok());
+        // 2% is not, even though it is within the venue-wide maximum
+        let err = dex.check_slippage(USDC, USDT, 1_000_000, 980_000).unwrap_err();
+        assert!(matches!(err, crate::EnclaveError::InvalidInput(_)));
+        assert!(err.to_string().contains("slippage tolerance 200 bps exceeds the 100 bps limit"), "{}", err);
+        // A minimum derived from the same bps rounds down but still passes
+        let min = flowx("default_slippage_bps: 100\n").min_amount_out(999).unwrap();
+        assert!(dex.check_slippage(USDC, USDT, 999, min).is_ok());
+    }
+
+    #[test]
+    fn test_volatile_pair_looser_slippage_limit() {
+        let dex = dex_with_limits();
+        // Short address form of SUI matches the configured one
+        let sui = "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI";
+        assert!(dex.check_slippage(sui, USDC, 1_000_000, 960_000).is_ok());
+        assert!(dex.check_slippage(USDC, sui, 1_000_000, 950_000).is_ok());
+        assert!(dex.check_slippage(sui, USDC, 1_000_000, 940_000).is_err());
+        // Accepting any output (no venue default, or no quote) is never within a limit
+        assert!(dex.check_slippage(sui, USDC, 1_000_000, 1).is_err());
+        assert!(dex.check_slippage(sui, USDC, 0, 1).is_err());
+        // Pairs without a limit are not checked
+        assert!(dex.check_slippage(sui, USDT, 1_000_000, 1).is_ok());
+    }
+
+    #[test]
+    fn test_slippage_limit_above_max_rejected() {
+        let mut yaml: serde_yaml::Value = serde_yaml::from_str(EMBEDDED_SEAL_CONFIG).unwrap();
+        yaml["dex"]["slippage_limits"] = serde_yaml::from_str(&format!(
+            "- token_a: \"0x2::sui::SUI\"\n  token_b: \"{}\"\n  max_slippage_bps: 6000\n",
+            USDC
+        ))
+        .unwrap();
+        let err = serde_yaml::from_value::<SealConfig>(yaml).unwrap_err().to_string();
+        assert!(err.contains("dex.slippage_limits for 0x2::sui::SUI/"), "{}", err);
+        assert!(err.contains("must be at most 5000 bps, got 6000"), "{}", err);
+    }
+
+    #[test]
+    fn test_pool_ordering_lookup() {
+        let venue = flowx(&format!(
+            "pool_orderings:\n  - coin_x: \"{}\"\n    coin_y: \"0x2::sui::SUI\"\n",
+            USDC
+        ));
+        let sui = "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI";
+        // Found whichever way the swap goes, with the pool's own ordering
+        for (token_in, token_out) in [(sui, USDC), (USDC, sui)] {
+            let ordering = venue.pool_ordering(token_in, token_out).unwrap();
+            assert_eq!(ordering.coin_x, USDC);
+        }
+        assert!(venue.pool_ordering(sui, USDT).is_none());
+        assert!(!venue.require_pool_ordering);
+    }
+
+    #[test]
+    fn test_ambiguous_pool_ordering_rejected() {
+        let mut yaml: serde_yaml::Value = serde_yaml::from_str(EMBEDDED_SEAL_CONFIG).unwrap();
+        yaml["dex"]["flowx"]["pool_orderings"] = serde_yaml::from_str(&format!(
+            "- coin_x: \"0x2::sui::SUI\"\n  coin_y: \"{0}\"\n- coin_x: \"{0}\"\n  coin_y: \"0x2::sui::SUI\"\n",
+            USDC
+        ))
+        .unwrap();
+        let err = serde_yaml::from_value::<SealConfig>(yaml.clone()).unwrap_err().to_string();
+        assert!(err.contains("in both orders"), "{}", err);
+
+        yaml["dex"]["flowx"]["pool_orderings"] =
+            serde_yaml::from_str("- coin_x: \"0x2::sui::SUI\"\n  coin_y: \"0x02::sui::SUI\"\n").unwrap();
+        let err = serde_yaml::from_value::<SealConfig>(yaml).unwrap_err().to_string();
+        assert!(err.contains("on both sides"), "{}", err);
+    }
+
+    #[test]
+    fn test_unprotected_output_refused_by_default() {
+        let venue = flowx("");
+        assert_eq!(venue.default_slippage_bps, None);
+        let err = venue.min_amount_out(1_000_000).unwrap_err();
+        assert!(matches!(err, crate::EnclaveError::InvalidInput(_)));
+        assert!(err.to_string().contains("slippage protection disabled"), "{}", err);
+    }
+
+    #[test]
+    fn test_unprotected_output_allowed_by_override() {
+        let venue = flowx("allow_unprotected_output: true\n");
+        assert_eq!(venue.min_amount_out(1_000_000).unwrap(), 1);
+        assert_eq!(venue.min_amount_out(0).unwrap(), 1);
+        // A configured default still applies when there is a quote
+        let venue = flowx("allow_unprotected_output: true\ndefault_slippage_bps: 100\n");
+        assert_eq!(venue.min_amount_out(1_000_000).unwrap(), 990_000);
+    }
+}
@@ -0,0 +1,427 @@
+//! Backend key signing, abstracted over where the key lives
+//!
+//! The backend key signs SEAL session certificates (personal messages) and
+//! swap transactions, and its address is the one authorized in the contract.
+//! `LocalSigner` holds the key in memory (`BACKEND_PRIVATE_KEY`).
+//! `KmsSigner` delegates to an external signing endpoint speaking the
+//! tx-signer protocol (`POST /sign`), so the key never enters this process.
+//!
+//! `MIST_KMS_SIGNER_URL` + `MIST_KMS_SIGNER_ADDRESS` select the KMS signer;
+//! otherwise the local key is used. The endpoint can't sign personal messages
+//! yet, so without SEAL session certificates no intent could be decrypted:
+//! startup refuses KMS mode until it can.
+
+use anyhow::Result;
+use base64::Engine;
+use fastcrypto::ed25519::Ed25519KeyPair;
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use fastcrypto::traits::{KeyPair as _, Signer as _, ToFromBytes};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use sui_crypto::ed25519::Ed25519PrivateKey;
+use sui_sdk_types::{Address, PersonalMessage, UserSignature};
+
+/// Environment variable with the external signing endpoint's base URL
+pub const KMS_SIGNER_URL_ENV: &str = "MIST_KMS_SIGNER_URL";
+/// Environment variable with the Sui address of the KMS-held key
+pub const KMS_SIGNER_ADDRESS_ENV: &str = "MIST_KMS_SIGNER_ADDRESS";
+
+/// Boxed future returned by `Signer` methods, so the trait stays object safe
+pub type SignFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+pub trait Signer: Send + Sync {
+    /// Sui address of the signing key
+    fn address(&self) -> Address;
+
+    /// Sign a personal message (used for SEAL session certificates)
+    fn sign_personal_message<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a, UserSignature>;
+
+    /// Sign BCS-encoded `TransactionData`, returning the serialized Sui
+    /// signature (`flag || signature || public key`)
+    fn sign_transaction<'a>(&'a self, tx_bytes: &'a [u8]) -> SignFuture<'a, Vec<u8>>;
+}
+
+/// Signer backed by an in-memory Ed25519 key
+pub struct LocalSigner {
+    keypair: Ed25519KeyPair,
+    sui_key: Ed25519PrivateKey,
+}
+
+impl LocalSigner {
+    pub fn from_keypair(keypair: &Ed25519KeyPair) -> Self {
+        let key_bytes: [u8; 32] = keypair
+            .as_ref()
+            .try_into()
+            .expect("ed25519 private key is 32 bytes");
+        Self {
+            keypair: Ed25519KeyPair::from_bytes(&key_bytes).expect("valid ed25519 key"),
+            sui_key: Ed25519PrivateKey::new(key_bytes),
+        }
+    }
+}
+
+impl Signer for LocalSigner {
+    fn address(&self) -> Address {
+        self.sui_key.public_key().to_address()
+    }
+
+    fn sign_personal_message<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a, UserSignature> {
+        use sui_crypto::SuiSigner;
+        let signature = self
+            .sui_key
+            .sign_personal_message(&PersonalMessage(message.into()))
+            .map_err(|e| anyhow::anyhow!("Failed to sign: {}", e));
+        Box::pin(async move { signature })
+    }
+
+    fn sign_transaction<'a>(&'a self, tx_bytes: &'a [u8]) -> SignFuture<'a, Vec<u8>> {
+        let signature = self.keypair.sign(transaction_digest(tx_bytes).as_ref());
+
+        let mut sig_bytes = vec![0x00]; // Ed25519 flag
+        sig_bytes.extend_from_slice(signature.as_ref());
+        sig_bytes.extend_from_slice(self.keypair.public().as_ref());
+        Box::pin(async move { Ok(sig_bytes) })
+    }
+}
+
+/// Request body of the signing endpoint (same as tx-signer's `/sign`)
+#[derive(Debug, Serialize, Deserialize)]
+struct SignRequest {
+    address: String,
+    tx_data_b64: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SignResponse {
+    /// Base64 serialized Sui signature
+    signature: String,
+}
+
+/// Signer that forwards to an external signing endpoint (KMS / HSM front)
+pub struct KmsSigner {
+    client: reqwest::Client,
+    url: String,
+    address: Address,
+}
+
+impl KmsSigner {
+    pub fn new(url: &str, address: Address) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+            address,
+        }
+    }
+}
+
+impl Signer for KmsSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign_personal_message<'a>(&'a self, _message: &'a [u8]) -> SignFuture<'a, UserSignature> {
+        // The tx-signer protocol only covers transactions so far
+        Box::pin(async {
+            Err(anyhow::anyhow!(
+                "KmsSigner: personal message signing is not supported by the signing endpoint"
+            ))
+        })
+    }
+
+    fn sign_transaction<'a>(&'a self, tx_bytes: &'a [u8]) -> SignFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            let request = SignRequest {
+                address: self.address.to_string(),
+                tx_data_b64: base64::engine::general_purpose::STANDARD.encode(tx_bytes),
+            };
+            let response = self
+                .client
+                .post(format!("{}/sign", self.url))
+                .json(&request)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                anyhow::bail!(
+                    "Signing endpoint returned {}: {}",
+                    response.status(),
+                    response.text().await.unwrap_or_default()
+                );
+            }
+            let body: SignResponse = response.json().await?;
+            let signature = base64::engine::general_purpose::STANDARD
+                .decode(body.signature)
+                .map_err(|e| anyhow::anyhow!("Invalid signature from signing endpoint: {}", e))?;
+
+            // The endpoint may hold several keys; only ours may sign
+            let signed_by = signature_address(&signature)?;
+            if signed_by != self.address {
+                anyhow::bail!(
+                    "Signing endpoint signed with {} instead of {}",
+                    signed_by,
+                    self.address
+                );
+            }
+            Ok(signature)
+        })
+    }
+}
+
+/// Sui address of the public key carried by a serialized Sui signature
+/// (`flag || signature || public key`)
+fn signature_address(signature: &[u8]) -> Result<Address> {
+    let public_key_len = match signature.first() {
+        Some(0x00) => 32,        // Ed25519
+        Some(0x01 | 0x02) => 33, // Secp256k1, Secp256r1
+        _ => anyhow::bail!("Unsupported signature scheme from signing endpoint"),
+    };
+    if signature.len() != 1 + 64 + public_key_len {
+        anyhow::bail!("Malformed signature from signing endpoint ({} bytes)", signature.len());
+    }
+    let mut flagged_key = vec![signature[0]];
+    flagged_key.extend_from_slice(&signature[65..]);
+    Ok(Address::new(Blake2b256::digest(&flagged_key).digest))
+}
+
+/// Where the backend key lives, from the environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerConfig {
+    /// `BACKEND_PRIVATE_KEY`
+    Local,
+    /// External signing endpoint
+    Kms { url: String, address: Address },
+}
+
+impl SignerConfig {
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(
+            std::env::var(KMS_SIGNER_URL_ENV).ok(),
+            std::env::var(KMS_SIGNER_ADDRESS_ENV).ok(),
+        )
+    }
+
+    /// Config from the values of `MIST_KMS_SIGNER_URL` and `MIST_KMS_SIGNER_ADDRESS`.
+    /// KMS mode is refused for now: without SEAL session certificates no
+    /// intent could be decrypted.
+    pub fn from_vars(url: Option<String>, address: Option<String>) -> Result<Self> {
+        if url.is_none() {
+            return Ok(Self::Local);
+        }
+        let address = address
+            .ok_or_else(|| anyhow::anyhow!("{} requires {}", KMS_SIGNER_URL_ENV, KMS_SIGNER_ADDRESS_ENV))?;
+        address
+            .parse::<Address>()
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {:?}", KMS_SIGNER_ADDRESS_ENV, e))?;
+        anyhow::bail!(
+            "{} is not supported yet: the signing endpoint cannot sign SEAL session certificates \
+             (personal messages); unset it to use BACKEND_PRIVATE_KEY",
+            KMS_SIGNER_URL_ENV
+        )
+    }
+
+    /// Build the signer; `local_key` is only used for `Local`
+    pub fn build(&self, local_key: &Ed25519KeyPair) -> Box<dyn Signer> {
+        match self {
+            Self::Local => Box::new(LocalSigner::from_keypair(local_key)),
+            Self::Kms { url, address } => Box::new(KmsSigner::new(url, *address)),
+        }
+    }
+}
+
+/// Blake2b256 over the `TransactionData` intent message, the bytes Sui signs
+pub fn transaction_digest(tx_bytes: &[u8]) -> [u8; 32] {
+    let mut intent_message = vec![0, 0, 0]; // TransactionData intent
+    intent_message.extend_from_slice(tx_bytes);
+    Blake2b256::digest(&intent_message).digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::intent_processor::DecryptOutcome;
+    use crate::app::DecryptedSwapDetails;
+    use crate::AppState;
+    use fastcrypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+    use fastcrypto::traits::{KeyPair, VerifyingKey};
+    use std::sync::{Arc, Mutex};
+
+    /// Records what it was asked to sign
+    struct FakeSigner {
+        address: Address,
+        signed: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl Signer for FakeSigner {
+        fn address(&self) -> Address {
+            self.address
+        }
+
+        fn sign_personal_message<'a>(&'a self, _message: &'a [u8]) -> SignFuture<'a, UserSignature> {
+            Box::pin(async { Err(anyhow::anyhow!("not used")) })
+        }
+
+        fn sign_transaction<'a>(&'a self, tx_bytes: &'a [u8]) -> SignFuture<'a, Vec<u8>> {
+            self.signed.lock().unwrap().push(tx_bytes.to_vec());
+            Box::pin(async { Ok(vec![0xaa; 97]) })
+        }
+    }
+
+    fn keypair() -> Ed25519KeyPair {
+        Ed25519KeyPair::generate(&mut rand::thread_rng())
+    }
+
+    #[tokio::test]
+    async fn test_local_signer_transaction_signature() {
+        let kp = keypair();
+        let signer = LocalSigner::from_keypair(&kp);
+
+        let sig = signer.sign_transaction(b"tx-data").await.unwrap();
+        assert_eq!(sig.len(), 1 + 64 + 32);
+        assert_eq!(sig[0], 0x00);
+        assert_eq!(&sig[65..], kp.public().as_ref());
+
+        let signature = Ed25519Signature::from_bytes(&sig[1..65]).unwrap();
+        let pk = Ed25519PublicKey::from_bytes(&sig[65..]).unwrap();
+        assert!(pk.verify(&transaction_digest(b"tx-data"), &signature).is_ok());
+        assert!(pk.verify(&transaction_digest(b"other"), &signature).is_err());
+
+        assert!(signer.sign_personal_message(b"seal session").await.is_ok());
+    }
+
+    /// Digest a wallet signs for `message` under the PersonalMessage intent
+    fn personal_message_digest(message: &[u8]) -> [u8; 32] {
+        let mut intent_message = vec![3, 0, 0]; // PersonalMessage intent
+        intent_message.extend(bcs::to_bytes(&message.to_vec()).unwrap());
+        Blake2b256::digest(&intent_message).digest
+    }
+
+    #[tokio::test]
+    async fn test_certificate_and_transaction_signatures_are_domain_separated() {
+        use sui_sdk_types::SimpleSignature;
+
+        // The backend key signs both; the same bytes must never yield a
+        // signature that is valid in the other role
+        let kp = keypair();
+        let signer = LocalSigner::from_keypair(&kp);
+        let data = b"identical underlying bytes";
+        assert_ne!(personal_message_digest(data), transaction_digest(data));
+
+        let tx_sig = signer.sign_transaction(data).await.unwrap();
+        let tx_sig = Ed25519Signature::from_bytes(&tx_sig[1..65]).unwrap();
+
+        let cert_sig = match signer.sign_personal_message(data).await.unwrap() {
+            UserSignature::Simple(SimpleSignature::Ed25519 { signature, .. }) => {
+                Ed25519Signature::from_bytes(signature.as_bytes()).unwrap()
+            }
+            other => panic!("expected an Ed25519 signature, got {:?}", other),
+        };
+        assert_ne!(tx_sig, cert_sig);
+
+        let pk = kp.public();
+        assert!(pk.verify(&transaction_digest(data), &tx_sig).is_ok());
+        assert!(pk.verify(&personal_message_digest(data), &tx_sig).is_err());
+        assert!(pk.verify(&personal_message_digest(data), &cert_sig).is_ok());
+        assert!(pk.verify(&transaction_digest(data), &cert_sig).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_app_state_signer_is_swappable() {
+        let kp = keypair();
+        let local_address = LocalSigner::from_keypair(&kp).address();
+        let state = AppState::new(kp, String::new());
+        assert_eq!(state.signer.address(), local_address);
+
+        let details: DecryptedSwapDetails = serde_json::from_str(
+            r#"{"nullifier":"0x1","inputAmount":"1","outputStealth":"0x2","remainderStealth":"0x3","signature":""}"#,
+        )
+        .unwrap();
+        state.decrypt_cache.insert(
+            b"enc".to_vec(),
+            DecryptOutcome { details, key_servers: vec![] },
+        );
+
+        let signed = Arc::new(Mutex::new(Vec::new()));
+        let fake_address = Address::new([7u8; 32]);
+        let state = state.with_signer(Box::new(FakeSigner {
+            address: fake_address,
+            signed: signed.clone(),
+        }));
+
+        assert_eq!(state.signer.address(), fake_address);
+        // Decryptions made under the old key don't carry over
+        assert!(state.decrypt_cache.is_empty());
+        assert_eq!(state.signer.sign_transaction(b"tx").await.unwrap(), vec![0xaa; 97]);
+        assert_eq!(*signed.lock().unwrap(), vec![b"tx".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn test_kms_signer_uses_signing_endpoint() {
+        use axum::{routing::post, Json, Router};
+
+        // The endpoint signs with its own key
+        let endpoint_signer = Arc::new(LocalSigner::from_keypair(&keypair()));
+        let expected = endpoint_signer.sign_transaction(b"tx-data").await.unwrap();
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        let app = Router::new().route(
+            "/sign",
+            post(move |Json(req): Json<SignRequest>| {
+                let seen = seen.clone();
+                let endpoint_signer = endpoint_signer.clone();
+                async move {
+                    seen.lock().unwrap().push((req.address, req.tx_data_b64.clone()));
+                    let tx_bytes = base64::engine::general_purpose::STANDARD.decode(req.tx_data_b64).unwrap();
+                    let signature = endpoint_signer.sign_transaction(&tx_bytes).await.unwrap();
+                    Json(SignResponse {
+                        signature: base64::engine::general_purpose::STANDARD.encode(signature),
+                    })
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let signer = KmsSigner::new(&url, endpoint_signer.address());
+        assert_eq!(signer.sign_transaction(b"tx-data").await.unwrap(), expected);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![(
+                endpoint_signer.address().to_string(),
+                base64::engine::general_purpose::STANDARD.encode(b"tx-data")
+            )]
+        );
+
+        // Only transactions are delegated so far
+        assert!(signer.sign_personal_message(b"seal session").await.is_err());
+
+        // A signature by any other key is refused
+        let other = KmsSigner::new(&url, Address::new([9u8; 32]));
+        let err = other.sign_transaction(b"tx-data").await.unwrap_err();
+        assert!(err.to_string().contains("instead of"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_signature_address_matches_signer() {
+        let signer = LocalSigner::from_keypair(&keypair());
+        let signature = signer.sign_transaction(b"tx").await.unwrap();
+        assert_eq!(signature_address(&signature).unwrap(), signer.address());
+
+        assert!(signature_address(&[]).is_err());
+        assert!(signature_address(&[0x05; 97]).is_err());
+        assert!(signature_address(&signature[..96]).is_err());
+    }
+
+    #[test]
+    fn test_kms_mode_refused_at_startup() {
+        assert_eq!(SignerConfig::from_vars(None, None).unwrap(), SignerConfig::Local);
+
+        let address = Address::new([9u8; 32]).to_string();
+        let err = SignerConfig::from_vars(Some("http://kms:4000".to_string()), Some(address)).unwrap_err();
+        assert!(err.to_string().contains("cannot sign SEAL session certificates"), "{}", err);
+
+        let err = SignerConfig::from_vars(Some("http://kms:4000".to_string()), None).unwrap_err();
+        assert!(err.to_string().contains(KMS_SIGNER_ADDRESS_ENV), "{}", err);
+    }
+}
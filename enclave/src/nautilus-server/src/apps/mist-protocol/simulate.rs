@@ -0,0 +1,126 @@
+//! Simulate-only mode for staging deploys
+//!
+//! With `MIST_SIMULATE_ONLY=true` the executor runs the full decrypt and
+//! quote pipeline and builds the swap transaction as usual, but only
+//! dry-runs it. Nothing is signed or submitted, so intents stay pending and
+//! are simulated again on the next poll.
+
+use anyhow::Result;
+use std::future::Future;
+
+/// Environment variable enabling simulate-only mode
+pub const SIMULATE_ONLY_ENV: &str = "MIST_SIMULATE_ONLY";
+
+/// Read `MIST_SIMULATE_ONLY`; unset means swaps are submitted
+pub fn simulate_only_from_env() -> Result<bool> {
+    match std::env::var(SIMULATE_ONLY_ENV) {
+        Ok(value) => parse_flag(&value)
+            .ok_or_else(|| anyhow::anyhow!("{} must be true or false, got {:?}", SIMULATE_ONLY_ENV, value)),
+        Err(_) => Ok(false),
+    }
+}
+
+pub(crate) fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" | "" => Some(false),
+        _ => None,
+    }
+}
+
+/// Digest of the transaction and, when known, the output it delivers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxOutcome {
+    pub digest: String,
+    /// Output credited to the stealth address according to the dry run
+    pub output_amount: Option<u64>,
+}
+
+/// How a built swap transaction was handled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    Executed(TxOutcome),
+    Simulated(TxOutcome),
+}
+
+/// Dry-run the transaction in simulate-only mode, otherwise sign and execute it.
+/// `execute` is never called when `simulate_only` is set.
+pub async fn submit_or_simulate<S, SFut, E, EFut>(
+    simulate_only: bool,
+    simulate: S,
+    execute: E,
+) -> Result<Submission>
+where
+    S: FnOnce() -> SFut,
+    SFut: Future<Output = Result<TxOutcome>>,
+    E: FnOnce() -> EFut,
+    EFut: Future<Output = Result<TxOutcome>>,
+{
+    if simulate_only {
+        simulate().await.map(Submission::Simulated)
+    } else {
+        execute().await.map(Submission::Executed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn outcome(digest: &str) -> TxOutcome {
+        TxOutcome { digest: digest.to_string(), output_amount: Some(42) }
+    }
+
+    #[test]
+    fn test_parse_flag() {
+        assert_eq!(parse_flag("true"), Some(true));
+        assert_eq!(parse_flag("1"), Some(true));
+        assert_eq!(parse_flag("FALSE"), Some(false));
+        assert_eq!(parse_flag(""), Some(false));
+        assert_eq!(parse_flag("maybe"), None);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_only_never_submits() {
+        let submitted = Cell::new(false);
+        let simulated = Cell::new(false);
+
+        let submission = submit_or_simulate(
+            true,
+            || async {
+                simulated.set(true);
+                Ok(outcome("dry"))
+            },
+            || async {
+                submitted.set(true);
+                Ok(outcome("real"))
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(submission, Submission::Simulated(outcome("dry")));
+        assert!(simulated.get());
+        assert!(!submitted.get());
+    }
+
+    #[tokio::test]
+    async fn test_normal_mode_executes() {
+        let simulated = Cell::new(false);
+
+        let submission = submit_or_simulate(
+            false,
+            || async {
+                simulated.set(true);
+                Ok(outcome("dry"))
+            },
+            || async { Ok(outcome("real")) },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(submission, Submission::Executed(outcome("real")));
+        assert!(!simulated.get());
+    }
+}
@@ -0,0 +1,173 @@
+//! Timeout for on-chain transaction submission
+//!
+//! `execute_transaction_block` waits for the fullnode's quorum driver; under
+//! load that can take arbitrarily long and stall the processor on one intent.
+//! Submissions are bounded by `MIST_SUBMIT_TIMEOUT_SECS` (default 30).
+//!
+//! Independently of how many intents are in flight, at most
+//! `MIST_MAX_CONCURRENT_SUBMITS` (default 4) transactions are submitted at
+//! once, to stay within the gas coin's owned-object throughput and the
+//! fullnode's rate limits. Decryption and quoting are not limited by it.
+
+use anyhow::Result;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Environment variable for the submission timeout in seconds
+pub const SUBMIT_TIMEOUT_ENV: &str = "MIST_SUBMIT_TIMEOUT_SECS";
+/// Default submission timeout
+pub const DEFAULT_SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Read the timeout from `MIST_SUBMIT_TIMEOUT_SECS`, defaulting to 30 seconds
+pub fn timeout_from_env() -> Result<Duration> {
+    match std::env::var(SUBMIT_TIMEOUT_ENV) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", SUBMIT_TIMEOUT_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_SUBMIT_TIMEOUT),
+    }
+}
+
+/// Environment variable for the maximum number of simultaneous submissions
+pub const MAX_CONCURRENT_SUBMITS_ENV: &str = "MIST_MAX_CONCURRENT_SUBMITS";
+/// Default maximum number of simultaneous submissions
+pub const DEFAULT_MAX_CONCURRENT_SUBMITS: usize = 4;
+
+/// Read the limit from `MIST_MAX_CONCURRENT_SUBMITS`, defaulting to 4
+pub fn max_concurrent_from_env() -> Result<usize> {
+    match std::env::var(MAX_CONCURRENT_SUBMITS_ENV) {
+        Ok(value) => match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", MAX_CONCURRENT_SUBMITS_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_MAX_CONCURRENT_SUBMITS),
+    }
+}
+
+/// Caps the number of submissions in flight
+#[derive(Debug)]
+pub struct SubmitLimiter {
+    permits: Semaphore,
+    limit: usize,
+}
+
+impl SubmitLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            permits: Semaphore::new(limit),
+            limit,
+        }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(max_concurrent_from_env()?))
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Run `submission` once a slot is free. The submission future is not
+    /// polled while waiting, so a timeout inside it only covers the
+    /// submission itself.
+    pub async fn run<T, Fut>(&self, submission: Fut) -> Result<T>
+    where
+        Fut: Future<Output = Result<T>>,
+    {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| anyhow::anyhow!("Submission limiter closed"))?;
+        submission.await
+    }
+}
+
+/// Await a submission, giving up after `timeout`.
+///
+/// A timed-out transaction may still land. That is safe: it consumes the
+/// intent, so a retry on the next poll either finds nothing or fails on-chain.
+pub async fn with_timeout<T, Fut>(timeout: Duration, submission: Fut) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    tokio::time::timeout(timeout, submission).await.map_err(|_| {
+        anyhow::anyhow!(
+            "Transaction submission timed out after {:?} (it may still be executed)",
+            timeout
+        )
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::{sleep, Instant};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_submission_times_out() {
+        let started = Instant::now();
+        let result = with_timeout(Duration::from_secs(30), async {
+            sleep(Duration::from_secs(3600)).await;
+            Ok("digest")
+        })
+        .await;
+
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fast_submission_passes_through() {
+        let result = with_timeout(Duration::from_secs(30), async {
+            sleep(Duration::from_secs(2)).await;
+            Ok("digest")
+        })
+        .await;
+        assert_eq!(result.unwrap(), "digest");
+
+        let failed: Result<()> =
+            with_timeout(Duration::from_secs(30), async { anyhow::bail!("rejected") }).await;
+        assert_eq!(failed.unwrap_err().to_string(), "rejected");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_submissions_are_bounded() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let limiter = Arc::new(SubmitLimiter::new(2));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let started = Instant::now();
+
+        let mut intents = tokio::task::JoinSet::new();
+        for i in 0..6 {
+            let (limiter, in_flight, peak) = (limiter.clone(), in_flight.clone(), peak.clone());
+            intents.spawn(async move {
+                limiter
+                    .run(with_timeout(Duration::from_secs(15), async {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        sleep(Duration::from_secs(10)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        Ok(i)
+                    }))
+                    .await
+            });
+        }
+
+        let mut done = 0;
+        while let Some(result) = intents.join_next().await {
+            // The last wave waits 20s for a slot, longer than the 15s timeout
+            result.unwrap().unwrap();
+            done += 1;
+        }
+        assert_eq!(done, 6);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        // Three waves of two
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,153 @@
+//! Periodic throughput summary for the intent processor
+//!
+//! Instead of reconstructing throughput from per-intent log lines, the
+//! processor emits one summary line every `MIST_SUMMARY_INTERVAL_SECS`
+//! (default 60) with counts since the previous summary.
+
+use anyhow::Result;
+use std::fmt;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Environment variable for the summary interval in seconds
+pub const SUMMARY_INTERVAL_ENV: &str = "MIST_SUMMARY_INTERVAL_SECS";
+/// Default summary interval
+pub const DEFAULT_SUMMARY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counters accumulated between two summaries
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SummaryReport {
+    /// Intents executed successfully
+    pub processed: u64,
+    /// Intents that failed to process
+    pub failed: u64,
+    /// Intents skipped while paused
+    pub skipped: u64,
+    /// Pending intents seen on the latest poll
+    pub pending: usize,
+    /// Mean time spent per executed intent (success or failure)
+    pub avg_latency: Option<Duration>,
+}
+
+impl fmt::Display for SummaryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "processed={} failed={} skipped={} pending={} avg_latency=",
+            self.processed, self.failed, self.skipped, self.pending
+        )?;
+        match self.avg_latency {
+            Some(latency) => write!(f, "{}ms", latency.as_millis()),
+            None => write!(f, "n/a"),
+        }
+    }
+}
+
+/// Read the interval from `MIST_SUMMARY_INTERVAL_SECS`, defaulting to 60 seconds
+pub fn interval_from_env() -> Result<Duration> {
+    match std::env::var(SUMMARY_INTERVAL_ENV) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+            _ => anyhow::bail!("{} must be a positive integer, got {:?}", SUMMARY_INTERVAL_ENV, value),
+        },
+        Err(_) => Ok(DEFAULT_SUMMARY_INTERVAL),
+    }
+}
+
+#[derive(Debug)]
+pub struct ProcessorSummary {
+    interval: Duration,
+    last_emit: Instant,
+    current: SummaryReport,
+    total_latency: Duration,
+}
+
+impl ProcessorSummary {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_emit: Instant::now(),
+            current: SummaryReport::default(),
+            total_latency: Duration::ZERO,
+        }
+    }
+
+    pub fn record_success(&mut self, latency: Duration) {
+        self.current.processed += 1;
+        self.total_latency += latency;
+    }
+
+    pub fn record_failure(&mut self, latency: Duration) {
+        self.current.failed += 1;
+        self.total_latency += latency;
+    }
+
+    pub fn record_skipped(&mut self, count: usize) {
+        self.current.skipped += count as u64;
+    }
+
+    pub fn set_pending(&mut self, pending: usize) {
+        self.current.pending = pending;
+    }
+
+    /// Return the report and reset the counters once the interval has elapsed
+    pub fn maybe_emit(&mut self) -> Option<SummaryReport> {
+        let now = Instant::now();
+        if now.duration_since(self.last_emit) < self.interval {
+            return None;
+        }
+
+        let executed = self.current.processed + self.current.failed;
+        let mut report = std::mem::take(&mut self.current);
+        if executed > 0 {
+            report.avg_latency = Some(self.total_latency / executed as u32);
+        }
+
+        self.total_latency = Duration::ZERO;
+        self.last_emit = now;
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_summary_emitted_per_interval() {
+        let mut summary = ProcessorSummary::new(Duration::from_secs(60));
+
+        summary.record_success(Duration::from_millis(100));
+        summary.record_success(Duration::from_millis(300));
+        summary.record_failure(Duration::from_millis(200));
+        summary.record_skipped(2);
+        summary.set_pending(4);
+
+        // Interval not yet elapsed
+        tokio::time::advance(Duration::from_secs(59)).await;
+        assert!(summary.maybe_emit().is_none());
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        let report = summary.maybe_emit().expect("summary due");
+        assert_eq!(
+            report,
+            SummaryReport {
+                processed: 2,
+                failed: 1,
+                skipped: 2,
+                pending: 4,
+                avg_latency: Some(Duration::from_millis(200)),
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "processed=2 failed=1 skipped=2 pending=4 avg_latency=200ms"
+        );
+
+        // Counters reset for the next interval
+        tokio::time::advance(Duration::from_secs(60)).await;
+        let report = summary.maybe_emit().expect("summary due");
+        assert_eq!(report, SummaryReport::default());
+        assert!(report.to_string().ends_with("avg_latency=n/a"));
+    }
+}
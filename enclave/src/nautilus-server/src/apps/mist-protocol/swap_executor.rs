@@ -16,24 +16,31 @@
 //!     clock: &Clock,
 //! ): Coin<Y>
 
+use super::diagnostics::{IntentStage, QuoteSnapshot};
+use super::{balance, deadline, simulate, submit};
 use super::{DecryptedSwapDetails, SwapExecutionResult, SwapIntentObject, SEAL_CONFIG};
-use crate::AppState;
+use crate::{AppState, EnclaveError};
 use anyhow::Result;
 use sui_sdk::SuiClient;
-use tracing::info;
+use tracing::{info, warn};
 
 // FlowX DEX integration for testnet
 #[cfg(feature = "mist-protocol")]
 use crate::flowx::utils::math;
 
+/// Gas budget for swap transactions: 0.1 SUI (higher for DEX swaps)
+const GAS_BUDGET: u64 = 100_000_000;
+
 /// Execute swap v2 - builds and submits the swap transaction
 /// Chooses between privacy mixer (same token) or DEX swap (different tokens)
 #[cfg(feature = "mist-protocol")]
 pub async fn execute_swap_v2(
     intent: &SwapIntentObject,
     details: &DecryptedSwapDetails,
+    nullifier: &[u8; 32],
     sui_client: &SuiClient,
-    _state: &AppState,
+    state: &AppState,
+    prefetched_pools: Option<crate::flowx::PairPools>,
 ) -> Result<SwapExecutionResult> {
     use sui_sdk::rpc_types::SuiObjectDataOptions;
     use sui_sdk::types::{
@@ -48,36 +55,23 @@ pub async fn execute_swap_v2(
     info!("  Token in:  {}", intent.token_in);
     info!("  Token out: {}", intent.token_out);
 
-    // Get backend address from env
-    let private_key_str = std::env::var("BACKEND_PRIVATE_KEY")?;
-
-    // Decode Bech32 to get keypair
-    use bech32::FromBase32;
-    let (hrp, data, _variant) = bech32::decode(&private_key_str)?;
-    assert!(hrp == "suiprivkey");
-    let decoded_bytes = Vec::<u8>::from_base32(&data)?;
-    let key_bytes: [u8; 32] = decoded_bytes[1..33].try_into()?;
-
-    use sui_crypto::ed25519::Ed25519PrivateKey;
-    let sui_private_key = Ed25519PrivateKey::new(key_bytes);
-    let backend_address_sui = sui_private_key.public_key().to_address();
-    let backend_address = SuiAddress::from_str(&format!("0x{}", hex::encode(backend_address_sui.as_bytes())))?;
+    // Address of the backend signer (local key or KMS)
+    let backend_address = SuiAddress::from_str(&format!("0x{}", hex::encode(state.signer.address().as_bytes())))?;
 
     info!("  Backend address: {}", backend_address);
 
-    // Parse amounts
-    let input_amount: u64 = details.input_amount.parse()?;
+    // The pool only holds SUI; don't build a swap for any other declared input
+    balance::check_token_in(&intent.token_in)?;
+
+    // Parse amounts; a zero amount is rejected before any RPC round-trip
+    let input_amount = balance::parse_input_amount(&details.input_amount)?;
 
     // Parse addresses
     let output_stealth = SuiAddress::from_str(&details.output_stealth)?;
     let remainder_stealth = SuiAddress::from_str(&details.remainder_stealth)?;
 
-    // Parse nullifier (hex string to bytes)
-    let nullifier_bytes = if details.nullifier.starts_with("0x") {
-        hex::decode(&details.nullifier[2..])?
-    } else {
-        hex::decode(&details.nullifier)?
-    };
+    // Nullifier was validated and decoded by the caller
+    let nullifier_bytes = nullifier.to_vec();
 
     // Get Mist Protocol object IDs
     let registry_id = ObjectID::from_hex_literal(&SEAL_CONFIG.registry_id.to_string())?;
@@ -86,49 +80,52 @@ pub async fn execute_swap_v2(
     let package_id = ObjectID::from_hex_literal(&SEAL_CONFIG.package_id.to_string())?;
 
     // Query shared object versions
-    let registry_obj = sui_client
-        .read_api()
-        .get_object_with_options(registry_id, SuiObjectDataOptions::new().with_owner())
-        .await?
-        .data
-        .ok_or_else(|| anyhow::anyhow!("Registry not found"))?;
-
-    let pool_obj = sui_client
-        .read_api()
-        .get_object_with_options(pool_id, SuiObjectDataOptions::new().with_owner())
-        .await?
-        .data
-        .ok_or_else(|| anyhow::anyhow!("Pool not found"))?;
-
-    let intent_obj = sui_client
-        .read_api()
-        .get_object_with_options(intent_id, SuiObjectDataOptions::new().with_owner())
-        .await?
-        .data
-        .ok_or_else(|| anyhow::anyhow!("Intent not found"))?;
-
-    let registry_version = match registry_obj.owner {
-        Some(sui_sdk::types::object::Owner::Shared { initial_shared_version }) => initial_shared_version,
-        _ => anyhow::bail!("Registry is not shared"),
-    };
+    let registry_obj = object_data(
+        sui_client
+            .read_api()
+            .get_object_with_options(registry_id, SuiObjectDataOptions::new().with_owner())
+            .await?,
+        "Registry",
+    )?;
 
-    let pool_version = match pool_obj.owner {
-        Some(sui_sdk::types::object::Owner::Shared { initial_shared_version }) => initial_shared_version,
-        _ => anyhow::bail!("Pool is not shared"),
+    let pool_obj = object_data(
+        sui_client
+            .read_api()
+            .get_object_with_options(pool_id, SuiObjectDataOptions::new().with_owner().with_content())
+            .await?,
+        "Pool",
+    )?;
+
+    // The input is withdrawn from the pool: don't build a swap it can't fund
+    let pool_fields = match &pool_obj.content {
+        Some(sui_sdk::rpc_types::SuiParsedData::MoveObject(obj)) => serde_json::to_value(&obj.fields).ok(),
+        _ => None,
     };
+    let pool_balance = pool_fields
+        .as_ref()
+        .and_then(|fields| balance::balance_field(fields, balance::POOL_BALANCE_FIELD))
+        .ok_or_else(|| EnclaveError::GenericError("Pool balance not readable from RPC".to_string()))?;
+    balance::check_sufficient(pool_balance, input_amount)?;
+
+    let intent_obj = object_data(
+        sui_client
+            .read_api()
+            .get_object_with_options(intent_id, SuiObjectDataOptions::new().with_owner())
+            .await?,
+        "Intent",
+    )?;
 
-    let intent_version = match intent_obj.owner {
-        Some(sui_sdk::types::object::Owner::Shared { initial_shared_version }) => initial_shared_version,
-        _ => anyhow::bail!("Intent is not shared"),
-    };
+    let registry = object_arg(&registry_obj, "Registry", SharedObjectMutability::Mutable)?;
+    let pool = object_arg(&pool_obj, "Pool", SharedObjectMutability::Mutable)?;
+    let intent_object = object_arg(&intent_obj, "Intent", SharedObjectMutability::Mutable)?;
 
     // Normalize token types for comparison
     let token_in_normalized = intent.token_in.to_lowercase();
     let token_out_normalized = intent.token_out.to_lowercase();
-    let sui_type = "0x2::sui::sui".to_lowercase();
 
     // Determine if this is a privacy mixer (same token) or DEX swap (different tokens)
     let is_privacy_mixer = token_in_normalized == token_out_normalized;
+    let venue = if is_privacy_mixer { "mixer" } else { "flowx" };
 
     let (output_amount, remainder_amount, pt) = if is_privacy_mixer {
         // Privacy mixer: SUI → SUI using execute_swap
@@ -136,28 +133,17 @@ pub async fn execute_swap_v2(
 
         let mut ptb = ProgrammableTransactionBuilder::new();
 
-        let registry_arg = ptb.obj(ObjectArg::SharedObject {
-            id: registry_id,
-            initial_shared_version: registry_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
-
-        let pool_arg = ptb.obj(ObjectArg::SharedObject {
-            id: pool_id,
-            initial_shared_version: pool_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
-
-        let intent_arg = ptb.obj(ObjectArg::SharedObject {
-            id: intent_id,
-            initial_shared_version: intent_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
+        let registry_arg = ptb.obj(registry)?;
+        let pool_arg = ptb.obj(pool)?;
+        let intent_arg = ptb.obj(intent_object)?;
 
         // For privacy mixer, output = input (1:1)
         let output_amount = input_amount;
         let remainder_amount = 0u64;
 
+        // Defense in depth: never sign an implausible output (SUI in, SUI out)
+        super::OUTPUT_CAP.check(output_amount.saturating_add(remainder_amount), input_amount)?;
+
         let nullifier_arg = ptb.pure(nullifier_bytes.clone())?;
         let output_amount_arg = ptb.pure(output_amount)?;
         let output_stealth_arg = ptb.pure(output_stealth)?;
@@ -186,47 +172,46 @@ pub async fn execute_swap_v2(
         // DEX swap: SUI → MIST_TOKEN using withdraw_for_swap + FlowX
         info!("  Mode: DEX Swap via FlowX");
 
-        // Verify it's SUI → something (we can only withdraw SUI from pool)
-        if token_in_normalized != sui_type {
-            anyhow::bail!("Only SUI input is supported for DEX swaps (pool only holds SUI)");
-        }
-
-        // FlowX configuration
-        let flowx_package_id = ObjectID::from_hex_literal(
-            "0x6cc1ce379acd35203f856f1dd0e063023caf091c47ce19b4695299de8b5fcb17"
-        )?;
-        let flowx_pool_registry_id = ObjectID::from_hex_literal(
-            "0xe59d16a0427a1ad98302eda383025d342d555ff9a98113f421c2184bdee1963e"
-        )?;
-        let flowx_versioned_id = ObjectID::from_hex_literal(
-            "0xf7eacab72d4a09da34ceb38922c21d7c48cb6bbedb5f1c57899f5c782abe1b5c"
-        )?;
+        // FlowX configuration (dex.flowx in seal_config.yaml)
+        let dex = SEAL_CONFIG
+            .dex
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FlowX is not configured (dex.flowx in seal_config.yaml)"))?;
+        let flowx = dex
+            .flowx
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FlowX is not configured (dex.flowx in seal_config.yaml)"))?;
+        // Never call a DEX package that isn't explicitly allowed
+        dex.check_package(&flowx.package_id)?;
+        let flowx_package_id = ObjectID::from_hex_literal(&flowx.package_id)?;
+        let flowx_pool_registry_id = ObjectID::from_hex_literal(&flowx.pool_registry_id)?;
+        let flowx_versioned_id = ObjectID::from_hex_literal(&flowx.versioned_id)?;
         let clock_id = ObjectID::from_hex_literal("0x6")?;
 
         // Query FlowX shared objects
-        let flowx_registry_obj = sui_client
-            .read_api()
-            .get_object_with_options(flowx_pool_registry_id, SuiObjectDataOptions::new().with_owner())
-            .await?
-            .data
-            .ok_or_else(|| anyhow::anyhow!("FlowX PoolRegistry not found"))?;
-
-        let flowx_versioned_obj = sui_client
-            .read_api()
-            .get_object_with_options(flowx_versioned_id, SuiObjectDataOptions::new().with_owner())
-            .await?
-            .data
-            .ok_or_else(|| anyhow::anyhow!("FlowX Versioned not found"))?;
-
-        let flowx_registry_version = match flowx_registry_obj.owner {
-            Some(sui_sdk::types::object::Owner::Shared { initial_shared_version }) => initial_shared_version,
-            _ => anyhow::bail!("FlowX PoolRegistry is not shared"),
-        };
+        let flowx_registry = object_arg(
+            &object_data(
+                sui_client
+                    .read_api()
+                    .get_object_with_options(flowx_pool_registry_id, SuiObjectDataOptions::new().with_owner())
+                    .await?,
+                "FlowX PoolRegistry",
+            )?,
+            "FlowX PoolRegistry",
+            SharedObjectMutability::Mutable,
+        )?;
 
-        let flowx_versioned_version = match flowx_versioned_obj.owner {
-            Some(sui_sdk::types::object::Owner::Shared { initial_shared_version }) => initial_shared_version,
-            _ => anyhow::bail!("FlowX Versioned is not shared"),
-        };
+        let flowx_versioned = object_arg(
+            &object_data(
+                sui_client
+                    .read_api()
+                    .get_object_with_options(flowx_versioned_id, SuiObjectDataOptions::new().with_owner())
+                    .await?,
+                "FlowX Versioned",
+            )?,
+            "FlowX Versioned",
+            SharedObjectMutability::Mutable,
+        )?;
 
         let mut ptb = ProgrammableTransactionBuilder::new();
 
@@ -234,23 +219,9 @@ pub async fn execute_swap_v2(
         let mut cmd_idx: u16 = 0;
 
         // Step 1: Call withdraw_for_swap to get SUI from Mist Protocol pool
-        let registry_arg = ptb.obj(ObjectArg::SharedObject {
-            id: registry_id,
-            initial_shared_version: registry_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
-
-        let pool_arg = ptb.obj(ObjectArg::SharedObject {
-            id: pool_id,
-            initial_shared_version: pool_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
-
-        let intent_arg = ptb.obj(ObjectArg::SharedObject {
-            id: intent_id,
-            initial_shared_version: intent_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
+        let registry_arg = ptb.obj(registry)?;
+        let pool_arg = ptb.obj(pool)?;
+        let intent_arg = ptb.obj(intent_object)?;
 
         let nullifier_arg = ptb.pure(nullifier_bytes.clone())?;
         let withdraw_amount_arg = ptb.pure(input_amount)?;
@@ -273,23 +244,47 @@ pub async fn execute_swap_v2(
         cmd_idx += 1;
 
         // Step 2: Call FlowX swap_exact_input
-        let flowx_registry_arg = ptb.obj(ObjectArg::SharedObject {
-            id: flowx_pool_registry_id,
-            initial_shared_version: flowx_registry_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
-
-        // Fee rate for the pool (0.3% = 3000)
-        let fee_rate: u64 = 3000;
-        let fee_rate_arg = ptb.pure(fee_rate)?;
-
-        // Minimum output (with 5% slippage for low liquidity testnet)
-        // For demo, accept any output since liquidity is low
-        let min_amount_out: u64 = 1; // Accept any output
+        let flowx_registry_arg = ptb.obj(flowx_registry)?;
+
+        // Fee tier and direction of the selected pool
+        let selected = select_flowx_pool(
+            sui_client,
+            flowx,
+            &intent.token_in,
+            &intent.token_out,
+            input_amount,
+            prefetched_pools,
+        )
+        .await?;
+        let fee_rate_arg = ptb.pure(selected.fee_rate)?;
+
+        // Minimum output from the venue's default slippage; accepting any
+        // output needs dex.flowx.allow_unprotected_output (low liquidity testnet)
+        let estimated_out = selected.estimate.amount_out_with(*super::OUTPUT_ROUNDING);
+        let min_amount_out = flowx.min_amount_out(estimated_out)?;
+        state.intent_diagnostics.quoted(
+            &intent.id,
+            QuoteSnapshot {
+                venue: venue.to_string(),
+                pool_id: selected.pool_id.clone(),
+                fee_rate: selected.fee_rate,
+                estimated_out,
+                min_amount_out,
+            },
+        );
+        check_quote(
+            dex,
+            &super::OUTPUT_CAP,
+            &intent.token_in,
+            &intent.token_out,
+            input_amount,
+            estimated_out,
+            min_amount_out,
+        )?;
         let min_amount_out_arg = ptb.pure(min_amount_out)?;
 
-        // Price limit for SUI → TOKEN (x_for_y = true, so min price)
-        let sqrt_price_limit: u128 = math::MIN_SQRT_PRICE + 1;
+        // Price limit follows the pool's X/Y ordering (SUI is not always X)
+        let sqrt_price_limit: u128 = selected.sqrt_price_limit();
         let sqrt_price_limit_arg = ptb.pure(sqrt_price_limit)?;
 
         // Deadline (30 minutes from now in milliseconds)
@@ -299,11 +294,7 @@ pub async fn execute_swap_v2(
             .as_millis() as u64 + (30 * 60 * 1000);
         let deadline_arg = ptb.pure(deadline_ms)?;
 
-        let versioned_arg = ptb.obj(ObjectArg::SharedObject {
-            id: flowx_versioned_id,
-            initial_shared_version: flowx_versioned_version,
-            mutability: SharedObjectMutability::Mutable,
-        })?;
+        let versioned_arg = ptb.obj(flowx_versioned)?;
 
         let clock_arg = ptb.obj(ObjectArg::SharedObject {
             id: clock_id,
@@ -311,7 +302,7 @@ pub async fn execute_swap_v2(
             mutability: SharedObjectMutability::Immutable,
         })?;
 
-        // Type arguments: X = SUI, Y = MIST_TOKEN
+        // Type arguments: <input, output>; swap_router resolves the pool ordering
         let sui_type_tag = TypeTag::from_str("0x2::sui::SUI")?;
         let mist_token_type = &intent.token_out;
         let mist_type_tag = TypeTag::from_str(mist_token_type)?;
@@ -342,14 +333,15 @@ pub async fn execute_swap_v2(
         ptb.command(Command::TransferObjects(vec![output_coin], output_stealth_arg));
         let _ = cmd_idx; // Silence unused variable warning
 
-        // For DEX swaps, we estimate output (actual amount determined by DEX)
-        // With low liquidity, we accept whatever the DEX gives us
-        let estimated_output = input_amount; // Estimate 1:1 for logging
+        // The quote stands in for the output until the DEX settles it
         let remainder_amount = 0u64; // No remainder for DEX swaps
 
-        info!("  Estimated output: {} MIST_TOKEN (actual determined by DEX)", estimated_output);
+        // The quote follows from the decrypted input amount
+        if super::LOG_PRIVACY.is_full() {
+            info!("  Estimated output: {} MIST_TOKEN (actual determined by DEX)", estimated_out);
+        }
 
-        (estimated_output, remainder_amount, ptb.finish())
+        (estimated_out, remainder_amount, ptb.finish())
     };
 
     // Get backend's SUI coins for gas
@@ -362,83 +354,126 @@ pub async fn execute_swap_v2(
         anyhow::bail!("Backend has no SUI coins for gas");
     }
 
-    let gas_coin = &sui_coins.data[0];
-    info!("  Gas coin: {} ({})", gas_coin.coin_object_id, gas_coin.balance);
+    // Gas coin(s) per MIST_GAS_COIN_STRATEGY
+    let coin_balances: Vec<(ObjectID, u64)> = sui_coins
+        .data
+        .iter()
+        .map(|coin| (coin.coin_object_id, coin.balance))
+        .collect();
+    let gas_coins = super::GAS_COIN_STRATEGY.select(&coin_balances, GAS_BUDGET)?;
+    info!("  Gas coins ({:?}): {:?}", *super::GAS_COIN_STRATEGY, gas_coins);
+
+    // The coin listing can lag behind the latest version; owned inputs must
+    // reference the exact current (version, digest) or validators reject the tx
+    let mut gas_refs = Vec::with_capacity(gas_coins.len());
+    for coin_id in gas_coins {
+        gas_refs.push(crate::flowx::fetch_owned_object_ref(sui_client, coin_id).await?);
+    }
 
     // Get gas price and build transaction
     let gas_price = sui_client.governance_api().get_reference_gas_price().await?;
 
-    let tx_data = TransactionData::new_programmable(
-        backend_address,
-        vec![(gas_coin.coin_object_id, gas_coin.version, gas_coin.digest)],
-        pt,
-        100_000_000, // 0.1 SUI gas budget (higher for DEX swaps)
-        gas_price,
-    );
+    let tx_data = TransactionData::new_programmable(backend_address, gas_refs, pt, GAS_BUDGET, gas_price);
 
-    // Sign transaction
-    info!("  Signing transaction...");
-
-    use sui_sdk::rpc_types::SuiTransactionBlockResponseOptions;
-    use sui_types::crypto::{Signature, ToFromBytes as SuiToFromBytes};
     use fastcrypto::hash::{Blake2b256, HashFunction};
-    use fastcrypto::traits::{Signer, ToFromBytes, KeyPair};
-
-    let tx_bytes = bcs::to_bytes(&tx_data)?;
-    let intent_message = {
-        let mut data = vec![0, 0, 0]; // TransactionData intent
-        data.extend_from_slice(&tx_bytes);
-        data
-    };
-
-    let tx_digest_bytes = Blake2b256::digest(&intent_message);
 
-    let ed25519_kp = fastcrypto::ed25519::Ed25519KeyPair::from(
-        fastcrypto::ed25519::Ed25519PrivateKey::from_bytes(&key_bytes)
-            .map_err(|e| anyhow::anyhow!("Invalid key bytes: {:?}", e))?
-    );
-
-    let signature = ed25519_kp.sign(tx_digest_bytes.as_ref());
-
-    let pub_key = ed25519_kp.public();
-    let pub_key_bytes: &[u8] = pub_key.as_ref();
-
-    let mut sig_bytes = vec![0x00]; // Ed25519 flag
-    sig_bytes.extend_from_slice(signature.as_ref());
-    sig_bytes.extend_from_slice(pub_key_bytes);
-
-    let sui_signature = <Signature as SuiToFromBytes>::from_bytes(&sig_bytes)
-        .map_err(|e| anyhow::anyhow!("Failed to create Signature: {:?}", e))?;
-
-    info!("  Transaction signed");
+    let simulate = || async {
+        use sui_sdk::rpc_types::SuiTransactionBlockEffectsAPI;
+        use sui_sdk::types::object::Owner;
 
-    // Execute transaction
-    info!("  Executing on-chain via SDK...");
+        info!("  Simulate-only mode: dry-running transaction");
+        let dry_run = sui_client
+            .read_api()
+            .dry_run_transaction_block(tx_data.clone())
+            .await?;
+        if dry_run.effects.status().is_err() {
+            anyhow::bail!("Simulated transaction failed: {:?}", dry_run.effects.status());
+        }
 
-    let transaction = sui_types::transaction::Transaction::from_data(
-        tx_data,
-        vec![sui_signature],
-    );
+        // What the output stealth address would receive
+        let token_out = TypeTag::from_str(&intent.token_out)?;
+        let received: i128 = dry_run
+            .balance_changes
+            .iter()
+            .filter(|change| {
+                matches!(change.owner, Owner::AddressOwner(owner) if owner == output_stealth)
+                    && change.coin_type == token_out
+            })
+            .map(|change| change.amount)
+            .sum();
+
+        Ok(simulate::TxOutcome {
+            digest: dry_run.effects.transaction_digest().to_string(),
+            output_amount: u64::try_from(received).ok(),
+        })
+    };
 
-    let response = sui_client
-        .quorum_driver_api()
-        .execute_transaction_block(
-            transaction,
-            SuiTransactionBlockResponseOptions::full_content(),
-            None,
-        )
-        .await?;
+    let execute = || async {
+        use sui_sdk::rpc_types::SuiTransactionBlockResponseOptions;
+        use sui_types::crypto::{Signature, ToFromBytes as SuiToFromBytes};
+
+        // Decryption and quoting took time: don't sign a transaction that
+        // would only abort on the deadline
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as u64;
+        deadline::check_time_remaining(intent.deadline, now_ms, *super::DEADLINE_MARGIN)?;
+
+        // Sign transaction
+        info!("  Signing transaction...");
+
+        let sig_bytes = state.signer.sign_transaction(&bcs::to_bytes(&tx_data)?).await?;
+
+        let sui_signature = <Signature as SuiToFromBytes>::from_bytes(&sig_bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to create Signature: {:?}", e))?;
+
+        info!("  Transaction signed");
+
+        // Execute transaction
+        info!("  Executing on-chain via SDK...");
+
+        let transaction = sui_types::transaction::Transaction::from_data(
+            tx_data.clone(),
+            vec![sui_signature],
+        );
+
+        let response = super::SUBMIT_LIMITER
+            .run(submit::with_timeout(*super::SUBMIT_TIMEOUT, async {
+                sui_client
+                    .quorum_driver_api()
+                    .execute_transaction_block(
+                        transaction,
+                        SuiTransactionBlockResponseOptions::full_content(),
+                        None,
+                    )
+                    .await
+                    .map_err(anyhow::Error::from)
+            }))
+            .await?;
+
+        let digest = response.digest.to_string();
+        info!("  Transaction executed: {}", digest);
+
+        // Check if transaction was successful
+        if let Some(effects) = &response.effects {
+            use sui_sdk::rpc_types::SuiTransactionBlockEffectsAPI;
+            if effects.status().is_err() {
+                anyhow::bail!("Transaction failed: {:?}", effects.status());
+            }
+        }
 
-    let digest = response.digest.to_string();
-    info!("  Transaction executed: {}", digest);
+        Ok(simulate::TxOutcome { digest, output_amount: None })
+    };
 
-    // Check if transaction was successful
-    if let Some(effects) = &response.effects {
-        use sui_sdk::rpc_types::SuiTransactionBlockEffectsAPI;
-        if effects.status().is_err() {
-            anyhow::bail!("Transaction failed: {:?}", effects.status());
-        }
-    }
+    state.intent_diagnostics.reached(&intent.id, IntentStage::Submitted);
+    let (outcome, executed) =
+        match simulate::submit_or_simulate(*super::SIMULATE_ONLY, simulate, execute).await? {
+            simulate::Submission::Executed(outcome) => (outcome, true),
+            simulate::Submission::Simulated(outcome) => {
+                info!("  Simulated (not submitted): {}", outcome.digest);
+                (outcome, false)
+            }
+        };
 
     // Compute nullifier hash for result
     let nullifier_hash = hex::encode(Blake2b256::digest(&nullifier_bytes));
@@ -447,21 +482,302 @@ pub async fn execute_swap_v2(
         success: true,
         intent_id: intent.id.clone(),
         nullifier_hash,
-        output_amount,
+        output_amount: outcome.output_amount.unwrap_or(output_amount),
         remainder_amount,
         output_stealth: details.output_stealth.clone(),
         remainder_stealth: details.remainder_stealth.clone(),
-        tx_digest: Some(digest),
+        venue: venue.to_string(),
+        tx_digest: Some(outcome.digest),
         error: None,
+        error_code: None,
+        executed,
+        simulated: !executed,
+        // Filled in by the processor, which owns the decryption step
+        seal_servers: Vec::new(),
+        receipt: None,
+        created_at_ms: None,
+        executed_at_ms: None,
     })
 }
 
+/// Checks on a FlowX quote before the swap is signed: the output cap
+/// (defense in depth against an implausible quote, in token_out units) and
+/// the pair's slippage limit (`dex.slippage_limits`), whatever the minimum.
+/// Without configured pools there is no quote (`estimated_out` 0), so only
+/// the minimum output protects the swap.
+#[cfg(feature = "mist-protocol")]
+fn check_quote(
+    dex: &super::seal_types::DexConfig,
+    cap: &super::limits::OutputCap,
+    token_in: &str,
+    token_out: &str,
+    input_amount: u64,
+    estimated_out: u64,
+    min_amount_out: u64,
+) -> Result<(), EnclaveError> {
+    cap.check(estimated_out, input_amount)?;
+    dex.check_slippage(token_in, token_out, estimated_out, min_amount_out)
+}
+
+/// Data of an object the swap depends on.
+///
+/// A deleted or wrapped object (e.g. a misconfigured pool ID) is reported as
+/// `InvalidInput` since retrying won't help; anything else without data is
+/// treated as a transient RPC failure.
+#[cfg(feature = "mist-protocol")]
+fn object_data(
+    response: sui_sdk::rpc_types::SuiObjectResponse,
+    name: &str,
+) -> Result<sui_sdk::rpc_types::SuiObjectData, EnclaveError> {
+    use sui_sdk::types::error::SuiObjectResponseError;
+
+    match (response.data, response.error) {
+        (Some(data), _) => Ok(data),
+        (None, Some(SuiObjectResponseError::Deleted { .. })) => Err(EnclaveError::InvalidInput(
+            format!("{} deleted or wrapped", name.to_lowercase()),
+        )),
+        (None, Some(SuiObjectResponseError::NotExists { object_id })) => Err(
+            EnclaveError::InvalidInput(format!("{} {} does not exist", name.to_lowercase(), object_id)),
+        ),
+        (None, error) => Err(EnclaveError::GenericError(format!(
+            "{} not available from RPC ({:?}), will retry",
+            name, error
+        ))),
+    }
+}
+
+/// Transaction input for an object read with its owner.
+///
+/// An RPC response without owner data is partial, so it's retried rather than
+/// taken as proof the object isn't shared. A shared object is passed by its
+/// initial shared version; an address-owned or immutable one by reference.
+#[cfg(feature = "mist-protocol")]
+fn object_arg(
+    data: &sui_sdk::rpc_types::SuiObjectData,
+    name: &str,
+    mutability: sui_sdk::types::transaction::SharedObjectMutability,
+) -> Result<sui_sdk::types::transaction::ObjectArg, EnclaveError> {
+    use sui_sdk::types::object::Owner;
+    use sui_sdk::types::transaction::ObjectArg;
+
+    match &data.owner {
+        None => Err(EnclaveError::GenericError(format!(
+            "{} owner missing from RPC response, will retry",
+            name
+        ))),
+        Some(Owner::Shared { initial_shared_version }) => Ok(ObjectArg::SharedObject {
+            id: data.object_id,
+            initial_shared_version: *initial_shared_version,
+            mutability,
+        }),
+        Some(owner @ (Owner::AddressOwner(_) | Owner::Immutable)) => {
+            warn!("  {} {} is not shared ({}); using it as an owned object", name, data.object_id, owner);
+            Ok(ObjectArg::ImmOrOwnedObject(data.object_ref()))
+        }
+        Some(owner) => Err(EnclaveError::InvalidInput(format!(
+            "{} {} can't be a transaction input (owner: {})",
+            name.to_lowercase(),
+            data.object_id,
+            owner
+        ))),
+    }
+}
+
+/// Read the FlowX pools an intent will be quoted against, before its amount
+/// is known. `None` when the intent won't need a quote (same-token mixer
+/// swap, or no pools configured).
+#[cfg(feature = "mist-protocol")]
+pub fn prefetch_flowx_pools<'a>(
+    sui_client: &'a SuiClient,
+    intent: &'a SwapIntentObject,
+) -> Option<impl std::future::Future<Output = Result<crate::flowx::PairPools>> + 'a> {
+    let flowx = SEAL_CONFIG.dex.as_ref()?.flowx.as_ref()?;
+    if intent.token_in.eq_ignore_ascii_case(&intent.token_out) || flowx.pools.is_empty() {
+        return None;
+    }
+
+    let pool_ids: Vec<String> = flowx.pools.iter().map(|p| p.pool_id.clone()).collect();
+    Some(async move {
+        crate::flowx::fetch_pair_pools(sui_client, &pool_ids, &intent.token_in, &intent.token_out).await
+    })
+}
+
+/// Pick the FlowX pool for `coin_in → coin_out`.
+///
+/// Quotes every pool listed under `dex.flowx.pools` (pools for other pairs are
+/// dropped) and picks one using the startup-configured strategy. Pool state
+/// read while the intent was being decrypted is reused. When no pools are
+/// configured, falls back to the default fee tier with the direction implied
+/// by FlowX's type-name ordering. Either way the direction is checked against
+/// the pair's entry in `dex.flowx.pool_orderings`, if it has one.
+#[cfg(feature = "mist-protocol")]
+async fn select_flowx_pool(
+    sui_client: &SuiClient,
+    flowx: &super::seal_types::FlowXDexConfig,
+    coin_in: &str,
+    coin_out: &str,
+    amount_in: u64,
+    prefetched: Option<crate::flowx::PairPools>,
+) -> Result<crate::flowx::SelectedPool> {
+    use crate::flowx::{pool_selection, select_pool_for_pair, SelectedPool};
+
+    let ordering = flowx.pool_ordering(coin_in, coin_out);
+
+    let selected = if flowx.pools.is_empty() {
+        if ordering.is_none() && flowx.require_pool_ordering {
+            return Err(crate::EnclaveError::InvalidInput(format!(
+                "No pool ordering listed for {} / {} in dex.flowx.pool_orderings",
+                coin_in, coin_out
+            ))
+            .into());
+        }
+        SelectedPool {
+            pool_id: String::new(),
+            fee_rate: flowx.default_fee_rate,
+            x_for_y: pool_selection::ordered_direction(coin_in, coin_out)?,
+            estimate: math::SwapEstimate { amount_out: 0, price_impact: 0.0, exact_out: 0.0 },
+        }
+    } else if let Some(pools) = prefetched {
+        pools.select(amount_in, *super::POOL_SELECTION_STRATEGY)?
+    } else {
+        let pool_ids: Vec<String> = flowx.pools.iter().map(|p| p.pool_id.clone()).collect();
+        select_pool_for_pair(
+            sui_client,
+            &pool_ids,
+            coin_in,
+            coin_out,
+            amount_in,
+            *super::POOL_SELECTION_STRATEGY,
+        )
+        .await?
+    };
+
+    if let Some(ordering) = ordering {
+        pool_selection::verify_direction(&ordering.coin_x, &ordering.coin_y, coin_in, coin_out, selected.x_for_y)
+            .map_err(|e| crate::EnclaveError::InvalidInput(e.to_string()))?;
+    }
+    Ok(selected)
+}
+
 #[cfg(not(feature = "mist-protocol"))]
 pub async fn execute_swap_v2(
     _intent: &SwapIntentObject,
     _details: &DecryptedSwapDetails,
+    _nullifier: &[u8; 32],
     _sui_client: &SuiClient,
     _state: &AppState,
+    _prefetched_pools: Option<crate::flowx::PairPools>,
 ) -> Result<SwapExecutionResult> {
     Err(anyhow::anyhow!("mist-protocol feature not enabled"))
 }
+
+#[cfg(all(test, feature = "mist-protocol"))]
+mod tests {
+    use super::*;
+    use sui_sdk::rpc_types::SuiObjectResponse;
+    use sui_sdk::types::base_types::{ObjectID, SequenceNumber};
+    use sui_sdk::types::digests::ObjectDigest;
+    use sui_sdk::types::error::SuiObjectResponseError;
+    use sui_sdk::types::transaction::ObjectArg;
+
+    fn object_with_owner(owner: Option<sui_sdk::types::object::Owner>) -> sui_sdk::rpc_types::SuiObjectData {
+        sui_sdk::rpc_types::SuiObjectData {
+            object_id: ObjectID::random(),
+            version: SequenceNumber::from_u64(9),
+            digest: ObjectDigest::random(),
+            type_: None,
+            owner,
+            previous_transaction: None,
+            storage_rebate: None,
+            display: None,
+            content: None,
+            bcs: None,
+        }
+    }
+
+    #[test]
+    fn test_missing_owner_is_retried() {
+        use sui_sdk::types::transaction::SharedObjectMutability;
+
+        let partial = object_with_owner(None);
+        match object_arg(&partial, "Pool", SharedObjectMutability::Mutable) {
+            Err(EnclaveError::GenericError(msg)) => assert_eq!(msg, "Pool owner missing from RPC response, will retry"),
+            other => panic!("expected a retryable GenericError, got {:?}", other),
+        }
+
+        let shared = object_with_owner(Some(sui_sdk::types::object::Owner::Shared {
+            initial_shared_version: SequenceNumber::from_u64(4),
+        }));
+        match object_arg(&shared, "Pool", SharedObjectMutability::Mutable) {
+            Ok(ObjectArg::SharedObject { id, initial_shared_version, .. }) => {
+                assert_eq!((id, initial_shared_version), (shared.object_id, SequenceNumber::from_u64(4)));
+            }
+            other => panic!("expected a shared object input, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_non_shared_owner_is_used_as_owned() {
+        use sui_sdk::types::base_types::SuiAddress;
+        use sui_sdk::types::object::Owner;
+        use sui_sdk::types::transaction::SharedObjectMutability;
+
+        for owner in [Owner::AddressOwner(SuiAddress::random_for_testing_only()), Owner::Immutable] {
+            let owned = object_with_owner(Some(owner));
+            match object_arg(&owned, "Pool", SharedObjectMutability::Mutable) {
+                Ok(ObjectArg::ImmOrOwnedObject(object_ref)) => assert_eq!(object_ref, owned.object_ref()),
+                other => panic!("expected an owned object input, got {:?}", other),
+            }
+        }
+
+        // A child object can't be passed in at all: not worth retrying
+        let child = object_with_owner(Some(Owner::ObjectOwner(SuiAddress::random_for_testing_only())));
+        assert!(matches!(
+            object_arg(&child, "Pool", SharedObjectMutability::Mutable),
+            Err(EnclaveError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_deleted_or_wrapped_object_is_invalid_input() {
+        let object_id = ObjectID::random();
+
+        let wrapped = SuiObjectResponse::new_with_error(SuiObjectResponseError::Deleted {
+            object_id,
+            version: SequenceNumber::from_u64(7),
+            digest: ObjectDigest::OBJECT_DIGEST_WRAPPED,
+        });
+        match object_data(wrapped, "Pool") {
+            Err(EnclaveError::InvalidInput(msg)) => assert_eq!(msg, "pool deleted or wrapped"),
+            other => panic!("expected InvalidInput, got {:?}", other.map(|_| ())),
+        }
+
+        let missing = SuiObjectResponse::new_with_error(SuiObjectResponseError::NotExists { object_id });
+        assert!(matches!(object_data(missing, "Pool"), Err(EnclaveError::InvalidInput(_))));
+
+        // No data and no error: transient, not a configuration problem
+        let empty = SuiObjectResponse { data: None, error: None };
+        assert!(matches!(object_data(empty, "Pool"), Err(EnclaveError::GenericError(_))));
+    }
+
+    #[test]
+    fn test_quote_over_output_cap_is_refused() {
+        use super::super::limits::OutputCap;
+        use super::super::seal_types::DexConfig;
+
+        let dex = DexConfig::default();
+        let mist_token = "0xabc::mist_token::MIST_TOKEN";
+        let cap = OutputCap { max_amount: Some(5_000_000), max_multiple: Some(20) };
+
+        // 1 SUI quoted at 4.9 MIST_TOKEN: within both caps
+        check_quote(&dex, &cap, "0x2::sui::SUI", mist_token, 1_000_000_000, 4_900_000, 4_850_000).unwrap();
+
+        // Quote above the absolute cap
+        let err = check_quote(&dex, &cap, "0x2::sui::SUI", mist_token, 1_000_000_000, 5_000_001, 4_950_000).unwrap_err();
+        assert!(err.to_string().contains("exceeds MIST_MAX_OUTPUT_AMOUNT"), "{}", err);
+
+        // Quote above 20x the input, though under the absolute cap
+        let err = check_quote(&dex, &cap, "0x2::sui::SUI", mist_token, 100_000, 2_000_001, 1_980_000).unwrap_err();
+        assert!(err.to_string().contains("20x input"), "{}", err);
+    }
+}
@@ -6,8 +6,12 @@
 //! This module defines the core types used in the privacy-preserving swap system.
 //! These types must match the Move contract definitions exactly.
 
+use crate::EnclaveError;
 use serde::{Deserialize, Serialize};
 
+/// Nullifier length in bytes
+pub const NULLIFIER_LEN: usize = 32;
+
 /// Nullifier - 32 bytes random value that breaks deposit→swap link
 /// Generated at deposit time, revealed at swap time
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -15,15 +19,39 @@ pub struct Nullifier(pub [u8; 32]);
 
 impl Nullifier {
     pub fn to_hex(&self) -> String {
-        hex::encode(&self.0)
+        hex::encode(self.0)
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, EnclaveError> {
+        normalize_nullifier(s).map(Self)
     }
+}
 
-    pub fn from_hex(s: &str) -> Result<Self, hex::FromHexError> {
-        let bytes = hex::decode(s)?;
-        let mut arr = [0u8; 32];
-        arr.copy_from_slice(&bytes);
-        Ok(Self(arr))
+/// Validate and normalize a hex nullifier string into fixed bytes.
+///
+/// Accepts an optional `0x` prefix and either case. Rejects anything that is
+/// not exactly 32 bytes of valid hex, so malformed nullifiers never reach the
+/// spent-set or the on-chain call.
+pub fn normalize_nullifier(s: &str) -> Result<[u8; NULLIFIER_LEN], EnclaveError> {
+    let trimmed = s.trim();
+    let hex_str = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+
+    if hex_str.len() != NULLIFIER_LEN * 2 {
+        return Err(EnclaveError::InvalidInput(format!(
+            "nullifier must be {} bytes ({} hex chars), got {} hex chars",
+            NULLIFIER_LEN,
+            NULLIFIER_LEN * 2,
+            hex_str.len()
+        )));
     }
+
+    let mut bytes = [0u8; NULLIFIER_LEN];
+    hex::decode_to_slice(hex_str, &mut bytes)
+        .map_err(|e| EnclaveError::InvalidInput(format!("nullifier is not valid hex: {}", e)))?;
+    Ok(bytes)
 }
 
 /// Encrypted data from SEAL (opaque bytes)
@@ -55,9 +83,9 @@ impl TokenType {
     }
 }
 
-/// ============================================
-/// REQUEST/RESPONSE TYPES FOR TEE ENDPOINTS
-/// ============================================
+// ============================================
+// REQUEST/RESPONSE TYPES FOR TEE ENDPOINTS
+// ============================================
 
 /// Request to process a swap intent
 /// Sent by frontend with SEAL-encrypted data
@@ -104,9 +132,9 @@ pub struct ProcessSwapIntentResponse {
     pub spent_nullifier: Option<String>,
 }
 
-/// ============================================
-/// ATTESTATION-RELATED TYPES
-/// ============================================
+// ============================================
+// ATTESTATION-RELATED TYPES
+// ============================================
 
 /// TEE registration data
 #[derive(Debug, Serialize, Deserialize)]
@@ -123,9 +151,9 @@ pub struct TeeRegistrationData {
     pub attestation_document: String,
 }
 
-/// ============================================
-/// NULLIFIER REGISTRY TYPES
-/// ============================================
+// ============================================
+// NULLIFIER REGISTRY TYPES
+// ============================================
 
 /// Check if a nullifier has been spent
 #[derive(Debug, Serialize, Deserialize)]
@@ -138,9 +166,9 @@ pub struct CheckNullifierResponse {
     pub is_spent: bool,
 }
 
-/// ============================================
-/// INTERNAL TYPES FOR DEPOSIT SCANNING
-/// ============================================
+// ============================================
+// INTERNAL TYPES FOR DEPOSIT SCANNING
+// ============================================
 
 /// Deposit object from on-chain (what TEE scans)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -176,6 +204,40 @@ mod tests {
         let hex = original.to_hex();
         let recovered = Nullifier::from_hex(&hex).unwrap();
         assert_eq!(original, recovered);
+
+        // Prefixed input is accepted, wrong length is an error (not a panic)
+        assert_eq!(Nullifier::from_hex(&format!("0x{}", hex)).unwrap(), original);
+        assert!(Nullifier::from_hex("0x1234").is_err());
+    }
+
+    #[test]
+    fn test_normalize_nullifier_valid() {
+        let hex = "ab".repeat(32);
+        assert_eq!(normalize_nullifier(&hex).unwrap(), [0xAB; 32]);
+        assert_eq!(normalize_nullifier(&format!("0x{}", hex)).unwrap(), [0xAB; 32]);
+        assert_eq!(
+            normalize_nullifier(&format!("0x{}", hex.to_uppercase())).unwrap(),
+            [0xAB; 32]
+        );
+    }
+
+    #[test]
+    fn test_normalize_nullifier_wrong_length() {
+        assert!(matches!(
+            normalize_nullifier("0x1234567890abcdef"),
+            Err(EnclaveError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            normalize_nullifier(&"ab".repeat(33)),
+            Err(EnclaveError::InvalidInput(_))
+        ));
+        assert!(matches!(normalize_nullifier("0x"), Err(EnclaveError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_normalize_nullifier_non_hex() {
+        let bad = format!("0x{}zz", "ab".repeat(31));
+        assert!(matches!(normalize_nullifier(&bad), Err(EnclaveError::InvalidInput(_))));
     }
 
     #[test]
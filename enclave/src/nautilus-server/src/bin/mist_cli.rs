@@ -0,0 +1,143 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Operator CLI for debugging intents
+//!
+//! `mist-cli process <intent_id>` runs the exact processing path of the
+//! background processor (decrypt, verify, execute) for a single intent, with
+//! verbose output, without starting the server or the polling loop.
+
+use anyhow::Result;
+use nautilus_server::app::{intent_processor, SwapExecutionResult, LOG_PRIVACY};
+use nautilus_server::AppState;
+use std::future::Future;
+
+const USAGE: &str = "Usage: mist-cli process <intent_id>";
+
+#[derive(Debug, PartialEq, Eq)]
+enum CliCommand {
+    /// Process a single intent once
+    Process { intent_id: String },
+}
+
+fn parse_args(args: &[String]) -> Result<CliCommand> {
+    match args {
+        [cmd, intent_id] if cmd == "process" => Ok(CliCommand::Process {
+            intent_id: intent_id.clone(),
+        }),
+        _ => Err(anyhow::anyhow!(USAGE)),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    // Verbose by default; RUST_LOG still takes precedence
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("debug")),
+        )
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = parse_args(&args)?;
+
+    run(command, |intent_id| async move {
+        println!("[1/3] Loading backend key and config");
+        let backend_kp = nautilus_server::app::load_backend_keypair()?;
+        let state = AppState::new(backend_kp, String::new());
+        println!("      SEAL package: {}", nautilus_server::app::SEAL_CONFIG.package_id);
+
+        println!("[2/3] Connecting to {}", intent_processor::SUI_RPC_URL);
+        let sui_client = sui_sdk::SuiClientBuilder::default()
+            .build(intent_processor::SUI_RPC_URL)
+            .await?;
+
+        println!("[3/3] Processing intent {}", intent_id);
+        intent_processor::process_single_intent(&intent_id, &sui_client, &state).await
+    })
+    .await
+}
+
+/// Dispatch a command. Processing is injected so the flow can be exercised
+/// without a Sui node or SEAL servers.
+async fn run<F, Fut>(command: CliCommand, process: F) -> Result<()>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<SwapExecutionResult>>,
+{
+    match command {
+        CliCommand::Process { intent_id } => match process(intent_id.clone()).await {
+            Ok(result) => {
+                // Same MIST_LOG_PRIVACY rules as the processor
+                match LOG_PRIVACY.format_swap_result(&result) {
+                    Some(line) => println!("Processed: {}", line),
+                    None => println!("Intent {} processed", result.intent_id),
+                }
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("Intent {} failed: {:#}", intent_id, e);
+                Err(e)
+            }
+        },
+    }
+}
+
+#[cfg(all(test, feature = "mist-protocol"))]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn mock_result(intent_id: &str) -> SwapExecutionResult {
+        SwapExecutionResult {
+            success: true,
+            intent_id: intent_id.to_string(),
+            nullifier_hash: String::new(),
+            output_amount: 100,
+            remainder_amount: 0,
+            output_stealth: "0xstealth".to_string(),
+            remainder_stealth: "0xremainder".to_string(),
+            venue: "flowx".to_string(),
+            tx_digest: Some("digest".to_string()),
+            error: None,
+            error_code: None,
+            executed: true,
+            simulated: false,
+            seal_servers: vec![],
+            receipt: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&args(&["process", "0x123"])).unwrap(),
+            CliCommand::Process { intent_id: "0x123".to_string() }
+        );
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["process"])).is_err());
+        assert!(parse_args(&args(&["replay", "0x123"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_process_subcommand_with_mock() {
+        let command = parse_args(&args(&["process", "0xabc"])).unwrap();
+
+        let ok = run(command, |id| async move {
+            assert_eq!(id, "0xabc");
+            Ok(mock_result(&id))
+        })
+        .await;
+        assert!(ok.is_ok());
+
+        let command = parse_args(&args(&["process", "0xdead"])).unwrap();
+        let err = run(command, |_| async { Err(anyhow::anyhow!("SEAL unavailable")) }).await;
+        assert!(err.unwrap_err().to_string().contains("SEAL unavailable"));
+    }
+}
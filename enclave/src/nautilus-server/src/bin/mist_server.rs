@@ -2,54 +2,181 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use axum::{routing::get, Router};
-use fastcrypto::ed25519::Ed25519KeyPair;
-use nautilus_server::common::{get_attestation, health_check};
+use axum::http::HeaderName;
+use axum::extract::State;
+use axum::{middleware, routing::get, Json, Router};
+use nautilus_server::common::{get_attestation, health_check, mark_started, PingResponse};
+use nautilus_server::request_id::{propagate_request_id, REQUEST_ID_HEADER};
+use nautilus_server::app::backlog::BacklogStatus;
 use nautilus_server::AppState;
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Uptime reported by ping
+    mark_started();
+
     // Load .env file (contains BACKEND_PRIVATE_KEY)
     dotenv::dotenv().ok();
 
-    // Initialize tracing subscriber to see logs
-    tracing_subscriber::fmt::init();
+    // Initialize tracing subscriber to see logs (and export spans with `otel`)
+    let _telemetry = nautilus_server::telemetry::init()?;
 
     println!("✅ Backend starting...");
+    #[cfg(feature = "trusted-input")]
+    println!("⚠️  INSECURE: trusted-input build, plaintext intent details are accepted without SEAL");
 
     // Load backend keypair from environment (persistent, not ephemeral!)
-    let backend_kp = load_backend_keypair()?;
+    let backend_kp = nautilus_server::app::load_backend_keypair()?;
+
+    // Pick the backend signer (local key or KMS), log its address and check it
+    // against the contract-authorized signer (only when mist-protocol feature is enabled)
+    #[cfg(feature = "mist-protocol")]
+    let signer = {
+        use nautilus_server::app::signer::SignerConfig;
+        use nautilus_server::app::{check_backend_address, EXPECTED_BACKEND_ADDRESS_ENV};
+        let config = SignerConfig::from_env()?;
+        let signer = config.build(&backend_kp);
+        let address = signer.address();
+
+        if let SignerConfig::Kms { url, .. } = &config {
+            println!("🔑 Backend signer: KMS at {}", url);
+        }
+        println!("🔑 Backend Wallet: {}", address);
 
-    // Log the backend wallet address (only when mist-protocol feature is enabled)
+        let expected = std::env::var(EXPECTED_BACKEND_ADDRESS_ENV).ok();
+        check_backend_address(&address.to_string(), expected.as_deref())?;
+        if expected.is_some() {
+            println!("🔑 Matches {} (contract-authorized signer)\n", EXPECTED_BACKEND_ADDRESS_ENV);
+        } else {
+            println!("🔑 This address is hardcoded in contract for authorization");
+            println!("⚠️  {} not set; skipping signer check\n", EXPECTED_BACKEND_ADDRESS_ENV);
+        }
+        signer
+    };
+
+    // Check the on-chain Enclave registration holds this keypair's public key
     #[cfg(feature = "mist-protocol")]
     {
-        use fastcrypto::traits::ToFromBytes;
-        let priv_key_bytes = backend_kp.as_bytes();
-        let key_bytes: [u8; 32] = priv_key_bytes[..32].try_into().unwrap();
-        let sui_private_key = sui_crypto::ed25519::Ed25519PrivateKey::new(key_bytes);
-        let address = sui_private_key.public_key().to_address();
+        use fastcrypto::traits::{KeyPair, ToFromBytes};
+        use nautilus_server::app::intent_processor::SUI_RPC_URL;
+        use nautilus_server::app::registration::{verify_registration, ENCLAVE_OBJECT_ENV};
+        match std::env::var(ENCLAVE_OBJECT_ENV) {
+            Ok(enclave_id) => {
+                let sui_client = sui_sdk::SuiClientBuilder::default().build(SUI_RPC_URL).await?;
+                verify_registration(&sui_client, &enclave_id, backend_kp.public().as_bytes()).await?;
+                println!("🏛️  Enclave {} registered with this keypair\n", enclave_id);
+            }
+            Err(_) => println!("⚠️  {} not set; skipping enclave registration check\n", ENCLAVE_OBJECT_ENV),
+        }
+    }
 
-        println!("🔑 Backend Wallet: {}", address);
-        println!("🔑 This address is hardcoded in contract for authorization\n");
+    // Validate runtime configuration up front rather than on the first swap
+    #[cfg(feature = "mist-protocol")]
+    {
+        use nautilus_server::app::{
+            DEADLINE_MARGIN, GAS_COIN_STRATEGY, LOG_PRIVACY, MAX_DECRYPTED_BYTES, OUTPUT_CAP, OUTPUT_ROUNDING,
+            PENDING_AGE_ALERT, POOL_SELECTION_STRATEGY, PREFETCH_QUOTES, PROCESSING_ORDER, RPC_CONNECT_ATTEMPTS,
+            SEAL_CONFIG, SEAL_DECRYPT_TIMEOUT, SEAL_HEADERS, SEAL_MAX_RESPONSE_AGE, SEAL_SESSION_TTL, SIMULATE_ONLY,
+            STARTUP_CANARY, SUBMIT_LIMITER, SUBMIT_TIMEOUT, SUI_RPC_URLS, SUMMARY_INTERVAL,
+        };
+        use nautilus_server::app::seal_types::SEAL_CONFIG_PATH_ENV;
+        lazy_static::initialize(&SEAL_CONFIG);
+        match std::env::var(SEAL_CONFIG_PATH_ENV) {
+            Ok(path) => println!("🗝️  SEAL config: {} ({} key servers)", path, SEAL_CONFIG.key_servers.len()),
+            Err(_) => println!("🗝️  SEAL config: embedded ({} key servers)", SEAL_CONFIG.key_servers.len()),
+        }
+        if let Some(name) = &SEAL_CONFIG.mvr_name {
+            println!("📛 Package {} resolved from MVR name {}", SEAL_CONFIG.package_id, name);
+        }
+        lazy_static::initialize(&SUI_RPC_URLS);
+        println!("🌐 Sui RPC endpoints: {}", SUI_RPC_URLS.join(", "));
+        lazy_static::initialize(&RPC_CONNECT_ATTEMPTS);
+        println!("🔁 Sui RPC connection attempts at startup: {}", *RPC_CONNECT_ATTEMPTS);
+        lazy_static::initialize(&POOL_SELECTION_STRATEGY);
+        println!("🔀 FlowX pool selection: {:?}", *POOL_SELECTION_STRATEGY);
+        lazy_static::initialize(&OUTPUT_ROUNDING);
+        println!("🔢 Output rounding: {:?}", *OUTPUT_ROUNDING);
+        lazy_static::initialize(&LOG_PRIVACY);
+        println!("🔒 Log privacy: {:?}", *LOG_PRIVACY);
+        lazy_static::initialize(&MAX_DECRYPTED_BYTES);
+        println!("📏 Max decrypted intent size: {} bytes", *MAX_DECRYPTED_BYTES);
+        lazy_static::initialize(&OUTPUT_CAP);
+        println!("🧯 Output cap: {:?}", *OUTPUT_CAP);
+        lazy_static::initialize(&SEAL_HEADERS);
+        println!("🔐 SEAL extra headers: {:?}", *SEAL_HEADERS);
+        lazy_static::initialize(&SUMMARY_INTERVAL);
+        println!("📊 Summary interval: {:?}", *SUMMARY_INTERVAL);
+        lazy_static::initialize(&PROCESSING_ORDER);
+        println!("📋 Processing order: {:?}", *PROCESSING_ORDER);
+        lazy_static::initialize(&PENDING_AGE_ALERT);
+        println!("⏰ Pending intent age alert: {:?}", *PENDING_AGE_ALERT);
+        lazy_static::initialize(&DEADLINE_MARGIN);
+        println!("⏳ Deadline margin before submission: {:?}", *DEADLINE_MARGIN);
+        lazy_static::initialize(&SEAL_MAX_RESPONSE_AGE);
+        println!("⏱️  SEAL max response age: {:?}", *SEAL_MAX_RESPONSE_AGE);
+        lazy_static::initialize(&SEAL_DECRYPT_TIMEOUT);
+        println!("⏱️  SEAL fetch and decrypt timeout: {:?}", *SEAL_DECRYPT_TIMEOUT);
+        lazy_static::initialize(&SEAL_SESSION_TTL);
+        println!("⏱️  SEAL session certificate TTL: {} min", *SEAL_SESSION_TTL);
+        lazy_static::initialize(&SUBMIT_TIMEOUT);
+        println!("⏱️  Transaction submit timeout: {:?}", *SUBMIT_TIMEOUT);
+        lazy_static::initialize(&GAS_COIN_STRATEGY);
+        println!("⛽ Gas coin strategy: {:?}", *GAS_COIN_STRATEGY);
+        lazy_static::initialize(&SUBMIT_LIMITER);
+        println!("🚦 Max concurrent submissions: {}", SUBMIT_LIMITER.limit());
+        lazy_static::initialize(&PREFETCH_QUOTES);
+        println!("⚡ Quote prefetch during decryption: {}", *PREFETCH_QUOTES);
+        lazy_static::initialize(&STARTUP_CANARY);
+        println!("🐤 Startup canary: {:?}", *STARTUP_CANARY);
+        lazy_static::initialize(&SIMULATE_ONLY);
+        if *SIMULATE_ONLY {
+            println!("🧪 Simulate-only mode: swaps are dry-run, never submitted");
+        }
     }
 
     // For mist-protocol, we don't need API_KEY
     let api_key = String::new();
-    let state = Arc::new(AppState { eph_kp: backend_kp, api_key });
+    let state = AppState::new(backend_kp, api_key);
+    #[cfg(feature = "mist-protocol")]
+    let state = state.with_signer(signer);
+    let state = Arc::new(state);
+
+    // Run a synthetic intent through SEAL decryption and validation
+    #[cfg(feature = "mist-protocol")]
+    {
+        use nautilus_server::app::canary::{startup_canary, CanaryMode};
+        use nautilus_server::app::STARTUP_CANARY;
+        if *STARTUP_CANARY != CanaryMode::Off {
+            match startup_canary(&state).await {
+                Ok(()) => println!("🐤 Startup canary passed\n"),
+                Err(e) if *STARTUP_CANARY == CanaryMode::Required => return Err(e.context("Startup canary failed")),
+                Err(e) => println!("⚠️  Startup canary failed: {:#}\n", e),
+            }
+        }
+    }
 
     // Define your own restricted CORS policy here if needed.
     let cors = CorsLayer::new()
         .allow_methods(Any)
         .allow_headers(Any)
-        .allow_origin(Any); // Allow all origins for development
+        .allow_origin(Any) // Allow all origins for development
+        .expose_headers([HeaderName::from_static(REQUEST_ID_HEADER)]);
 
     let app = Router::new()
         .route("/", get(ping))
         .route("/get_attestation", get(get_attestation))
         .route("/health_check", get(health_check))
+        .route("/status", get(status));
+
+    // Admin endpoints for pausing/resuming intent processing
+    #[cfg(feature = "mist-protocol")]
+    let app = app.merge(nautilus_server::app::admin::router());
+
+    let app = app
         .with_state(state.clone())
+        .layer(middleware::from_fn(propagate_request_id))
         .layer(cors);
 
     // Spawn intent processor background task if mist-protocol feature is enabled
@@ -57,9 +184,12 @@ async fn main() -> Result<()> {
     {
         use nautilus_server::app::intent_processor;
         let processor_state = state.clone();
-        tokio::spawn(async move {
-            intent_processor::start_intent_processor(processor_state).await;
-        });
+        tokio::spawn(intent_processor::supervise(
+            processor_state,
+            std::time::Duration::from_secs(5),
+            None,
+            intent_processor::start_intent_processor,
+        ));
     }
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3001").await?;
@@ -69,50 +199,14 @@ async fn main() -> Result<()> {
         .map_err(|e| anyhow::anyhow!("Server error: {}", e))
 }
 
-async fn ping() -> &'static str {
-    "Pong!"
+async fn ping() -> Json<PingResponse> {
+    Json(PingResponse::new(
+        "mist-server",
+        nautilus_server::app::intent_processor::SUI_NETWORK,
+    ))
 }
 
-/// Load backend keypair from environment variable
-///
-/// Expects BACKEND_PRIVATE_KEY in Bech32 format (suiprivkey1...)
-fn load_backend_keypair() -> Result<Ed25519KeyPair> {
-    let private_key_str = std::env::var("BACKEND_PRIVATE_KEY")
-        .map_err(|_| anyhow::anyhow!(
-            "BACKEND_PRIVATE_KEY not found in environment.\n\
-             Generate a wallet with: sui client new-address ed25519\n\
-             Then set BACKEND_PRIVATE_KEY=<private_key>"
-        ))?;
-
-    // Decode Bech32 private key
-    use bech32::FromBase32;
-    let (hrp, data, _variant) = bech32::decode(&private_key_str)
-        .map_err(|e| anyhow::anyhow!("Invalid Bech32 private key: {}", e))?;
-
-    if hrp != "suiprivkey" {
-        return Err(anyhow::anyhow!("Invalid HRP: expected 'suiprivkey', got '{}'", hrp));
-    }
-
-    let decoded_bytes = Vec::<u8>::from_base32(&data)
-        .map_err(|e| anyhow::anyhow!("Failed to decode base32: {}", e))?;
-
-    // First byte is the scheme (0x00 for ed25519), rest is the 32-byte private key
-    if decoded_bytes.len() != 33 {
-        return Err(anyhow::anyhow!("Invalid key length: expected 33 bytes, got {}", decoded_bytes.len()));
-    }
-
-    if decoded_bytes[0] != 0x00 {
-        return Err(anyhow::anyhow!("Invalid key scheme: expected ed25519 (0x00), got 0x{:02x}", decoded_bytes[0]));
-    }
-
-    let key_bytes: [u8; 32] = decoded_bytes[1..33]
-        .try_into()
-        .map_err(|_| anyhow::anyhow!("Failed to extract 32 bytes"))?;
-
-    // Use ToFromBytes trait
-    use fastcrypto::traits::ToFromBytes;
-    let keypair = Ed25519KeyPair::from_bytes(&key_bytes)
-        .map_err(|e| anyhow::anyhow!("Failed to create keypair: {}", e))?;
-
-    Ok(keypair)
+/// Intent processor backlog, for monitoring
+async fn status(State(state): State<Arc<AppState>>) -> Json<BacklogStatus> {
+    Json(state.backlog.snapshot())
 }
@@ -4,6 +4,7 @@
 use crate::AppState;
 use crate::EnclaveError;
 use axum::{extract::State, Json};
+use fastcrypto::hash::{Blake2b256, HashFunction};
 use fastcrypto::traits::Signer;
 use fastcrypto::{encoding::Encoding, traits::ToFromBytes};
 use fastcrypto::{encoding::Hex, traits::KeyPair as FcKeyPair};
@@ -16,15 +17,15 @@ use serde_repr::Deserialize_repr;
 use serde_repr::Serialize_repr;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tracing::info;
 
 use fastcrypto::ed25519::Ed25519KeyPair;
 /// ==== COMMON TYPES ====
 /// Intent message wrapper struct containing the intent scope and timestamp.
 /// This standardizes the serialized payload for signing.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntentMessage<T: Serialize> {
     pub intent: IntentScope,
     pub timestamp_ms: u64,
@@ -33,10 +34,12 @@ pub struct IntentMessage<T: Serialize> {
 
 /// Intent scope enum. Add new scope here if needed, each corresponds to a
 /// scope for signing. Replace in with your own intent per message type being signed by the enclave.
-#[derive(Serialize_repr, Deserialize_repr, Debug)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum IntentScope {
     ProcessData = 0,
+    /// Signed receipt for an executed swap
+    SwapReceipt = 1,
 }
 
 impl<T: Serialize + Debug> IntentMessage<T> {
@@ -50,7 +53,7 @@ impl<T: Serialize + Debug> IntentMessage<T> {
 }
 
 /// Wrapper struct containing the response (the intent message) and signature.
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessedDataResponse<T> {
     pub response: T,
     pub signature: String,
@@ -84,6 +87,44 @@ pub fn to_signed_response<T: Serialize + Clone>(
 }
 
 /// ==== HEALTHCHECK, GET ATTESTASTION ENDPOINT IMPL ====
+/// Process start time, set once at startup.
+static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// Record the process start time for `ping`'s uptime. Later calls are no-ops.
+pub fn mark_started() {
+    STARTED_AT.get_or_init(Instant::now);
+}
+
+/// Time since `mark_started` (or since the first call, if it was never called).
+pub fn uptime() -> Duration {
+    STARTED_AT.get_or_init(Instant::now).elapsed()
+}
+
+/// Ping response, so monitoring can confirm the running build and uptime.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PingResponse {
+    pub service: String,
+    /// Crate version.
+    pub version: String,
+    /// Commit the binary was built from (`GIT_SHA` at build time).
+    pub git_sha: String,
+    pub uptime_secs: u64,
+    /// Sui network the service talks to.
+    pub network: String,
+}
+
+impl PingResponse {
+    pub fn new(service: &str, network: &str) -> Self {
+        Self {
+            service: service.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: option_env!("GIT_SHA").unwrap_or("unknown").to_string(),
+            uptime_secs: uptime().as_secs(),
+            network: network.to_string(),
+        }
+    }
+}
+
 /// Response for get attestation.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetAttestationResponse {
@@ -92,36 +133,105 @@ pub struct GetAttestationResponse {
 }
 
 /// Endpoint that returns an attestation committed
-/// to the enclave's public key.
+/// to the enclave's public key. The same document is served until the key
+/// changes, so it is the one swap receipts' `attestation_hash` refers to.
 pub async fn get_attestation(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<GetAttestationResponse>, EnclaveError> {
     info!("get attestation called");
 
-    let pk = state.eph_kp.public();
+    let document = state
+        .attestation
+        .document(state.eph_kp.public().as_bytes(), attestation_document)?;
+    Ok(Json(GetAttestationResponse {
+        attestation: Hex::encode(document),
+    }))
+}
+
+/// Request an attestation document committed to `public_key` from the NSM
+/// driver. Fails outside a Nitro enclave.
+pub fn attestation_document(public_key: &[u8]) -> Result<Vec<u8>, EnclaveError> {
     let fd = driver::nsm_init();
 
     // Send attestation request to NSM driver with public key set.
     let request = NsmRequest::Attestation {
         user_data: None,
         nonce: None,
-        public_key: Some(ByteBuf::from(pk.as_bytes().to_vec())),
+        public_key: Some(ByteBuf::from(public_key.to_vec())),
     };
 
     let response = driver::nsm_process_request(fd, request);
+    driver::nsm_exit(fd);
     match response {
-        NsmResponse::Attestation { document } => {
-            driver::nsm_exit(fd);
-            Ok(Json(GetAttestationResponse {
-                attestation: Hex::encode(document),
-            }))
-        }
-        _ => {
-            driver::nsm_exit(fd);
-            Err(EnclaveError::GenericError(
-                "unexpected response".to_string(),
-            ))
+        NsmResponse::Attestation { document } => Ok(document),
+        _ => Err(EnclaveError::GenericError(
+            "unexpected response".to_string(),
+        )),
+    }
+}
+
+/// Hex Blake2b-256 of an attestation document, as returned by `get_attestation`
+pub fn attestation_hash(document: &[u8]) -> String {
+    Hex::encode(Blake2b256::digest(document).digest)
+}
+
+/// Attestation document for the current enclave key, with its hash.
+///
+/// Every NSM document carries its own timestamp and signature, so a document
+/// is requested once per public key and that same document is both served by
+/// `get_attestation` and hashed into receipts. A different key triggers a new
+/// attestation. Failures are not cached.
+#[derive(Debug, Default)]
+pub struct AttestationCache {
+    cached: Mutex<Option<CachedAttestation>>,
+}
+
+#[derive(Debug)]
+struct CachedAttestation {
+    public_key: Vec<u8>,
+    document: Vec<u8>,
+    hash: String,
+}
+
+impl AttestationCache {
+    /// Attestation document for `public_key`, requested with `attest` only
+    /// when the key differs from the cached one
+    pub fn document(
+        &self,
+        public_key: &[u8],
+        attest: impl FnOnce(&[u8]) -> Result<Vec<u8>, EnclaveError>,
+    ) -> Result<Vec<u8>, EnclaveError> {
+        self.with_attestation(public_key, attest, |cached| cached.document.clone())
+    }
+
+    /// `attestation_hash` of the document `document` returns for `public_key`
+    pub fn hash(
+        &self,
+        public_key: &[u8],
+        attest: impl FnOnce(&[u8]) -> Result<Vec<u8>, EnclaveError>,
+    ) -> Result<String, EnclaveError> {
+        self.with_attestation(public_key, attest, |cached| cached.hash.clone())
+    }
+
+    fn with_attestation<T>(
+        &self,
+        public_key: &[u8],
+        attest: impl FnOnce(&[u8]) -> Result<Vec<u8>, EnclaveError>,
+        read: impl FnOnce(&CachedAttestation) -> T,
+    ) -> Result<T, EnclaveError> {
+        let mut cached = self.cached.lock().unwrap();
+        match cached.as_ref() {
+            Some(current) if current.public_key == public_key => {}
+            _ => {
+                let document = attest(public_key)?;
+                *cached = Some(CachedAttestation {
+                    public_key: public_key.to_vec(),
+                    hash: attestation_hash(&document),
+                    document,
+                });
+            }
         }
+        Ok(read(cached.as_ref().expect("attestation cached above")))
     }
 }
 
@@ -219,3 +329,61 @@ pub async fn health_check(
         endpoints_status,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_reports_build_and_uptime() {
+        mark_started();
+        let before = uptime();
+        std::thread::sleep(Duration::from_millis(1100));
+        assert!(uptime() > before);
+
+        let ping = PingResponse::new("mist-server", "testnet");
+        assert!(ping.uptime_secs >= 1);
+        assert_eq!(ping.version, env!("CARGO_PKG_VERSION"));
+        assert!(!ping.git_sha.is_empty());
+
+        let json = serde_json::to_value(&ping).unwrap();
+        for field in ["service", "version", "git_sha", "uptime_secs", "network"] {
+            assert!(json.get(field).is_some(), "missing {}", field);
+        }
+        assert_eq!(json["service"], "mist-server");
+        assert_eq!(json["network"], "testnet");
+    }
+
+    #[test]
+    fn test_attestation_cached_until_key_changes() {
+        let cache = AttestationCache::default();
+        let calls = std::cell::Cell::new(0);
+        // Like NSM, every request yields a different document
+        let attest = |pk: &[u8]| {
+            calls.set(calls.get() + 1);
+            Ok([b"doc:".as_slice(), pk, &[calls.get() as u8]].concat())
+        };
+
+        let first = cache.hash(b"key-1", attest).unwrap();
+        assert_eq!(first.len(), 64);
+        assert_eq!(cache.hash(b"key-1", attest).unwrap(), first);
+        assert_eq!(calls.get(), 1);
+
+        // The served document is the one the hash covers
+        let served = cache.document(b"key-1", attest).unwrap();
+        assert_eq!(attestation_hash(&served), first);
+        assert_eq!(calls.get(), 1);
+
+        // Rotated key: new attestation, new hash
+        let rotated = cache.document(b"key-2", attest).unwrap();
+        assert_eq!(cache.hash(b"key-2", attest).unwrap(), attestation_hash(&rotated));
+        assert_ne!(attestation_hash(&rotated), first);
+        assert_eq!(calls.get(), 2);
+
+        // A failed attestation is retried next time
+        let failing = |_: &[u8]| Err(EnclaveError::GenericError("no NSM".to_string()));
+        assert!(cache.hash(b"key-3", failing).is_err());
+        assert_eq!(cache.document(b"key-2", attest).unwrap(), rotated);
+        assert_eq!(calls.get(), 2);
+    }
+}
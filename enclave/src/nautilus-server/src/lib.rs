@@ -8,6 +8,7 @@ use axum::Json;
 use fastcrypto::ed25519::Ed25519KeyPair;
 use serde_json::json;
 use std::fmt;
+use std::sync::atomic::AtomicBool;
 
 mod apps {
     #[cfg(feature = "mist-protocol")]
@@ -30,7 +31,12 @@ pub mod flowx {
     pub use crate::apps::flowx::*;
 }
 
+pub mod circuit_breaker;
 pub mod common;
+pub mod request_id;
+pub mod telemetry;
+
+use circuit_breaker::CircuitBreaker;
 
 /// App state, at minimum needs to maintain the ephemeral keypair.  
 pub struct AppState {
@@ -38,6 +44,56 @@ pub struct AppState {
     pub eph_kp: Ed25519KeyPair,
     /// API key when querying api.weatherapi.com
     pub api_key: String,
+    /// When set, the intent processor keeps polling but skips execution
+    pub paused: AtomicBool,
+    /// Attestation document for `eph_kp`, served by `get_attestation` and
+    /// hashed into signed receipts so they can be tied to the attested build
+    pub attestation: common::AttestationCache,
+    /// SEAL key server circuit breaker. Kept here (not in the processor task)
+    /// so a re-spawned processor doesn't reset it and re-hammer dead servers.
+    pub seal_breaker: CircuitBreaker,
+    /// Attempts, stage reached and last error per intent, for diagnostics
+    pub intent_diagnostics: app::diagnostics::IntentDiagnostics,
+    /// Pending, in-flight and finished intent counts for `GET /status`
+    pub backlog: app::backlog::BacklogCounters,
+    /// Latest intent failures (ID, time, failure class) for admin triage
+    pub recent_failures: app::recent_failures::RecentFailures,
+    /// Backend key for SEAL certificates and swap transactions. Defaults to
+    /// `eph_kp`; swap in a KMS-backed signer with `with_signer`.
+    #[cfg(feature = "mist-protocol")]
+    pub signer: Box<dyn app::signer::Signer>,
+    /// SEAL decryptions by hash of the encrypted object, so retried intents
+    /// skip the key servers. Cleared by `with_signer`.
+    #[cfg(feature = "mist-protocol")]
+    pub decrypt_cache: app::decrypt_cache::DecryptCache<app::intent_processor::DecryptOutcome>,
+}
+
+impl AppState {
+    pub fn new(eph_kp: Ed25519KeyPair, api_key: String) -> Self {
+        Self {
+            #[cfg(feature = "mist-protocol")]
+            signer: Box::new(app::signer::LocalSigner::from_keypair(&eph_kp)),
+            eph_kp,
+            api_key,
+            paused: AtomicBool::new(false),
+            attestation: common::AttestationCache::default(),
+            seal_breaker: CircuitBreaker::from_env(),
+            intent_diagnostics: app::diagnostics::IntentDiagnostics::default(),
+            backlog: app::backlog::BacklogCounters::default(),
+            recent_failures: app::recent_failures::RecentFailures::from_env(),
+            #[cfg(feature = "mist-protocol")]
+            decrypt_cache: app::decrypt_cache::DecryptCache::from_env(),
+        }
+    }
+
+    /// Replace the backend signer. Cached decryptions were authorized for the
+    /// previous key, so they are dropped.
+    #[cfg(feature = "mist-protocol")]
+    pub fn with_signer(mut self, signer: Box<dyn app::signer::Signer>) -> Self {
+        self.signer = signer;
+        self.decrypt_cache.clear();
+        self
+    }
 }
 
 /// Implement IntoResponse for EnclaveError.
@@ -47,6 +103,9 @@ impl IntoResponse for EnclaveError {
             EnclaveError::GenericError(e) => (StatusCode::BAD_REQUEST, e),
             EnclaveError::InvalidInput(e) => (StatusCode::BAD_REQUEST, e),
             EnclaveError::DecryptionFailed(e) => (StatusCode::INTERNAL_SERVER_ERROR, e),
+            EnclaveError::Unauthorized(e) => (StatusCode::UNAUTHORIZED, e),
+            EnclaveError::SealVersionMismatch(e) => (StatusCode::BAD_GATEWAY, e),
+            EnclaveError::KeyServerUnavailable(e) => (StatusCode::SERVICE_UNAVAILABLE, e),
         };
         let body = Json(json!({
             "error": error_message,
@@ -61,6 +120,11 @@ pub enum EnclaveError {
     GenericError(String),
     InvalidInput(String),
     DecryptionFailed(String),
+    Unauthorized(String),
+    /// A SEAL key server refused our `Client-Sdk-Version`
+    SealVersionMismatch(String),
+    /// The SEAL key servers didn't deliver a decryption in time
+    KeyServerUnavailable(String),
 }
 
 impl fmt::Display for EnclaveError {
@@ -69,6 +133,9 @@ impl fmt::Display for EnclaveError {
             EnclaveError::GenericError(e) => write!(f, "{}", e),
             EnclaveError::InvalidInput(e) => write!(f, "Invalid input: {}", e),
             EnclaveError::DecryptionFailed(e) => write!(f, "Decryption failed: {}", e),
+            EnclaveError::Unauthorized(e) => write!(f, "Unauthorized: {}", e),
+            EnclaveError::SealVersionMismatch(e) => write!(f, "SEAL SDK version mismatch: {}", e),
+            EnclaveError::KeyServerUnavailable(e) => write!(f, "Key server unavailable: {}", e),
         }
     }
 }
@@ -0,0 +1,168 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Request ID propagation
+//!
+//! Every request gets an ID, taken from `X-Request-Id` when the client sends
+//! a usable one and generated otherwise. It is attached to the request's
+//! tracing span, returned in the `X-Request-Id` response header, and added as
+//! `request_id` to JSON error bodies so frontend, backend and enclave logs can
+//! be correlated.
+
+use axum::body::{to_bytes, Body};
+use axum::extract::Request;
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Instrument;
+
+/// Header carrying the request ID in both directions
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied ID that is accepted as-is
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Error bodies larger than this are passed through untouched
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// Request ID of the current request, available to handlers as an extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Middleware: resolve the request ID, run the request in a span carrying it,
+/// and echo it on the response. Install with `axum::middleware::from_fn`.
+pub async fn propagate_request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| is_valid_request_id(v))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let response = next.run(request).instrument(span).await;
+
+    echo_request_id(response, &id).await
+}
+
+/// Client IDs end up in headers and logs: keep them short and plain
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+async fn echo_request_id(response: Response, id: &str) -> Response {
+    let (mut parts, body) = response.into_parts();
+    // Validated or generated above, so always a valid header value
+    if let Ok(value) = HeaderValue::from_str(id) {
+        parts.headers.insert(REQUEST_ID_HEADER, value);
+    }
+
+    let is_json = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let is_error = parts.status.is_client_error() || parts.status.is_server_error();
+    if !is_error || !is_json {
+        return Response::from_parts(parts, body);
+    }
+
+    // The body is rewritten (or dropped); let hyper recompute the length
+    parts.headers.remove(CONTENT_LENGTH);
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            fields.insert("request_id".to_string(), id.into());
+            Body::from(serde_json::Value::Object(fields).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EnclaveError;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::{middleware, Extension, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/ok", get(|Extension(id): Extension<RequestId>| async move { id.0 }))
+            .route(
+                "/fail",
+                get(|| async { Err::<(), _>(EnclaveError::InvalidInput("bad".to_string())) }),
+            )
+            .layer(middleware::from_fn(propagate_request_id))
+    }
+
+    fn get_request(uri: &str, request_id: Option<&str>) -> axum::http::Request<Body> {
+        let mut req = axum::http::Request::builder().uri(uri);
+        if let Some(id) = request_id {
+            req = req.header(REQUEST_ID_HEADER, id);
+        }
+        req.body(Body::empty()).unwrap()
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_supplied_request_id_is_echoed() {
+        let res = app().oneshot(get_request("/ok", Some("frontend-123"))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[REQUEST_ID_HEADER], "frontend-123");
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], b"frontend-123");
+
+        // Error bodies carry it too
+        let res = app().oneshot(get_request("/fail", Some("frontend-123"))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers()[REQUEST_ID_HEADER], "frontend-123");
+        let body = body_json(res).await;
+        assert_eq!(body["request_id"], "frontend-123");
+        assert_eq!(body["error"], "bad");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_generated_when_absent_or_invalid() {
+        let res = app().oneshot(get_request("/fail", None)).await.unwrap();
+        let header = res.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&header).is_ok());
+        assert_eq!(body_json(res).await["request_id"], header.as_str());
+
+        // Unusable client ID is replaced, not echoed
+        let res = app().oneshot(get_request("/ok", Some("has space"))).await.unwrap();
+        let header = res.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(header).is_ok());
+    }
+
+    #[test]
+    fn test_request_id_validation() {
+        assert!(is_valid_request_id("abc-123_x.y"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("has space"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracing setup for the server
+//!
+//! Log lines go to stdout, filtered by `RUST_LOG`. Built with the `otel`
+//! feature and with `MIST_OTLP_ENDPOINT` set, spans at INFO and above are
+//! also exported over OTLP/HTTP to `<endpoint>/v1/traces`, including the
+//! per-intent `process_intent` span and its `intent_id` attribute. Without
+//! either, nothing is exported.
+
+/// Environment variable with the OTLP collector base URL, e.g. `http://collector:4318`
+pub const OTLP_ENDPOINT_ENV: &str = "MIST_OTLP_ENDPOINT";
+
+/// `service.name` reported with exported spans
+#[cfg(feature = "otel")]
+const SERVICE_NAME: &str = "mist-server";
+
+/// Keeps the span exporter running; flushes pending spans when dropped
+#[derive(Default)]
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush OTLP spans: {}", e);
+            }
+        }
+    }
+}
+
+/// Install the global tracing subscriber. Hold the guard for the life of the
+/// process.
+pub fn init() -> anyhow::Result<TelemetryGuard> {
+    #[cfg(feature = "otel")]
+    if let Ok(endpoint) = std::env::var(OTLP_ENDPOINT_ENV) {
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::util::SubscriberInitExt;
+        use tracing_subscriber::Layer;
+
+        let provider = otlp_provider(&endpoint)?;
+        tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer().with_filter(tracing_subscriber::EnvFilter::from_default_env()))
+            .with(otlp_layer(&provider).with_filter(tracing_subscriber::filter::LevelFilter::INFO))
+            .try_init()?;
+        println!("📡 Exporting spans to {}", endpoint);
+        return Ok(TelemetryGuard { provider: Some(provider) });
+    }
+
+    tracing_subscriber::fmt::try_init().map_err(|e| anyhow::anyhow!(e))?;
+    Ok(TelemetryGuard::default())
+}
+
+/// Batch OTLP/HTTP exporter to `<endpoint>/v1/traces`
+#[cfg(feature = "otel")]
+fn otlp_provider(endpoint: &str) -> anyhow::Result<opentelemetry_sdk::trace::TracerProvider> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()?;
+    Ok(opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(opentelemetry_sdk::Resource::new([opentelemetry::KeyValue::new(
+            "service.name",
+            SERVICE_NAME,
+        )]))
+        .build())
+}
+
+/// Layer turning tracing spans into OpenTelemetry spans
+#[cfg(feature = "otel")]
+fn otlp_layer<S>(provider: &opentelemetry_sdk::trace::TracerProvider) -> impl tracing_subscriber::Layer<S>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME))
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Mock collector: records every `POST /v1/traces` body
+    async fn mock_collector() -> (String, Arc<Mutex<Vec<Bytes>>>) {
+        use axum::routing::post;
+
+        let received: Arc<Mutex<Vec<Bytes>>> = Arc::default();
+        let sink = received.clone();
+        let app = axum::Router::new().route(
+            "/v1/traces",
+            post(move |body: Bytes| {
+                let sink = sink.clone();
+                async move { sink.lock().unwrap().push(body) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (endpoint, received)
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_intent_span_exported_to_collector() {
+        let (endpoint, received) = mock_collector().await;
+        let provider = otlp_provider(&endpoint).unwrap();
+
+        let subscriber = tracing_subscriber::registry().with(otlp_layer(&provider));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("process_intent", intent_id = "0xfeedface");
+            span.in_scope(|| tracing::info!("processing"));
+        });
+
+        // The batch exporter sends on flush; flushing blocks, so off the runtime
+        let flushed = tokio::task::spawn_blocking(move || provider.force_flush()).await.unwrap();
+        assert!(flushed.iter().all(|result| result.is_ok()), "{:?}", flushed);
+
+        // OTLP protobuf carries names and string attributes verbatim
+        let body: Vec<u8> = received.lock().unwrap().iter().flat_map(|b| b.to_vec()).collect();
+        assert!(contains(&body, b"process_intent"), "span not exported");
+        assert!(contains(&body, b"intent_id"));
+        assert!(contains(&body, b"0xfeedface"));
+        assert!(contains(&body, SERVICE_NAME.as_bytes()));
+    }
+}
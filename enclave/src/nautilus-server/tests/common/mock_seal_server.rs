@@ -0,0 +1,206 @@
+//! Reusable SEAL key server stand-in
+//!
+//! `MockSealServer` serves `POST /v1/fetch_key` from a locally generated IBE
+//! master key, so objects encrypted to its public key decrypt without the
+//! live Mysten key servers. Like a real key server it derives the key for
+//! `package_id || id` from the request's `seal_approve` PTB and returns it
+//! ElGamal-encrypted to the request's `enc_key`. Certificates, signatures
+//! and the policy itself are not checked.
+//!
+//! The builder simulates failing, slow and version-checking servers:
+//!
+//! ```ignore
+//! let server = MockSealServer::builder().delay(Duration::from_millis(200)).start().await;
+//! let broken = MockSealServer::builder().fail_with(StatusCode::SERVICE_UNAVAILABLE, "down").start().await;
+//! let strict = MockSealServer::builder().require_sdk_version("0.6.0").start().await;
+//! ```
+//!
+//! A running server can be taken down and brought back with `fail_with` and
+//! `recover`, for tests whose SEAL config can't change mid-process.
+//!
+//! Lives in `tests/common` for the integration tests (`mod common;`) and is
+//! also compiled into the crate's unit tests as `mock_seal_server`.
+#![cfg(feature = "mist-protocol")]
+
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use base64::Engine;
+use crypto::ibe;
+use seal_sdk::types::{DecryptionKey, ElGamalPublicKey, FetchKeyResponse};
+use seal_sdk::IBEPublicKey;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sui_sdk_types::{Command, Input, ObjectId, ProgrammableTransaction};
+
+/// A running mock key server
+pub struct MockSealServer {
+    /// Key server object ID to list in `key_servers`
+    pub object_id: ObjectId,
+    /// IBE public key to list in `public_keys` and encrypt to
+    pub public_key: IBEPublicKey,
+    /// Base URL to list in `key_server_urls`
+    pub url: String,
+    behaviour: Arc<Behaviour>,
+}
+
+impl MockSealServer {
+    pub fn builder() -> MockSealServerBuilder {
+        MockSealServerBuilder::default()
+    }
+
+    /// fetch_key requests received so far, answered or not
+    pub fn requests(&self) -> usize {
+        self.behaviour.requests.load(Ordering::SeqCst)
+    }
+
+    /// TCP connections the requests so far arrived on
+    pub fn connections(&self) -> usize {
+        self.behaviour.peers.lock().unwrap().len()
+    }
+
+    /// From now on answer every request with `status` and `body`
+    pub fn fail_with(&self, status: StatusCode, body: &str) {
+        *self.behaviour.failure.lock().unwrap() = Some((status, body.to_string()));
+    }
+
+    /// Serve keys again after `fail_with`
+    pub fn recover(&self) {
+        *self.behaviour.failure.lock().unwrap() = None;
+    }
+}
+
+#[derive(Default)]
+pub struct MockSealServerBuilder {
+    object_id: Option<ObjectId>,
+    failure: Option<(StatusCode, String)>,
+    delay: Duration,
+    required_sdk_version: Option<String>,
+}
+
+impl MockSealServerBuilder {
+    /// Object ID of the server; random by default
+    pub fn object_id(mut self, object_id: ObjectId) -> Self {
+        self.object_id = Some(object_id);
+        self
+    }
+
+    /// Answer every request with `status` and `body` instead of keys. Any
+    /// status can be given, e.g. `200` with a malformed body.
+    pub fn fail_with(mut self, status: StatusCode, body: &str) -> Self {
+        self.failure = Some((status, body.to_string()));
+        self
+    }
+
+    /// Wait this long before answering
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Reject requests whose `Client-Sdk-Version` differs, the way key
+    /// servers reject outdated SDKs
+    pub fn require_sdk_version(mut self, version: &str) -> Self {
+        self.required_sdk_version = Some(version.to_string());
+        self
+    }
+
+    /// Generate the master key and serve on `127.0.0.1:0`
+    pub async fn start(self) -> MockSealServer {
+        let (master_key, public_key) = ibe::generate_key_pair(&mut rand::thread_rng());
+        let object_id = self.object_id.unwrap_or_else(|| ObjectId::new(rand::random()));
+
+        let behaviour = Arc::new(Behaviour {
+            master_key,
+            failure: Mutex::new(self.failure),
+            delay: self.delay,
+            required_sdk_version: self.required_sdk_version,
+            requests: AtomicUsize::new(0),
+            peers: Mutex::new(HashSet::new()),
+        });
+        let app = Router::new()
+            .route("/v1/fetch_key", post(fetch_key))
+            .with_state(behaviour.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+                .await
+                .unwrap()
+        });
+
+        MockSealServer {
+            object_id,
+            public_key,
+            url,
+            behaviour,
+        }
+    }
+}
+
+struct Behaviour {
+    master_key: ibe::MasterKey,
+    failure: Mutex<Option<(StatusCode, String)>>,
+    delay: Duration,
+    required_sdk_version: Option<String>,
+    requests: AtomicUsize,
+    peers: Mutex<HashSet<SocketAddr>>,
+}
+
+async fn fetch_key(
+    State(behaviour): State<Arc<Behaviour>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: String,
+) -> (StatusCode, String) {
+    behaviour.requests.fetch_add(1, Ordering::SeqCst);
+    behaviour.peers.lock().unwrap().insert(peer);
+    tokio::time::sleep(behaviour.delay).await;
+
+    if let Some(required) = &behaviour.required_sdk_version {
+        let sent = headers.get("Client-Sdk-Version").and_then(|v| v.to_str().ok());
+        if sent != Some(required.as_str()) {
+            return (
+                StatusCode::BAD_REQUEST,
+                r#"{"error":"DeprecatedSDKVersion","message":"SDK version is deprecated, please upgrade"}"#.to_string(),
+            );
+        }
+    }
+    if let Some((status, body)) = behaviour.failure.lock().unwrap().clone() {
+        return (status, body);
+    }
+
+    match derive_keys(&behaviour.master_key, &body) {
+        Ok(response) => (StatusCode::OK, serde_json::to_string(&response).unwrap()),
+        Err(e) => (StatusCode::BAD_REQUEST, format!(r#"{{"error":"InvalidPTB","message":"{}"}}"#, e)),
+    }
+}
+
+/// Key for the ID in the request's `seal_approve` call, encrypted to its `enc_key`
+fn derive_keys(master_key: &ibe::MasterKey, body: &str) -> anyhow::Result<FetchKeyResponse> {
+    let request: serde_json::Value = serde_json::from_str(body)?;
+    let ptb_b64 = request["ptb"].as_str().ok_or_else(|| anyhow::anyhow!("missing ptb"))?;
+    let ptb: ProgrammableTransaction =
+        bcs::from_bytes(&base64::engine::general_purpose::STANDARD.decode(ptb_b64)?)?;
+    let enc_key: ElGamalPublicKey = serde_json::from_value(request["enc_key"].clone())?;
+
+    let package_id = match ptb.commands.first() {
+        Some(Command::MoveCall(call)) => call.package,
+        _ => anyhow::bail!("first command is not a seal_approve call"),
+    };
+    let id: Vec<u8> = match ptb.inputs.first() {
+        Some(Input::Pure { value }) => bcs::from_bytes(value)?,
+        _ => anyhow::bail!("first input is not the encryption ID"),
+    };
+
+    let user_secret_key = ibe::extract(master_key, &crypto::create_full_id(package_id.inner(), &id));
+    let encrypted_key = crypto::elgamal::encrypt(&mut rand::thread_rng(), &user_secret_key, &enc_key);
+    Ok(FetchKeyResponse {
+        decryption_keys: vec![DecryptionKey { id, encrypted_key }],
+    })
+}
@@ -0,0 +1,352 @@
+//! Sui JSON-RPC stand-in
+//!
+//! `MockSuiRpc` answers the calls the intent processor makes for a privacy
+//! mixer swap, so a `SuiClient` built against `url` runs the real executor:
+//!
+//! - `sui_getObject` for the intents, the pool, the registry and gas coins
+//! - `suix_getCoins` and `suix_getReferenceGasPrice`
+//! - `sui_dryRunTransactionBlock`: succeeds and credits `execute_swap`'s
+//!   output amount to its output stealth address
+//! - `sui_executeTransactionBlock` / `sui_getTransactionBlock`: record the
+//!   signed transaction and report success under its real digest
+//!
+//! Nothing is executed and object versions never change. Transactions are
+//! decoded, so a malformed one fails the call.
+//!
+//! ```ignore
+//! let rpc = MockSuiRpc::builder()
+//!     .pool(SEAL_CONFIG.pool_id.to_string(), 10_000_000_000)
+//!     .registry(SEAL_CONFIG.registry_id.to_string())
+//!     .gas_coin(backend_address, 1_000_000_000)
+//!     .intent(&intent_id, &encrypted_details, "0x2::sui::SUI", "0x2::sui::SUI", deadline_ms)
+//!     .start()
+//!     .await;
+//! let sui_client = SuiClientBuilder::default().build(&rpc.url).await?;
+//! ```
+#![cfg(feature = "mist-protocol")]
+
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use base64::Engine;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::digests::{ObjectDigest, TransactionDigest};
+use sui_types::transaction::{Argument, CallArg, Command, TransactionData, TransactionDataAPI, TransactionKind};
+
+/// Methods listed by `rpc.discover`
+const METHODS: &[&str] = &[
+    "sui_getObject",
+    "suix_getCoins",
+    "suix_getReferenceGasPrice",
+    "sui_dryRunTransactionBlock",
+    "sui_executeTransactionBlock",
+    "sui_getTransactionBlock",
+];
+
+/// Reference gas price reported to the executor
+pub const GAS_PRICE: u64 = 1000;
+
+/// A running mock RPC
+pub struct MockSuiRpc {
+    /// URL to build the `SuiClient` with
+    pub url: String,
+    chain: Arc<Chain>,
+}
+
+/// A transaction received by `sui_executeTransactionBlock`
+#[derive(Debug, Clone)]
+pub struct Submitted {
+    pub tx_data: TransactionData,
+    /// BCS transaction bytes, as signed
+    pub tx_bytes: Vec<u8>,
+    /// Base64 serialized signatures (`flag || signature || public key`)
+    pub signatures: Vec<String>,
+}
+
+impl MockSuiRpc {
+    pub fn builder() -> MockSuiRpcBuilder {
+        MockSuiRpcBuilder::default()
+    }
+
+    /// Requests received for `method`
+    pub fn calls(&self, method: &str) -> usize {
+        self.chain.calls.lock().unwrap().iter().filter(|m| *m == method).count()
+    }
+
+    /// Transactions submitted for execution, in order
+    pub fn submitted(&self) -> Vec<Submitted> {
+        self.chain.submitted.lock().unwrap().clone()
+    }
+}
+
+#[derive(Default)]
+pub struct MockSuiRpcBuilder {
+    objects: HashMap<ObjectID, Value>,
+    coins: Vec<(SuiAddress, Value)>,
+}
+
+impl MockSuiRpcBuilder {
+    /// The `LiquidityPool` shared object holding `sui_balance`
+    pub fn pool(mut self, id: &str, sui_balance: u64) -> Self {
+        let content = move_object(
+            "0x1::mist_protocol::LiquidityPool",
+            json!({ "sui_balance": sui_balance.to_string() }),
+        );
+        self.objects.insert(object_id(id), object(id, 7, shared(3), Some(content)));
+        self
+    }
+
+    /// The `NullifierRegistry` shared object
+    pub fn registry(mut self, id: &str) -> Self {
+        self.objects.insert(object_id(id), object(id, 7, shared(3), None));
+        self
+    }
+
+    /// A shared `SwapIntent` as the processor reads it
+    pub fn intent(mut self, id: &str, encrypted_details: &[u8], token_in: &str, token_out: &str, deadline_ms: u64) -> Self {
+        let content = move_object(
+            "0x1::mist_protocol::SwapIntent",
+            json!({
+                "encrypted_details": encrypted_details,
+                "token_in": token_in.as_bytes(),
+                "token_out": token_out.as_bytes(),
+                "deadline": deadline_ms.to_string(),
+            }),
+        );
+        self.objects.insert(object_id(id), object(id, 5, shared(5), Some(content)));
+        self
+    }
+
+    /// A SUI coin owned by `owner`, listed by `suix_getCoins`
+    pub fn gas_coin(mut self, owner: SuiAddress, balance: u64) -> Self {
+        let id = ObjectID::random().to_string();
+        let data = object(&id, 3, json!({ "AddressOwner": owner.to_string() }), None);
+        self.coins.push((
+            owner,
+            json!({
+                "coinType": "0x2::sui::SUI",
+                "coinObjectId": id,
+                "version": "3",
+                "digest": data["data"]["digest"],
+                "balance": balance.to_string(),
+                "previousTransaction": TransactionDigest::new([1; 32]).to_string(),
+            }),
+        ));
+        self.objects.insert(object_id(&id), data);
+        self
+    }
+
+    /// Serve on `127.0.0.1:0`
+    pub async fn start(self) -> MockSuiRpc {
+        let chain = Arc::new(Chain {
+            objects: self.objects,
+            coins: self.coins,
+            calls: Mutex::new(Vec::new()),
+            submitted: Mutex::new(Vec::new()),
+            executed: Mutex::new(HashMap::new()),
+        });
+        let app = Router::new().route("/", post(handle)).with_state(chain.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        MockSuiRpc { url, chain }
+    }
+}
+
+struct Chain {
+    objects: HashMap<ObjectID, Value>,
+    coins: Vec<(SuiAddress, Value)>,
+    calls: Mutex<Vec<String>>,
+    submitted: Mutex<Vec<Submitted>>,
+    /// `sui_getTransactionBlock` responses by digest
+    executed: Mutex<HashMap<String, Value>>,
+}
+
+async fn handle(State(chain): State<Arc<Chain>>, Json(request): Json<Value>) -> Json<Value> {
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+    chain.calls.lock().unwrap().push(method.clone());
+
+    let params = &request["params"];
+    let result = match method.as_str() {
+        "rpc.discover" => Ok(json!({
+            "openrpc": "1.2.6",
+            "info": { "title": "Mock Sui JSON-RPC", "version": "1.40.0" },
+            "methods": METHODS.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+        })),
+        "sui_getObject" => Ok(get_object(&chain, &params[0])),
+        "suix_getCoins" => Ok(get_coins(&chain, &params[0])),
+        "suix_getReferenceGasPrice" => Ok(json!(GAS_PRICE.to_string())),
+        "sui_dryRunTransactionBlock" => decode_transaction(&params[0]).map(|(tx_data, _)| dry_run(&tx_data)),
+        "sui_executeTransactionBlock" => execute(&chain, &params[0], &params[1]),
+        "sui_getTransactionBlock" => params[0]
+            .as_str()
+            .and_then(|digest| chain.executed.lock().unwrap().get(digest).cloned())
+            .ok_or_else(|| format!("transaction {} not found", params[0])),
+        _ => Err(format!("method {} not found", method)),
+    };
+
+    Json(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+        Err(message) => json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": { "code": -32602, "message": message },
+        }),
+    })
+}
+
+fn get_object(chain: &Chain, id: &Value) -> Value {
+    let id = object_id(id.as_str().unwrap_or_default());
+    chain
+        .objects
+        .get(&id)
+        .cloned()
+        .unwrap_or_else(|| json!({ "error": { "code": "notExists", "object_id": id.to_string() } }))
+}
+
+fn get_coins(chain: &Chain, owner: &Value) -> Value {
+    let owner: SuiAddress = owner.as_str().unwrap_or_default().parse().unwrap();
+    let coins: Vec<&Value> = chain
+        .coins
+        .iter()
+        .filter(|(coin_owner, _)| *coin_owner == owner)
+        .map(|(_, coin)| coin)
+        .collect();
+    json!({ "data": coins, "nextCursor": null, "hasNextPage": false })
+}
+
+fn decode_transaction(tx_bytes: &Value) -> Result<(TransactionData, Vec<u8>), String> {
+    let tx_bytes = base64::engine::general_purpose::STANDARD
+        .decode(tx_bytes.as_str().unwrap_or_default())
+        .map_err(|e| format!("invalid transaction bytes: {}", e))?;
+    let tx_data = bcs::from_bytes(&tx_bytes).map_err(|e| format!("invalid transaction data: {}", e))?;
+    Ok((tx_data, tx_bytes))
+}
+
+fn execute(chain: &Chain, tx_bytes: &Value, signatures: &Value) -> Result<Value, String> {
+    let (tx_data, tx_bytes) = decode_transaction(tx_bytes)?;
+    let signatures = signatures
+        .as_array()
+        .ok_or("missing signatures")?
+        .iter()
+        .filter_map(|s| s.as_str().map(str::to_string))
+        .collect();
+
+    let dry_run = dry_run(&tx_data);
+    let response = json!({
+        "digest": tx_data.digest().to_string(),
+        "effects": dry_run["effects"],
+        "events": [],
+        "objectChanges": [],
+        "balanceChanges": dry_run["balanceChanges"],
+        "confirmedLocalExecution": true,
+    });
+    chain.executed.lock().unwrap().insert(tx_data.digest().to_string(), response.clone());
+    chain.submitted.lock().unwrap().push(Submitted { tx_data, tx_bytes, signatures });
+    Ok(response)
+}
+
+/// Successful effects; `execute_swap` credits its output amount to its
+/// output stealth address
+fn dry_run(tx_data: &TransactionData) -> Value {
+    let sender = tx_data.sender();
+    let gas = tx_data.gas()[0];
+    let balance_changes: Vec<Value> = execute_swap_output(tx_data)
+        .map(|(stealth, amount)| {
+            json!({
+                "owner": { "AddressOwner": stealth.to_string() },
+                "coinType": "0x2::sui::SUI",
+                "amount": amount.to_string(),
+            })
+        })
+        .into_iter()
+        .collect();
+
+    json!({
+        "effects": {
+            "messageVersion": "v1",
+            "status": { "status": "success" },
+            "executedEpoch": "1",
+            "gasUsed": {
+                "computationCost": "1000000",
+                "storageCost": "0",
+                "storageRebate": "0",
+                "nonRefundableStorageFee": "0",
+            },
+            "transactionDigest": tx_data.digest().to_string(),
+            "gasObject": {
+                "owner": { "AddressOwner": sender.to_string() },
+                "reference": { "objectId": gas.0.to_string(), "version": gas.1.value(), "digest": gas.2.to_string() },
+            },
+        },
+        "events": [],
+        "objectChanges": [],
+        "balanceChanges": balance_changes,
+        "input": {
+            "messageVersion": "v1",
+            "transaction": { "kind": "ProgrammableTransaction", "inputs": [], "transactions": [] },
+            "sender": sender.to_string(),
+            "gasData": {
+                "payment": [],
+                "owner": sender.to_string(),
+                "price": tx_data.gas_price().to_string(),
+                "budget": tx_data.gas_budget().to_string(),
+            },
+        },
+    })
+}
+
+/// `(output_stealth, output_amount)` of the transaction's `execute_swap` call
+fn execute_swap_output(tx_data: &TransactionData) -> Option<(SuiAddress, u64)> {
+    let TransactionKind::ProgrammableTransaction(ptb) = tx_data.kind() else {
+        return None;
+    };
+    let call = ptb.commands.iter().find_map(|command| match command {
+        Command::MoveCall(call) if call.function.as_str() == "execute_swap" => Some(call),
+        _ => None,
+    })?;
+    // (registry, pool, intent, nullifier, output_amount, output_stealth, ..)
+    let pure = |index: usize| match call.arguments.get(index)? {
+        Argument::Input(input) => match ptb.inputs.get(*input as usize)? {
+            CallArg::Pure(bytes) => Some(bytes.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let amount: u64 = bcs::from_bytes(&pure(4)?).ok()?;
+    let stealth: SuiAddress = bcs::from_bytes(&pure(5)?).ok()?;
+    Some((stealth, amount))
+}
+
+fn object_id(id: &str) -> ObjectID {
+    ObjectID::from_hex_literal(id).unwrap()
+}
+
+fn shared(initial_shared_version: u64) -> Value {
+    json!({ "Shared": { "initial_shared_version": initial_shared_version } })
+}
+
+fn move_object(type_: &str, fields: Value) -> Value {
+    json!({ "dataType": "moveObject", "type": type_, "hasPublicTransfer": false, "fields": fields })
+}
+
+/// `SuiObjectResponse` for an object at `version`
+fn object(id: &str, version: u64, owner: Value, content: Option<Value>) -> Value {
+    let id = object_id(id);
+    let mut data = json!({
+        "objectId": id.to_string(),
+        "version": version.to_string(),
+        "digest": ObjectDigest::new(id.into_bytes()).to_string(),
+        "owner": owner,
+    });
+    if let Some(content) = content {
+        data["type"] = content["type"].clone();
+        data["content"] = content;
+    }
+    json!({ "data": data })
+}
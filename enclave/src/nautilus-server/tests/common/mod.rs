@@ -0,0 +1,40 @@
+//! Stand-ins shared by the integration tests
+//!
+//! Test files that need them declare `mod common;`:
+//!
+//! - `mock_seal_server`: SEAL key servers that hand out real key shares
+//! - `mock_sui_rpc`: the Sui JSON-RPC calls the intent processor makes
+//!
+//! `SEAL_CONFIG` and the other `MIST_*` settings are `lazy_static`s, read
+//! once per process. A test file points them at its mocks (`use_seal_config`,
+//! `std::env::set_var`) before the first intent is encrypted or processed,
+//! and keeps to a single `#[tokio::test]` so nothing reads them earlier.
+//! Each file under `tests/` runs as its own process, so files can use
+//! different settings.
+#![cfg(feature = "mist-protocol")]
+#![allow(dead_code)]
+
+pub mod mock_seal_server;
+pub mod mock_sui_rpc;
+
+use fastcrypto::serde_helpers::ToFromByteArray;
+use mock_seal_server::MockSealServer;
+use nautilus_server::app::seal_types::{EMBEDDED_SEAL_CONFIG, SEAL_CONFIG_PATH_ENV};
+use std::path::PathBuf;
+
+/// Write the embedded SEAL config with `servers` as its key servers and
+/// point `SEAL_CONFIG_PATH` at it. Contract and DEX IDs are unchanged.
+pub fn use_seal_config(name: &str, servers: &[&MockSealServer]) -> PathBuf {
+    let mut config: serde_yaml::Value = serde_yaml::from_str(EMBEDDED_SEAL_CONFIG).unwrap();
+    config["key_servers"] = servers.iter().map(|s| s.object_id.to_string()).collect();
+    config["public_keys"] = servers
+        .iter()
+        .map(|s| format!("0x{}", hex::encode(s.public_key.to_byte_array())))
+        .collect();
+    config["key_server_urls"] = servers.iter().map(|s| s.url.clone()).collect();
+
+    let path = std::env::temp_dir().join(format!("seal_config_{}_{}.yaml", name, std::process::id()));
+    std::fs::write(&path, serde_yaml::to_string(&config).unwrap()).unwrap();
+    std::env::set_var(SEAL_CONFIG_PATH_ENV, &path);
+    path
+}
@@ -0,0 +1,87 @@
+//! End-to-end latency per intent, through `process_single_intent`
+//!
+//! Every intent is freshly SEAL-encrypted, so each one is decrypted with key
+//! shares from the mock key servers (2-of-3, the third too slow to wait for),
+//! then validated (nullifier, wallet signature) and dry-run against the mock
+//! Sui RPC. Fails when p95 exceeds `MIST_BENCH_P95_BUDGET_MS` (default 500).
+//!
+//! `cargo test --features bench --test intent_latency -- --nocapture`
+#![cfg(all(feature = "mist-protocol", feature = "bench"))]
+
+mod common;
+
+use common::mock_seal_server::MockSealServer;
+use common::mock_sui_rpc::MockSuiRpc;
+use common::use_seal_config;
+use fastcrypto::ed25519::Ed25519KeyPair;
+use fastcrypto::traits::KeyPair;
+use nautilus_server::app::canary::{canary_details, encrypt_for_tee};
+use nautilus_server::app::intent_processor::process_single_intent;
+use nautilus_server::app::latency::{p95_budget_from_env, LatencyStats};
+use nautilus_server::app::SEAL_CONFIG;
+use nautilus_server::AppState;
+use std::time::{Duration, Instant};
+use sui_sdk::SuiClientBuilder;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+const INTENTS: usize = 100;
+const SUI: &str = "0x2::sui::SUI";
+
+#[tokio::test]
+async fn bench_intent_latency() {
+    let budget = p95_budget_from_env(Duration::from_millis(500)).unwrap();
+
+    std::env::set_var("MIST_SIMULATE_ONLY", "true");
+    let servers = [
+        MockSealServer::builder().start().await,
+        MockSealServer::builder().start().await,
+        MockSealServer::builder().delay(Duration::from_secs(30)).start().await,
+    ];
+    use_seal_config("intent_latency", &[&servers[0], &servers[1], &servers[2]]);
+
+    let state = AppState::new(Ed25519KeyPair::generate(&mut rand::thread_rng()), String::new());
+    let backend: SuiAddress = state.signer.address().to_string().parse().unwrap();
+    let deadline_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+        + 3_600_000;
+
+    // Each intent signed by its own wallet and encrypted on its own
+    let mut rpc = MockSuiRpc::builder()
+        .pool(&SEAL_CONFIG.pool_id.to_string(), 10_000_000_000)
+        .registry(&SEAL_CONFIG.registry_id.to_string())
+        .gas_coin(backend, 1_000_000_000);
+    let mut intent_ids = Vec::with_capacity(INTENTS);
+    for _ in 0..INTENTS {
+        let wallet = Ed25519KeyPair::generate(&mut rand::thread_rng());
+        let (details, _) = canary_details(&wallet);
+        let encrypted_details = encrypt_for_tee(&serde_json::to_vec(&details).unwrap()).unwrap();
+        let intent_id = ObjectID::random().to_string();
+        rpc = rpc.intent(&intent_id, &encrypted_details, SUI, SUI, deadline_ms);
+        intent_ids.push(intent_id);
+    }
+    let rpc = rpc.start().await;
+    let sui_client = SuiClientBuilder::default().build(&rpc.url).await.unwrap();
+
+    let mut samples = Vec::with_capacity(INTENTS);
+    for intent_id in &intent_ids {
+        let started = Instant::now();
+        let result = process_single_intent(intent_id, &sui_client, &state).await.unwrap();
+        samples.push(started.elapsed());
+        assert!(result.success && result.simulated, "{:?}", result);
+    }
+    assert_eq!(rpc.calls("sui_dryRunTransactionBlock"), INTENTS);
+    assert!(rpc.submitted().is_empty());
+    // Every intent needed fresh key shares
+    assert_eq!(servers[0].requests() + servers[1].requests(), 2 * INTENTS);
+
+    let stats = LatencyStats::from_samples(samples).unwrap();
+    println!("intent latency: {} (budget p95 {}ms)", stats, budget.as_millis());
+    assert!(
+        stats.p95 <= budget,
+        "p95 {}ms exceeds budget {}ms",
+        stats.p95.as_millis(),
+        budget.as_millis()
+    );
+}
@@ -0,0 +1,141 @@
+//! Tests of the mock SEAL key server in `tests/common`
+#![cfg(feature = "mist-protocol")]
+
+mod common;
+
+use axum::http::StatusCode;
+use base64::Engine;
+use common::mock_seal_server::MockSealServer;
+use crypto::{EncryptionInput, IBEPublicKeys};
+use seal_sdk::types::{ElGamalPublicKey, FetchKeyResponse};
+use seal_sdk::IBEPublicKey;
+use seal_sdk::{genkey, seal_decrypt_all_objects, seal_encrypt, EncryptedObject};
+use std::collections::HashMap;
+use std::time::Duration;
+use sui_sdk_types::{Argument, Command, Identifier, Input, MoveCall, ObjectId, ProgrammableTransaction};
+
+/// Package the test policy lives in
+fn package_id() -> ObjectId {
+    ObjectId::new([0x42; 32])
+}
+
+/// `seal_approve_tee(id)`, as the processor builds it
+fn seal_approve_ptb(id: &[u8]) -> ProgrammableTransaction {
+    ProgrammableTransaction {
+        inputs: vec![Input::Pure {
+            value: bcs::to_bytes(id).unwrap(),
+        }],
+        commands: vec![Command::MoveCall(MoveCall {
+            package: package_id(),
+            module: Identifier::new("seal_policy").unwrap(),
+            function: Identifier::new("seal_approve_tee").unwrap(),
+            type_arguments: vec![],
+            arguments: vec![Argument::Input(0)],
+        })],
+    }
+}
+
+fn encrypt(servers: &[&MockSealServer], threshold: u8, data: &[u8]) -> EncryptedObject {
+    let (encrypted, _key) = seal_encrypt(
+        package_id(),
+        b"mock-intent".to_vec(),
+        servers.iter().map(|s| s.object_id).collect(),
+        &IBEPublicKeys::BonehFranklinBLS12381(servers.iter().map(|s| s.public_key).collect()),
+        threshold,
+        EncryptionInput::Aes256Gcm {
+            data: data.to_vec(),
+            aad: None,
+        },
+    )
+    .unwrap();
+    encrypted
+}
+
+async fn post_fetch_key(
+    server: &MockSealServer,
+    encrypted: &EncryptedObject,
+    enc_key: &ElGamalPublicKey,
+    sdk_version: &str,
+) -> reqwest::Response {
+    let ptb = seal_approve_ptb(&encrypted.id);
+    let body = serde_json::json!({
+        "ptb": base64::engine::general_purpose::STANDARD.encode(bcs::to_bytes(&ptb).unwrap()),
+        "enc_key": enc_key,
+    });
+    reqwest::Client::new()
+        .post(format!("{}/v1/fetch_key", server.url))
+        .header("Client-Sdk-Version", sdk_version)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_decrypts_against_mock_servers() {
+    let first = MockSealServer::builder().object_id(ObjectId::new([0x01; 32])).start().await;
+    assert_eq!(first.object_id, ObjectId::new([0x01; 32]));
+    let second = MockSealServer::builder().delay(Duration::from_millis(50)).start().await;
+    let encrypted = encrypt(&[&first, &second], 2, b"swap details");
+
+    let (enc_secret, enc_key, _verification_key) = genkey(&mut rand::thread_rng());
+    let mut responses = Vec::new();
+    for server in [&first, &second] {
+        let response = post_fetch_key(server, &encrypted, &enc_key, "0.5.11").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let keys: FetchKeyResponse = response.json().await.unwrap();
+        responses.push((server.object_id, keys));
+    }
+
+    let server_pk_map: HashMap<ObjectId, IBEPublicKey> = [&first, &second]
+        .iter()
+        .map(|s| (s.object_id, s.public_key))
+        .collect();
+    let decrypted = seal_decrypt_all_objects(&enc_secret, &responses, &[encrypted], &server_pk_map).unwrap();
+    assert_eq!(decrypted, vec![b"swap details".to_vec()]);
+    assert_eq!((first.requests(), second.requests()), (1, 1));
+}
+
+#[tokio::test]
+async fn test_failure_delay_and_version_options() {
+    let broken = MockSealServer::builder()
+        .fail_with(StatusCode::SERVICE_UNAVAILABLE, "overloaded")
+        .start()
+        .await;
+    let slow = MockSealServer::builder().delay(Duration::from_millis(200)).start().await;
+    let strict = MockSealServer::builder().require_sdk_version("0.6.0").start().await;
+    let encrypted = encrypt(&[&broken, &slow, &strict], 1, b"x");
+    let (_enc_secret, enc_key, _verification_key) = genkey(&mut rand::thread_rng());
+
+    let response = post_fetch_key(&broken, &encrypted, &enc_key, "0.5.11").await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.text().await.unwrap(), "overloaded");
+
+    let started = std::time::Instant::now();
+    assert_eq!(post_fetch_key(&slow, &encrypted, &enc_key, "0.5.11").await.status(), StatusCode::OK);
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    let response = post_fetch_key(&strict, &encrypted, &enc_key, "0.5.11").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(response.text().await.unwrap().contains("DeprecatedSDKVersion"));
+    assert_eq!(post_fetch_key(&strict, &encrypted, &enc_key, "0.6.0").await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_taken_down_while_running() {
+    let server = MockSealServer::builder().start().await;
+    let encrypted = encrypt(&[&server], 1, b"x");
+    let (_enc_secret, enc_key, _verification_key) = genkey(&mut rand::thread_rng());
+
+    assert_eq!(post_fetch_key(&server, &encrypted, &enc_key, "0.5.11").await.status(), StatusCode::OK);
+
+    server.fail_with(StatusCode::SERVICE_UNAVAILABLE, "down");
+    let response = post_fetch_key(&server, &encrypted, &enc_key, "0.5.11").await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    server.recover();
+    assert_eq!(post_fetch_key(&server, &encrypted, &enc_key, "0.5.11").await.status(), StatusCode::OK);
+    assert_eq!(server.requests(), 3);
+    // A client per request: one connection each
+    assert_eq!(server.connections(), 3);
+}
@@ -0,0 +1,89 @@
+//! A SEAL-encrypted intent through `process_single_intent`: decryption with
+//! key shares from two mock key servers, validation, and execution against
+//! the mock Sui RPC, ending in a signed receipt
+#![cfg(feature = "mist-protocol")]
+
+mod common;
+
+use common::mock_seal_server::MockSealServer;
+use common::mock_sui_rpc::MockSuiRpc;
+use common::use_seal_config;
+use fastcrypto::ed25519::{Ed25519KeyPair, Ed25519Signature};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use fastcrypto::traits::{KeyPair, ToFromBytes, VerifyingKey};
+use nautilus_server::app::canary::{canary_details, encrypt_for_tee};
+use nautilus_server::app::intent_processor::process_single_intent;
+use nautilus_server::app::receipt::verify_swap_receipt;
+use nautilus_server::app::SEAL_CONFIG;
+use nautilus_server::AppState;
+use sui_sdk::SuiClientBuilder;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+const SUI: &str = "0x2::sui::SUI";
+
+#[tokio::test]
+async fn test_encrypted_intent_is_executed() {
+    let servers = [MockSealServer::builder().start().await, MockSealServer::builder().start().await];
+    use_seal_config("process_intent", &[&servers[0], &servers[1]]);
+
+    // What the frontend submits: signed details, SEAL-encrypted to the TEE
+    let wallet = Ed25519KeyPair::generate(&mut rand::thread_rng());
+    let (details, _) = canary_details(&wallet);
+    let encrypted_details = encrypt_for_tee(&serde_json::to_vec(&details).unwrap()).unwrap();
+
+    let eph_kp = Ed25519KeyPair::generate(&mut rand::thread_rng());
+    let state = AppState::new(eph_kp.copy(), String::new());
+    let backend: SuiAddress = state.signer.address().to_string().parse().unwrap();
+
+    let intent_id = ObjectID::random().to_string();
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let rpc = MockSuiRpc::builder()
+        .pool(&SEAL_CONFIG.pool_id.to_string(), 10_000_000_000)
+        .registry(&SEAL_CONFIG.registry_id.to_string())
+        .gas_coin(backend, 1_000_000_000)
+        .intent(&intent_id, &encrypted_details, SUI, SUI, now_ms + 3_600_000)
+        .start()
+        .await;
+    let sui_client = SuiClientBuilder::default().build(&rpc.url).await.unwrap();
+
+    let result = process_single_intent(&intent_id, &sui_client, &state).await.unwrap();
+    assert!(result.success && result.executed && !result.simulated, "{:?}", result);
+    assert_eq!(result.output_stealth, details.output_stealth);
+    assert_eq!(result.output_amount, 1);
+    assert_eq!(result.venue, "mixer");
+
+    // Both key servers were asked and their shares used
+    let mut used = result.seal_servers.clone();
+    used.sort();
+    let mut expected: Vec<String> = servers.iter().map(|s| s.object_id.to_string()).collect();
+    expected.sort();
+    assert_eq!(used, expected);
+    assert!(servers.iter().all(|s| s.requests() == 1));
+
+    // The transaction was submitted once, signed by the backend key
+    assert_eq!(rpc.calls("sui_dryRunTransactionBlock"), 0);
+    let submitted = rpc.submitted();
+    assert_eq!(submitted.len(), 1);
+    assert_eq!(result.tx_digest, Some(submitted[0].tx_data.digest().to_string()));
+    let signature = Base64::decode(&submitted[0].signatures[0]).unwrap();
+    assert_eq!((signature[0], &signature[65..]), (0x00, eph_kp.public().as_bytes()));
+    let mut intent_message = vec![0, 0, 0];
+    intent_message.extend_from_slice(&submitted[0].tx_bytes);
+    eph_kp
+        .public()
+        .verify(
+            Blake2b256::digest(&intent_message).as_ref(),
+            &Ed25519Signature::from_bytes(&signature[1..65]).unwrap(),
+        )
+        .unwrap();
+
+    // The receipt names the executed transaction and verifies against the enclave key
+    let receipt = result.receipt.as_ref().unwrap();
+    verify_swap_receipt(eph_kp.public(), receipt).unwrap();
+    assert_eq!(receipt.response.data.intent_id, intent_id);
+    assert_eq!(receipt.response.data.tx_digest, result.tx_digest);
+}
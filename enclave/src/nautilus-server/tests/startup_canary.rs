@@ -0,0 +1,34 @@
+//! The startup canary against mock key servers: it passes while both serve
+//! key shares and fails once one of them is down (2-of-2 threshold)
+#![cfg(feature = "mist-protocol")]
+
+mod common;
+
+use axum::http::StatusCode;
+use common::mock_seal_server::MockSealServer;
+use common::use_seal_config;
+use fastcrypto::ed25519::Ed25519KeyPair;
+use fastcrypto::traits::KeyPair;
+use nautilus_server::app::canary::startup_canary;
+use nautilus_server::AppState;
+
+#[tokio::test]
+async fn test_startup_canary() {
+    let servers = [MockSealServer::builder().start().await, MockSealServer::builder().start().await];
+    use_seal_config("startup_canary", &[&servers[0], &servers[1]]);
+    let state = AppState::new(Ed25519KeyPair::generate(&mut rand::thread_rng()), String::new());
+
+    startup_canary(&state).await.unwrap();
+    assert!(servers.iter().all(|s| s.requests() == 1));
+
+    // One key server down: the threshold can't be met
+    servers[1].fail_with(StatusCode::SERVICE_UNAVAILABLE, "down for maintenance");
+    let err = startup_canary(&state).await.unwrap_err();
+    assert_eq!(err.to_string(), "canary decryption failed");
+    assert_eq!(servers[1].requests(), 2);
+
+    // Back up: the next canary passes again
+    servers[1].recover();
+    startup_canary(&state).await.unwrap();
+    assert!(servers.iter().all(|s| s.requests() == 3));
+}
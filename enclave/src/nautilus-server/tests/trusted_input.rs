@@ -0,0 +1,55 @@
+//! A plaintext intent through `process_single_intent` with `trusted-input`,
+//! in simulate-only mode: validated and dry-run without any key server, and
+//! never submitted
+//!
+//! `cargo test --features trusted-input --test trusted_input`
+#![cfg(all(feature = "mist-protocol", feature = "trusted-input"))]
+
+mod common;
+
+use common::mock_sui_rpc::MockSuiRpc;
+use fastcrypto::ed25519::Ed25519KeyPair;
+use fastcrypto::traits::KeyPair;
+use nautilus_server::app::canary::canary_details;
+use nautilus_server::app::intent_processor::process_single_intent;
+use nautilus_server::app::SEAL_CONFIG;
+use nautilus_server::AppState;
+use sui_sdk::SuiClientBuilder;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+const SUI: &str = "0x2::sui::SUI";
+
+#[tokio::test]
+async fn test_plaintext_intent_is_simulated() {
+    // The embedded SEAL config: its key servers must never be contacted
+    std::env::set_var("MIST_SIMULATE_ONLY", "true");
+    let state = AppState::new(Ed25519KeyPair::generate(&mut rand::thread_rng()), String::new());
+    let backend: SuiAddress = state.signer.address().to_string().parse().unwrap();
+
+    let wallet = Ed25519KeyPair::generate(&mut rand::thread_rng());
+    let (details, _) = canary_details(&wallet);
+    let intent_id = ObjectID::random().to_string();
+    let deadline_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+        + 3_600_000;
+    let rpc = MockSuiRpc::builder()
+        .pool(&SEAL_CONFIG.pool_id.to_string(), 10_000_000_000)
+        .registry(&SEAL_CONFIG.registry_id.to_string())
+        .gas_coin(backend, 1_000_000_000)
+        .intent(&intent_id, &serde_json::to_vec(&details).unwrap(), SUI, SUI, deadline_ms)
+        .start()
+        .await;
+    let sui_client = SuiClientBuilder::default().build(&rpc.url).await.unwrap();
+
+    let result = process_single_intent(&intent_id, &sui_client, &state).await.unwrap();
+    assert!(result.success && result.simulated && !result.executed, "{:?}", result);
+    assert!(result.seal_servers.is_empty());
+    assert!(result.receipt.is_none());
+    assert_eq!(result.output_stealth, details.output_stealth);
+    assert_eq!(result.output_amount, 1);
+
+    assert_eq!(rpc.calls("sui_dryRunTransactionBlock"), 1);
+    assert_eq!(rpc.calls("sui_executeTransactionBlock"), 0);
+}
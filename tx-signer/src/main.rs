@@ -2,8 +2,13 @@
 //! This service runs on port 4000 and signs transactions using the Sui CLI
 
 use axum::{Router, routing::post, Json, http::StatusCode};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use blake2::{digest::consts::U32, Blake2b, Digest};
 use serde::{Deserialize, Serialize};
 
+/// Intent prefix of a Sui transaction: scope TransactionData, version 0, app Sui
+const TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+
 #[derive(Deserialize)]
 struct SignRequest {
     /// Backend address (or alias) to sign with
@@ -12,10 +17,23 @@ struct SignRequest {
     tx_data_b64: String,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, PartialEq, Serialize)]
 struct SignResponse {
     /// Base64 encoded signature from sui keytool
     signature: String,
+    /// Base64 Blake2b-256 digest that was signed: the intent message
+    /// (intent prefix + transaction bytes), as reported by sui keytool
+    digest: String,
+}
+
+/// Fields of `sui keytool --json sign` output used in the response
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeytoolSignOutput {
+    /// Transaction bytes keytool signed, base64
+    raw_tx_data: String,
+    digest: String,
+    sui_signature: String,
 }
 
 #[derive(Serialize)]
@@ -70,8 +88,9 @@ async fn sign_transaction(
 
     // Call sui keytool sign
     let output = std::process::Command::new("sui")
-        .args(&[
+        .args([
             "keytool",
+            "--json",
             "sign",
             "--address", &req.address,
             "--data", &req.tx_data_b64,
@@ -98,32 +117,129 @@ async fn sign_transaction(
         ));
     }
 
-    // Parse the signature from the output
+    // Parse the signature and digest from the output
     let output_str = String::from_utf8_lossy(&output.stdout);
+    let response = parse_sign_output(&output_str, &req.tx_data_b64).map_err(|e| {
+        eprintln!("   ❌ {}", e);
+        eprintln!("   Output was:\n{}", output_str);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    let signature = &response.signature;
+
+    println!("   ✅ Transaction signed successfully!");
+    println!("   📝 Signature (first 40 chars): {}...", &signature[..40.min(signature.len())]);
+    println!("   🔏 Digest: {}", response.digest);
+
+    Ok(Json(response))
+}
+
+/// Base64 Blake2b-256 of the intent message (intent prefix + transaction
+/// bytes) for the base64 transaction `tx_data_b64`
+fn transaction_digest(tx_data_b64: &str) -> Result<String, String> {
+    let tx_bytes = BASE64
+        .decode(tx_data_b64)
+        .map_err(|e| format!("Invalid base64 transaction data: {}", e))?;
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(TRANSACTION_INTENT);
+    hasher.update(tx_bytes);
+    Ok(BASE64.encode(hasher.finalize()))
+}
+
+/// Parse `sui keytool --json sign` output, checking that keytool signed
+/// exactly `tx_data_b64` and reported its digest
+fn parse_sign_output(stdout: &str, tx_data_b64: &str) -> Result<SignResponse, String> {
+    let output: KeytoolSignOutput = serde_json::from_str(stdout.trim())
+        .map_err(|e| format!("Failed to parse sui keytool output: {}", e))?;
+
+    if output.raw_tx_data != tx_data_b64 {
+        return Err(format!(
+            "sui keytool signed different transaction data: {}",
+            output.raw_tx_data
+        ));
+    }
+
+    let expected_digest = transaction_digest(tx_data_b64)?;
+    if output.digest != expected_digest {
+        return Err(format!(
+            "sui keytool reported digest {} for a transaction with digest {}",
+            output.digest, expected_digest
+        ));
+    }
 
-    // The output contains a line like: │ suiSignature │ <base64_signature> │
-    let signature = output_str
-        .lines()
-        .find(|line| line.contains("suiSignature"))
-        .and_then(|line| {
-            // Split by │ and get the signature part (3rd column)
-            line.split('│')
-                .nth(2)
-                .map(|s| s.trim().to_string())
+    Ok(SignResponse {
+        signature: output.sui_signature,
+        digest: output.digest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TX_DATA: &str = "AAACAAgA4fUFAAAAAAAg1k0hpNvVz1W1VWpgOVg5AgVSd1bZ6Mc0Fv3Xw7oXXhICAgABAQAAAQEDAAAAAAEBAA==";
+
+    /// Blake2b-256 over `[0, 0, 0] || tx bytes`, computed independently of
+    /// `transaction_digest`
+    fn intent_digest(raw_tx_data: &str) -> String {
+        let mut intent_message = vec![0u8, 0, 0];
+        intent_message.extend(BASE64.decode(raw_tx_data).unwrap());
+        BASE64.encode(Blake2b::<U32>::digest(intent_message))
+    }
+
+    fn keytool_output(raw_tx_data: &str, digest: &str) -> String {
+        serde_json::json!({
+            "suiAddress": "0x9a2a7d2bdb5cba0e5e42ec6e6ee3f8f5a4fdd2c43d4ad5e1d0c2f7a9b8c1d2e3",
+            "rawTxData": raw_tx_data,
+            "intent": { "scope": 0, "version": 0, "app_id": 0 },
+            "rawIntentMsg": format!("AAAA{}", raw_tx_data),
+            "digest": digest,
+            "suiSignature": "AIbPZyJ9N6nz9cS9QbQ4b2l8kE7Jf0r8q3m0Dz8l2oVq",
         })
-        .ok_or_else(|| {
-            eprintln!("   ❌ Failed to parse signature from output");
-            eprintln!("   Output was:\n{}", output_str);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: "Failed to parse signature from sui keytool output".to_string(),
-                }),
+        .to_string()
+    }
+
+    #[test]
+    fn test_digest_returned_for_signed_transaction() {
+        let digest = intent_digest(TX_DATA);
+        // Blake2b-256 of the intent message, as sui keytool reports it
+        assert_eq!(digest, "SExdAotHJlN1Unfteh/R9GdAcuxreC8TM3xhLd3V+yM=");
+
+        let response = parse_sign_output(&keytool_output(TX_DATA, &digest), TX_DATA).unwrap();
+        assert_eq!(
+            response,
+            SignResponse {
+                signature: "AIbPZyJ9N6nz9cS9QbQ4b2l8kE7Jf0r8q3m0Dz8l2oVq".to_string(),
+                digest: digest.clone(),
+            }
+        );
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["digest"], digest);
+    }
+
+    #[test]
+    fn test_rejects_digest_of_other_message() {
+        let wrong = intent_digest("AAAB");
+        let err = parse_sign_output(&keytool_output(TX_DATA, &wrong), TX_DATA).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "sui keytool reported digest {} for a transaction with digest {}",
+                wrong,
+                intent_digest(TX_DATA)
             )
-        })?;
+        );
+    }
 
-    println!("   ✅ Transaction signed successfully!");
-    println!("   📝 Signature (first 40 chars): {}...", &signature[..40.min(signature.len())]);
+    #[test]
+    fn test_rejects_signature_over_other_transaction() {
+        let err = parse_sign_output(&keytool_output("AAAB", &intent_digest("AAAB")), TX_DATA).unwrap_err();
+        assert_eq!(err, "sui keytool signed different transaction data: AAAB");
 
-    Ok(Json(SignResponse { signature }))
+        let err = parse_sign_output("│ suiSignature │ abc │", TX_DATA).unwrap_err();
+        assert!(err.starts_with("Failed to parse sui keytool output"), "{}", err);
+    }
 }